@@ -18,9 +18,48 @@ pub struct Ids {
     pub ids: Vec<String>,
 }
 
+#[derive(Serialize, Default)]
+pub struct PlaylistDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collaborative: Option<bool>,
+}
+
+impl From<PlaylistDetailsUpdate> for PlaylistDetails {
+    fn from(update: PlaylistDetailsUpdate) -> Self {
+        Self {
+            name: update.title,
+            description: update.description,
+            public: update.public,
+            collaborative: update.collaborative,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PlaylistTracksReorder {
+    pub range_start: usize,
+    pub range_length: usize,
+    pub insert_before: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlaylistSnapshot {
+    pub snapshot_id: String,
+}
+
 pub enum SearchType {
     Artist,
     Album,
+    Track,
+    Playlist,
 }
 
 impl SearchType {
@@ -28,6 +67,19 @@ impl SearchType {
         match self {
             Self::Artist => "artist",
             Self::Album => "album",
+            Self::Track => "track",
+            Self::Playlist => "playlist",
+        }
+    }
+}
+
+impl From<SearchCategory> for SearchType {
+    fn from(category: SearchCategory) -> Self {
+        match category {
+            SearchCategory::Albums => Self::Album,
+            SearchCategory::Artists => Self::Artist,
+            SearchCategory::Songs => Self::Track,
+            SearchCategory::Playlists => Self::Playlist,
         }
     }
 }
@@ -157,6 +209,11 @@ trait WithImages {
 pub struct Playlist {
     pub id: String,
     pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    #[serde(default)]
+    pub collaborative: bool,
+    pub snapshot_id: Option<String>,
     pub images: Vec<Image>,
     pub tracks: Page<PlaylistTrack>,
     pub owner: PlaylistOwner,
@@ -253,6 +310,54 @@ impl WithImages for Artist {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct Show {
+    pub id: String,
+    pub name: String,
+    pub publisher: String,
+    pub description: Option<String>,
+    pub images: Vec<Image>,
+    // Unavailable episodes are null
+    pub episodes: Option<Page<Option<Episode>>>,
+}
+
+impl WithImages for Show {
+    fn images(&self) -> &[Image] {
+        &self.images[..]
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SavedShow {
+    pub show: Show,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Episode {
+    pub id: String,
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub release_date: Option<String>,
+    pub duration_ms: i64,
+    pub images: Vec<Image>,
+    pub resume_point: Option<ResumePoint>,
+    // Only there when the episode is fetched on its own
+    pub show: Option<Show>,
+}
+
+impl WithImages for Episode {
+    fn images(&self) -> &[Image] {
+        &self.images[..]
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ResumePoint {
+    pub fully_played: bool,
+    pub resume_position_ms: u32,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct User {
     pub id: String,
@@ -264,10 +369,41 @@ pub struct TopTracks {
     pub tracks: Vec<TrackItem>,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct Recommendations {
+    pub tracks: Vec<TrackItem>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlayHistoryItem {
+    pub track: TrackItem,
+    pub played_at: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Cursors {
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+// Some endpoints (such as the listening history) are paged with cursors rather than offsets
+#[derive(Deserialize, Debug, Clone)]
+pub struct CursorPage<T> {
+    items: Vec<T>,
+    next: Option<String>,
+    cursors: Option<Cursors>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FollowedArtistsPage {
+    pub artists: CursorPage<Artist>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct AlbumTrackItem {
     pub id: String,
     pub track_number: Option<usize>,
+    pub disc_number: Option<usize>,
     pub uri: String,
     pub name: String,
     pub duration_ms: i64,
@@ -304,6 +440,8 @@ impl FailibleTrackItem {
 pub struct RawSearchResults {
     pub albums: Option<Page<Album>>,
     pub artists: Option<Page<Artist>>,
+    pub tracks: Option<Page<TrackItem>>,
+    pub playlists: Option<Page<Playlist>>,
 }
 
 impl From<Artist> for ArtistSummary {
@@ -328,12 +466,55 @@ impl From<SavedTrack> for TrackItem {
     }
 }
 
+impl From<PlayHistoryItem> for TrackItem {
+    fn from(item: PlayHistoryItem) -> Self {
+        item.track
+    }
+}
+
+impl From<CursorPage<PlayHistoryItem>> for RecentlyPlayed {
+    fn from(page: CursorPage<PlayHistoryItem>) -> Self {
+        let CursorPage {
+            items,
+            next,
+            cursors,
+        } = page;
+        // The last page still has cursors, only the absence of a next page tells it apart
+        let before = next.and(cursors).and_then(|c| c.before);
+        Self {
+            songs: Page::new(items).into(),
+            before,
+        }
+    }
+}
+
+impl From<FollowedArtistsPage> for FollowedArtists {
+    fn from(page: FollowedArtistsPage) -> Self {
+        let CursorPage {
+            items,
+            next,
+            cursors,
+        } = page.artists;
+        let after = next.and(cursors).and_then(|c| c.after);
+        Self {
+            artists: items.into_iter().map(|a| a.into()).collect(),
+            after,
+        }
+    }
+}
+
 impl From<TopTracks> for Vec<SongDescription> {
     fn from(top_tracks: TopTracks) -> Self {
         Page::new(top_tracks.tracks).into()
     }
 }
 
+impl From<Recommendations> for Vec<SongDescription> {
+    fn from(recommendations: Recommendations) -> Self {
+        Page::new(recommendations.tracks).into()
+    }
+}
+
 impl<T> From<Page<T>> for Vec<SongDescription>
 where
     T: TryInto<TrackItem>,
@@ -363,51 +544,74 @@ where
             batch_size: page.limit(),
             total: page.total(),
         };
-        let songs = page
+        let mut songs = vec![];
+        let mut skipped = vec![];
+        for (i, t) in page.into_iter().enumerate() {
+            let track: Result<TrackItem, _> = t.try_into();
+            match track {
+                Ok(track) => songs.push(track.into()),
+                Err(_) => skipped.push(batch.offset + i),
+            }
+        }
+        SongBatch {
+            songs,
+            batch,
+            skipped,
+        }
+    }
+}
+
+impl From<TrackItem> for SongDescription {
+    fn from(track_item: TrackItem) -> Self {
+        let TrackItem { track, album } = track_item;
+        let AlbumTrackItem {
+            artists,
+            id,
+            uri,
+            name,
+            duration_ms,
+            track_number,
+            disc_number,
+        } = track;
+        let artists = artists
             .into_iter()
-            .filter_map(|t| {
-                let TrackItem { track, album } = t.try_into().ok()?;
-                let AlbumTrackItem {
-                    artists,
-                    id,
-                    uri,
-                    name,
-                    duration_ms,
-                    track_number,
-                } = track;
-                let artists = artists
-                    .into_iter()
-                    .map(|a| ArtistRef {
-                        id: a.id,
-                        name: a.name,
-                    })
-                    .collect::<Vec<ArtistRef>>();
-
-                let art = album.best_image_for_width(200).map(|i| &i.url).cloned();
-                let Album {
-                    id: album_id,
-                    name: album_name,
-                    ..
-                } = album;
-
-                let album_ref = AlbumRef {
-                    id: album_id,
-                    name: album_name,
-                };
-
-                Some(SongDescription {
-                    id,
-                    track_number: track_number.map(|u| u as u32),
-                    uri,
-                    title: name,
-                    artists,
-                    album: album_ref,
-                    duration: duration_ms as u32,
-                    art,
-                })
+            .map(|a| ArtistRef {
+                id: a.id,
+                name: a.name,
             })
-            .collect();
-        SongBatch { songs, batch }
+            .collect::<Vec<ArtistRef>>();
+
+        let art = album.best_image_for_width(200).map(|i| &i.url).cloned();
+        let Album {
+            id: album_id,
+            name: album_name,
+            artists: album_artists,
+            ..
+        } = album;
+
+        let album_ref = AlbumRef {
+            id: album_id,
+            name: album_name,
+        };
+
+        SongDescription {
+            id,
+            track_number: track_number.map(|u| u as u32),
+            disc_number: disc_number.map(|u| u as u32),
+            uri,
+            title: name,
+            artists,
+            album: album_ref,
+            album_artists: album_artists
+                .into_iter()
+                .map(|a| ArtistRef {
+                    id: a.id,
+                    name: a.name,
+                })
+                .collect(),
+            duration: duration_ms as u32,
+            art,
+        }
     }
 }
 
@@ -459,6 +663,90 @@ impl From<Album> for AlbumDescription {
     }
 }
 
+impl From<(Page<Option<Episode>>, &Show)> for EpisodeBatch {
+    fn from(page_and_show: (Page<Option<Episode>>, &Show)) -> Self {
+        let (page, show) = page_and_show;
+        let batch = Batch {
+            offset: page.offset(),
+            batch_size: page.limit(),
+            total: page.total(),
+        };
+        let episodes = page
+            .into_iter()
+            .flatten()
+            .map(|episode| (episode, show).into())
+            .collect();
+        EpisodeBatch { episodes, batch }
+    }
+}
+
+impl From<(Episode, &Show)> for EpisodeDescription {
+    fn from(episode_and_show: (Episode, &Show)) -> Self {
+        let (episode, show) = episode_and_show;
+        let art = episode
+            .best_image_for_width(200)
+            .or_else(|| show.best_image_for_width(200))
+            .map(|i| i.url.clone());
+        let Episode {
+            id,
+            uri,
+            name,
+            description,
+            release_date,
+            duration_ms,
+            resume_point,
+            ..
+        } = episode;
+        Self {
+            id,
+            uri,
+            title: name,
+            description: description.filter(|d| !d.is_empty()),
+            release_date,
+            duration: duration_ms as u32,
+            art,
+            show: AlbumRef {
+                id: show.id.clone(),
+                name: show.name.clone(),
+            },
+            resume_position: resume_point
+                .filter(|r| !r.fully_played && r.resume_position_ms > 0)
+                .map(|r| r.resume_position_ms),
+        }
+    }
+}
+
+impl From<Show> for ShowDescription {
+    fn from(mut show: Show) -> Self {
+        let art = show.best_image_for_width(200).map(|i| i.url.clone());
+        let episodes = std::mem::replace(&mut show.episodes, None)
+            .map(|page| (page, &show).into())
+            .unwrap_or_else(EpisodeBatch::empty);
+        let Show {
+            id,
+            name,
+            publisher,
+            description,
+            ..
+        } = show;
+        Self {
+            id,
+            title: name,
+            publisher,
+            description: description.filter(|d| !d.is_empty()),
+            art,
+            episodes,
+            is_saved: false,
+        }
+    }
+}
+
+impl From<SavedShow> for ShowDescription {
+    fn from(saved: SavedShow) -> Self {
+        saved.show.into()
+    }
+}
+
 impl From<AlbumInfo> for AlbumReleaseDetails {
     fn from(
         AlbumInfo {
@@ -487,6 +775,10 @@ impl From<Playlist> for PlaylistDescription {
         let Playlist {
             id,
             name,
+            description,
+            public,
+            collaborative,
+            snapshot_id,
             tracks,
             owner,
             ..
@@ -499,6 +791,10 @@ impl From<Playlist> for PlaylistDescription {
         PlaylistDescription {
             id,
             title: name,
+            description: description.filter(|d| !d.is_empty()),
+            public,
+            collaborative,
+            snapshot_id,
             art,
             songs: song_batch,
             owner: UserRef {
@@ -537,4 +833,65 @@ mod tests {
         let track_item: Option<TrackItem> = deserialized.try_into().ok();
         assert!(track_item.is_some());
     }
+
+    #[test]
+    fn test_playlist_page_skipped() {
+        let page = r#"{"items":[{"is_local":true,"track":{"name":""}},{"is_local":false,"track":{"album":{"artists":[],"id":"","images":[],"name":""},"artists":[],"duration_ms":1,"id":"track","name":"","uri":""}},{"is_local":false,"track":null}],"offset":100,"limit":100,"total":103}"#;
+        let deserialized: Page<PlaylistTrack> = serde_json::from_str(page).unwrap();
+        let batch: SongBatch = deserialized.into();
+        assert_eq!(batch.songs.len(), 1);
+        assert_eq!(batch.skipped, vec![100, 102]);
+    }
+
+    #[test]
+    fn test_search_results_tracks_and_playlists() {
+        let results = r#"{"tracks":{"items":[{"album":{"artists":[{"id":"","name":""}],"id":"","images":[],"name":""},"artists":[{"id":"","name":""}],"duration_ms":1,"id":"track","name":"","uri":""}],"offset":0,"limit":5,"total":1},"playlists":{"items":[{"id":"playlist","name":"","images":[],"tracks":{"href":"","total":12},"owner":{"id":"","display_name":""}}],"offset":0,"limit":5,"total":1}}"#;
+        let deserialized: RawSearchResults = serde_json::from_str(results).unwrap();
+        let songs: Vec<SongDescription> = deserialized.tracks.unwrap().into();
+        assert_eq!(songs.len(), 1);
+        assert_eq!(songs[0].id, "track");
+        let playlists = deserialized.playlists.unwrap();
+        assert_eq!(playlists.total(), 1);
+        assert!(deserialized.albums.is_none());
+    }
+
+    #[test]
+    fn test_show_episodes() {
+        let show = r#"{"id":"show","name":"Show","publisher":"Publisher","description":"","images":[{"height":64,"url":"show_art","width":64}],"episodes":{"items":[{"id":"episode0","uri":"spotify:episode:episode0","name":"","description":"","release_date":"2022-01-01","duration_ms":1,"images":[],"resume_point":{"fully_played":false,"resume_position_ms":42}},null,{"id":"episode1","uri":"spotify:episode:episode1","name":"","duration_ms":1,"images":[],"resume_point":{"fully_played":true,"resume_position_ms":1}}],"offset":0,"limit":3,"total":3}}"#;
+        let deserialized: Show = serde_json::from_str(show).unwrap();
+        let show: ShowDescription = deserialized.into();
+        assert_eq!(show.description, None);
+        assert_eq!(show.episodes.batch.total, 3);
+
+        let episodes = &show.episodes.episodes;
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].show.id, "show");
+        assert_eq!(episodes[0].art.as_deref(), Some("show_art"));
+        assert_eq!(episodes[0].resume_position, Some(42));
+        assert_eq!(episodes[1].resume_position, None);
+    }
+
+    #[test]
+    fn test_episode_with_show() {
+        let episode = r#"{"id":"episode0","uri":"spotify:episode:episode0","name":"Episode","duration_ms":1,"images":[],"resume_point":{"fully_played":false,"resume_position_ms":42},"show":{"id":"show","name":"Show","publisher":"Publisher","images":[{"height":64,"url":"show_art","width":64}]}}"#;
+        let mut deserialized: Episode = serde_json::from_str(episode).unwrap();
+        let show = deserialized.show.take().unwrap();
+        let episode: EpisodeDescription = (deserialized, &show).into();
+        assert_eq!(episode.title, "Episode");
+        assert_eq!(episode.show.name, "Show");
+        assert_eq!(episode.art.as_deref(), Some("show_art"));
+        assert_eq!(episode.resume_position, Some(42));
+    }
+
+    #[test]
+    fn test_playlist_details_skip_unset() {
+        let details: PlaylistDetails = PlaylistDetailsUpdate {
+            title: Some("Renamed".to_string()),
+            collaborative: Some(false),
+            ..Default::default()
+        }
+        .into();
+        let serialized = serde_json::to_string(&details).unwrap();
+        assert_eq!(serialized, r#"{"name":"Renamed","collaborative":false}"#);
+    }
 }
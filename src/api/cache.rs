@@ -85,7 +85,10 @@ pub struct CacheManager {
 impl CacheManager {
     pub fn for_dir(dir: &str) -> Option<Self> {
         let root: PathBuf = glib::user_cache_dir().into();
-        let root = root.join(dir);
+        Self::for_path(root.join(dir))
+    }
+
+    pub fn for_path(root: PathBuf) -> Option<Self> {
         let mask = 0o744;
 
         glib::mkdir_with_parents(&root, mask);
@@ -12,11 +12,16 @@ use crate::app::models::*;
 
 pub type SpotifyResult<T> = Result<T, SpotifyApiError>;
 
+// Most ids the API accepts when checking saved tracks
+const CONTAINS_TRACKS_LIMIT: usize = 50;
+
 pub trait SpotifyApiClient {
     fn get_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistDescription>>;
 
     fn get_album(&self, id: &str) -> BoxFuture<SpotifyResult<AlbumFullDescription>>;
 
+    fn get_track(&self, id: &str) -> BoxFuture<SpotifyResult<SongDescription>>;
+
     fn get_album_tracks(
         &self,
         id: &str,
@@ -49,6 +54,41 @@ pub trait SpotifyApiClient {
 
     fn remove_saved_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<()>>;
 
+    // Whether each track is in the user's library, in the order of the ids
+    fn contains_saved_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<Vec<bool>>>;
+
+    // Pages go forward, `after` being the cursor returned with the previous page
+    fn get_followed_artists(
+        &self,
+        after: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<FollowedArtists>>;
+
+    fn follow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistSummary>>;
+
+    fn unfollow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<()>>;
+
+    fn get_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>>;
+
+    fn get_show_episodes(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<EpisodeBatch>>;
+
+    fn get_episode(&self, id: &str) -> BoxFuture<SpotifyResult<EpisodeDescription>>;
+
+    fn get_saved_shows(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<ShowDescription>>>;
+
+    fn save_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>>;
+
+    fn remove_saved_show(&self, id: &str) -> BoxFuture<SpotifyResult<()>>;
+
     fn get_saved_playlists(
         &self,
         offset: usize,
@@ -59,9 +99,34 @@ pub trait SpotifyApiClient {
 
     fn remove_from_playlist(&self, id: &str, uris: Vec<String>) -> BoxFuture<SpotifyResult<()>>;
 
+    // Resolves to the new snapshot id of the playlist
+    fn reorder_playlist_tracks(
+        &self,
+        id: &str,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+        snapshot_id: Option<String>,
+    ) -> BoxFuture<SpotifyResult<String>>;
+
+    fn create_playlist(
+        &self,
+        user_id: &str,
+        name: &str,
+    ) -> BoxFuture<SpotifyResult<PlaylistDescription>>;
+
+    fn update_playlist_details(
+        &self,
+        id: &str,
+        update: PlaylistDetailsUpdate,
+    ) -> BoxFuture<SpotifyResult<()>>;
+
+    fn unfollow_playlist(&self, id: &str) -> BoxFuture<SpotifyResult<()>>;
+
     fn search(
         &self,
         query: &str,
+        categories: Vec<SearchCategory>,
         offset: usize,
         limit: usize,
     ) -> BoxFuture<SpotifyResult<SearchResults>>;
@@ -73,6 +138,29 @@ pub trait SpotifyApiClient {
         limit: usize,
     ) -> BoxFuture<SpotifyResult<Vec<AlbumDescription>>>;
 
+    // Pages go back in time, `before` being the cursor returned with the previous page
+    fn get_recently_played(
+        &self,
+        before: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<RecentlyPlayed>>;
+
+    fn get_top_items(
+        &self,
+        category: TopItemsCategory,
+        time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<TopItems>>;
+
+    // Spotify accepts at most 5 seeds, tracks and artists combined
+    fn get_recommendations(
+        &self,
+        seed_tracks: Vec<String>,
+        seed_artists: Vec<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<SongDescription>>>;
+
     fn get_user(&self, id: &str) -> BoxFuture<SpotifyResult<UserDescription>>;
 
     fn get_user_playlists(
@@ -89,14 +177,24 @@ enum SpotCacheKey<'a> {
     SavedAlbums(usize, usize),
     SavedTracks(usize, usize),
     SavedPlaylists(usize, usize),
+    SavedShows(usize, usize),
+    TopTracks(TimeRange, usize, usize),
+    TopArtists(TimeRange, usize, usize),
+    FollowedArtists(Option<&'a str>, usize),
     Album(&'a str),
     AlbumLiked(&'a str),
     AlbumTracks(&'a str, usize, usize),
+    Track(&'a str),
     Playlist(&'a str),
     PlaylistTracks(&'a str, usize, usize),
     ArtistAlbums(&'a str, usize, usize),
     Artist(&'a str),
     ArtistTopTracks(&'a str),
+    ArtistFollowed(&'a str),
+    Show(&'a str),
+    ShowSaved(&'a str),
+    ShowEpisodes(&'a str, usize, usize),
+    Episode(&'a str),
     User(&'a str),
     UserPlaylists(&'a str, usize, usize),
 }
@@ -109,11 +207,22 @@ impl<'a> SpotCacheKey<'a> {
             Self::SavedPlaylists(offset, limit) => {
                 format!("me_playlists_{}_{}.json", offset, limit)
             }
+            Self::SavedShows(offset, limit) => format!("me_shows_{}_{}.json", offset, limit),
+            Self::TopTracks(time_range, offset, limit) => {
+                format!("me_top_tracks_{:?}_{}_{}.json", time_range, offset, limit)
+            }
+            Self::TopArtists(time_range, offset, limit) => {
+                format!("me_top_artists_{:?}_{}_{}.json", time_range, offset, limit)
+            }
+            Self::FollowedArtists(after, limit) => {
+                format!("me_following_{}_{}.json", after.unwrap_or("start"), limit)
+            }
             Self::Album(id) => format!("album_{}.json", id),
             Self::AlbumTracks(id, offset, limit) => {
                 format!("album_item_{}_{}_{}.json", id, offset, limit)
             }
             Self::AlbumLiked(id) => format!("album_liked_{}.json", id),
+            Self::Track(id) => format!("track_{}.json", id),
             Self::Playlist(id) => format!("playlist_{}.json", id),
             Self::PlaylistTracks(id, offset, limit) => {
                 format!("playlist_item_{}_{}_{}.json", id, offset, limit)
@@ -123,6 +232,13 @@ impl<'a> SpotCacheKey<'a> {
             }
             Self::Artist(id) => format!("artist_{}.json", id),
             Self::ArtistTopTracks(id) => format!("artist_top_tracks_{}.json", id),
+            Self::ArtistFollowed(id) => format!("artist_followed_{}.json", id),
+            Self::Show(id) => format!("show_{}.json", id),
+            Self::ShowSaved(id) => format!("show_saved_{}.json", id),
+            Self::ShowEpisodes(id, offset, limit) => {
+                format!("show_item_{}_{}_{}.json", id, offset, limit)
+            }
+            Self::Episode(id) => format!("episode_{}.json", id),
             Self::User(id) => format!("user_{}.json", id),
             Self::UserPlaylists(id, offset, limit) => {
                 format!("user_playlists_{}_{}_{}.json", id, offset, limit)
@@ -134,12 +250,17 @@ impl<'a> SpotCacheKey<'a> {
 lazy_static! {
     pub static ref ME_TRACKS_CACHE: Regex = Regex::new(r"^me_tracks_\w+_\w+\.json$").unwrap();
     pub static ref ME_ALBUMS_CACHE: Regex = Regex::new(r"^me_albums_\w+_\w+\.json$").unwrap();
+    pub static ref ME_PLAYLISTS_CACHE: Regex =
+        Regex::new(r"^(me|user)_playlists_\w+_\w+\.json$").unwrap();
+    pub static ref ME_FOLLOWING_CACHE: Regex = Regex::new(r"^me_following_\w+_\w+\.json$").unwrap();
+    pub static ref ME_SHOWS_CACHE: Regex = Regex::new(r"^me_shows_\w+_\w+\.json$").unwrap();
     pub static ref USER_CACHE: Regex =
-        Regex::new(r"^me_(albums|playlists|tracks)_\w+_\w+\.json$").unwrap();
+        Regex::new(r"^me_(albums|playlists|tracks|following|shows|top_\w+)_\w+_\w+\.json$")
+            .unwrap();
 }
 
 fn playlist_cache_key(id: &str) -> Regex {
-    Regex::new(&format!(r"^playlist(_{}|item_{}_\w+_\w+)\.json$", id, id)).unwrap()
+    Regex::new(&format!(r"^playlist_({}|item_{}_\w+_\w+)\.json$", id, id)).unwrap()
 }
 
 pub struct CachedSpotifyClient {
@@ -149,10 +270,14 @@ pub struct CachedSpotifyClient {
 
 impl CachedSpotifyClient {
     pub fn new() -> CachedSpotifyClient {
-        CachedSpotifyClient {
-            client: SpotifyClient::new(),
-            cache: CacheManager::for_dir("spot/net").unwrap(),
-        }
+        Self::with_client(
+            SpotifyClient::new(),
+            CacheManager::for_dir("spot/net").unwrap(),
+        )
+    }
+
+    pub(crate) fn with_client(client: SpotifyClient, cache: CacheManager) -> CachedSpotifyClient {
+        CachedSpotifyClient { client, cache }
     }
 
     fn default_cache_policy(&self) -> CachePolicy {
@@ -328,6 +453,90 @@ impl SpotifyApiClient for CachedSpotifyClient {
         })
     }
 
+    fn reorder_playlist_tracks(
+        &self,
+        id: &str,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+        snapshot_id: Option<String>,
+    ) -> BoxFuture<SpotifyResult<String>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            self.cache
+                .set_expired_pattern(&playlist_cache_key(&id))
+                .await
+                .unwrap_or(());
+
+            let snapshot = self
+                .client
+                .reorder_playlist_tracks(&id, range_start, range_length, insert_before, snapshot_id)
+                .send()
+                .await?
+                .deserialize()
+                .ok_or(SpotifyApiError::NoContent)?;
+            Ok(snapshot.snapshot_id)
+        })
+    }
+
+    fn create_playlist(
+        &self,
+        user_id: &str,
+        name: &str,
+    ) -> BoxFuture<SpotifyResult<PlaylistDescription>> {
+        let user_id = user_id.to_owned();
+        let name = name.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_PLAYLISTS_CACHE).await;
+
+            let playlist = self
+                .client
+                .create_playlist(&user_id, name)
+                .send()
+                .await?
+                .deserialize()
+                .ok_or(SpotifyApiError::NoContent)?;
+            Ok(playlist.into())
+        })
+    }
+
+    fn update_playlist_details(
+        &self,
+        id: &str,
+        update: PlaylistDetailsUpdate,
+    ) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_PLAYLISTS_CACHE).await;
+            let _ = self
+                .cache
+                .set_expired_pattern(&playlist_cache_key(&id))
+                .await;
+
+            self.client
+                .update_playlist_details(&id, update.into())
+                .send_no_response()
+                .await
+        })
+    }
+
+    fn unfollow_playlist(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_PLAYLISTS_CACHE).await;
+            let _ = self
+                .cache
+                .set_expired_pattern(&playlist_cache_key(&id))
+                .await;
+
+            self.client.unfollow_playlist(&id).send_no_response().await
+        })
+    }
+
     fn get_album(&self, id: &str) -> BoxFuture<SpotifyResult<AlbumFullDescription>> {
         let id = id.to_owned();
 
@@ -355,6 +564,20 @@ impl SpotifyApiClient for CachedSpotifyClient {
         })
     }
 
+    fn get_track(&self, id: &str) -> BoxFuture<SpotifyResult<SongDescription>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let track = self
+                .cache_get_or_write(SpotCacheKey::Track(&id), None, |etag| {
+                    self.client.get_track(&id).etag(etag).send()
+                })
+                .await?;
+
+            Ok(track.into())
+        })
+    }
+
     fn save_album(&self, id: &str) -> BoxFuture<SpotifyResult<AlbumDescription>> {
         let id = id.to_owned();
 
@@ -392,6 +615,23 @@ impl SpotifyApiClient for CachedSpotifyClient {
         })
     }
 
+    fn contains_saved_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<Vec<bool>>> {
+        Box::pin(async move {
+            let mut saved = Vec::with_capacity(ids.len());
+            for chunk in ids.chunks(CONTAINS_TRACKS_LIMIT) {
+                let mut chunk_saved = self
+                    .client
+                    .contains_saved_tracks(chunk)
+                    .send()
+                    .await?
+                    .deserialize()
+                    .ok_or(SpotifyApiError::NoContent)?;
+                saved.append(&mut chunk_saved);
+            }
+            Ok(saved)
+        })
+    }
+
     fn get_album_tracks(
         &self,
         id: &str,
@@ -494,6 +734,278 @@ impl SpotifyApiClient for CachedSpotifyClient {
         })
     }
 
+    fn get_recently_played(
+        &self,
+        before: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<RecentlyPlayed>> {
+        Box::pin(async move {
+            // Not cached, as it changes with every song played
+            let page = self
+                .client
+                .get_recently_played(before.as_deref(), limit)
+                .send()
+                .await?
+                .deserialize()
+                .ok_or(SpotifyApiError::NoContent)?;
+
+            Ok(page.into())
+        })
+    }
+
+    fn get_top_items(
+        &self,
+        category: TopItemsCategory,
+        time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<TopItems>> {
+        Box::pin(async move {
+            match category {
+                TopItemsCategory::Songs => {
+                    let page = self
+                        .cache_get_or_write(
+                            SpotCacheKey::TopTracks(time_range, offset, limit),
+                            None,
+                            |etag| {
+                                self.client
+                                    .get_top_tracks(time_range, offset, limit)
+                                    .etag(etag)
+                                    .send()
+                            },
+                        )
+                        .await?;
+                    Ok(TopItems::Songs(page.into()))
+                }
+                TopItemsCategory::Artists => {
+                    let page = self
+                        .cache_get_or_write(
+                            SpotCacheKey::TopArtists(time_range, offset, limit),
+                            None,
+                            |etag| {
+                                self.client
+                                    .get_top_artists(time_range, offset, limit)
+                                    .etag(etag)
+                                    .send()
+                            },
+                        )
+                        .await?;
+                    let artists = page
+                        .into_iter()
+                        .map(|a| a.into())
+                        .collect::<Vec<ArtistSummary>>();
+                    Ok(TopItems::Artists(artists))
+                }
+            }
+        })
+    }
+
+    fn get_recommendations(
+        &self,
+        seed_tracks: Vec<String>,
+        seed_artists: Vec<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<SongDescription>>> {
+        Box::pin(async move {
+            // Not cached, the point is to get different songs every time
+            let recommendations = self
+                .client
+                .get_recommendations(&seed_tracks, &seed_artists, limit)
+                .send()
+                .await?
+                .deserialize()
+                .ok_or(SpotifyApiError::NoContent)?;
+
+            Ok(recommendations.into())
+        })
+    }
+
+    fn get_followed_artists(
+        &self,
+        after: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<FollowedArtists>> {
+        Box::pin(async move {
+            let page = self
+                .cache_get_or_write(
+                    SpotCacheKey::FollowedArtists(after.as_deref(), limit),
+                    None,
+                    |etag| {
+                        self.client
+                            .get_followed_artists(after.as_deref(), limit)
+                            .etag(etag)
+                            .send()
+                    },
+                )
+                .await?;
+
+            Ok(page.into())
+        })
+    }
+
+    fn follow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistSummary>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_FOLLOWING_CACHE).await;
+            self.client.follow_artist(&id).send_no_response().await?;
+            let artist = self
+                .cache_get_or_write(SpotCacheKey::Artist(&id), None, |etag| {
+                    self.client.get_artist(&id).etag(etag).send()
+                })
+                .await?;
+            Ok(artist.into())
+        })
+    }
+
+    fn unfollow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_FOLLOWING_CACHE).await;
+            self.client.unfollow_artist(&id).send_no_response().await
+        })
+    }
+
+    fn get_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            // Revalidated, so that the resume points of episodes stay fresh
+            let show = self.cache_get_or_write(
+                SpotCacheKey::Show(&id),
+                Some(if self.client.has_token() {
+                    CachePolicy::Revalidate
+                } else {
+                    CachePolicy::IgnoreExpiry
+                }),
+                |etag| self.client.get_show(&id).etag(etag).send(),
+            );
+
+            let saved = self.cache_get_or_write(
+                SpotCacheKey::ShowSaved(&id),
+                Some(if self.client.has_token() {
+                    CachePolicy::Revalidate
+                } else {
+                    CachePolicy::IgnoreExpiry
+                }),
+                |etag| self.client.is_show_saved(&id).etag(etag).send(),
+            );
+
+            let (show, saved) = join!(show, saved);
+
+            let mut show: ShowDescription = show?.into();
+            // Not worth failing the whole show over
+            show.is_saved = saved
+                .ok()
+                .and_then(|saved| saved.first().copied())
+                .unwrap_or(false);
+
+            Ok(show)
+        })
+    }
+
+    fn get_show_episodes(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<EpisodeBatch>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let show = self.cache_get_or_write(
+                SpotCacheKey::Show(&id),
+                Some(CachePolicy::IgnoreExpiry),
+                |etag| self.client.get_show(&id).etag(etag).send(),
+            );
+
+            let episodes = self.cache_get_or_write(
+                SpotCacheKey::ShowEpisodes(&id, offset, limit),
+                Some(if self.client.has_token() {
+                    CachePolicy::Revalidate
+                } else {
+                    CachePolicy::IgnoreExpiry
+                }),
+                |etag| {
+                    self.client
+                        .get_show_episodes(&id, offset, limit)
+                        .etag(etag)
+                        .send()
+                },
+            );
+
+            let (show, episodes) = join!(show, episodes);
+            Ok((episodes?, &show?).into())
+        })
+    }
+
+    fn get_episode(&self, id: &str) -> BoxFuture<SpotifyResult<EpisodeDescription>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            // Revalidated, so that the resume point stays fresh
+            let mut episode = self
+                .cache_get_or_write(
+                    SpotCacheKey::Episode(&id),
+                    Some(if self.client.has_token() {
+                        CachePolicy::Revalidate
+                    } else {
+                        CachePolicy::IgnoreExpiry
+                    }),
+                    |etag| self.client.get_episode(&id).etag(etag).send(),
+                )
+                .await?;
+
+            let show = episode.show.take().ok_or(SpotifyApiError::NoContent)?;
+            Ok((episode, &show).into())
+        })
+    }
+
+    fn get_saved_shows(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<ShowDescription>>> {
+        Box::pin(async move {
+            let page = self
+                .cache_get_or_write(SpotCacheKey::SavedShows(offset, limit), None, |etag| {
+                    self.client.get_saved_shows(offset, limit).etag(etag).send()
+                })
+                .await?;
+
+            let shows = page
+                .into_iter()
+                .map(|saved| {
+                    let mut show: ShowDescription = saved.into();
+                    show.is_saved = true;
+                    show
+                })
+                .collect::<Vec<ShowDescription>>();
+
+            Ok(shows)
+        })
+    }
+
+    fn save_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_SHOWS_CACHE).await;
+            self.client.save_show(&id).send_no_response().await?;
+            self.get_show(&id[..]).await
+        })
+    }
+
+    fn remove_saved_show(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+
+        Box::pin(async move {
+            let _ = self.cache.set_expired_pattern(&ME_SHOWS_CACHE).await;
+            self.client.remove_saved_show(&id).send_no_response().await
+        })
+    }
+
     fn get_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistDescription>> {
         let id = id.to_owned();
 
@@ -502,6 +1014,16 @@ impl SpotifyApiClient for CachedSpotifyClient {
                 self.client.get_artist(&id).etag(etag).send()
             });
 
+            let followed = self.cache_get_or_write(
+                SpotCacheKey::ArtistFollowed(&id),
+                Some(if self.client.has_token() {
+                    CachePolicy::Revalidate
+                } else {
+                    CachePolicy::IgnoreExpiry
+                }),
+                |etag| self.client.is_artist_followed(&id).etag(etag).send(),
+            );
+
             let albums = self.get_artist_albums(&id, 0, 20);
 
             let top_tracks =
@@ -509,7 +1031,8 @@ impl SpotifyApiClient for CachedSpotifyClient {
                     self.client.get_artist_top_tracks(&id).etag(etag).send()
                 });
 
-            let (artist, albums, top_tracks) = join!(artist, albums, top_tracks);
+            let (artist, albums, top_tracks, followed) =
+                join!(artist, albums, top_tracks, followed);
 
             let artist = artist?;
             let result = ArtistDescription {
@@ -517,6 +1040,11 @@ impl SpotifyApiClient for CachedSpotifyClient {
                 name: artist.name,
                 albums: albums?,
                 top_tracks: top_tracks?.into(),
+                // Not worth failing the whole artist over
+                is_followed: followed
+                    .ok()
+                    .and_then(|followed| followed.first().copied())
+                    .unwrap_or(false),
             };
             Ok(result)
         })
@@ -525,15 +1053,17 @@ impl SpotifyApiClient for CachedSpotifyClient {
     fn search(
         &self,
         query: &str,
+        categories: Vec<SearchCategory>,
         offset: usize,
         limit: usize,
     ) -> BoxFuture<SpotifyResult<SearchResults>> {
         let query = query.to_owned();
+        let types = categories.into_iter().map(|c| c.into()).collect();
 
         Box::pin(async move {
             let results = self
                 .client
-                .search(query, offset, limit)
+                .search(query, types, offset, limit)
                 .send()
                 .await?
                 .deserialize()
@@ -553,7 +1083,21 @@ impl SpotifyApiClient for CachedSpotifyClient {
                 .map(|saved| saved.into())
                 .collect::<Vec<ArtistSummary>>();
 
-            Ok(SearchResults { albums, artists })
+            let songs = results.tracks.unwrap_or_default().into();
+
+            let playlists = results
+                .playlists
+                .unwrap_or_default()
+                .into_iter()
+                .map(|saved| saved.into())
+                .collect::<Vec<PlaylistDescription>>();
+
+            Ok(SearchResults {
+                albums,
+                artists,
+                songs,
+                playlists,
+            })
         })
     }
 
@@ -614,8 +1158,34 @@ impl SpotifyApiClient for CachedSpotifyClient {
 #[cfg(test)]
 pub mod tests {
 
+    use super::*;
     use crate::api::api_models::*;
 
+    #[test]
+    fn test_playlist_cache_key() {
+        let key = playlist_cache_key("abc");
+        assert!(key.is_match("playlist_abc.json"));
+        assert!(key.is_match("playlist_item_abc_0_100.json"));
+        assert!(!key.is_match("playlist_abcd.json"));
+        assert!(!key.is_match("playlist_item_abcd_0_100.json"));
+    }
+
+    #[test]
+    fn test_user_cache_key() {
+        let top_tracks = SpotCacheKey::TopTracks(TimeRange::Short, 0, 50).into_raw();
+        assert_eq!(top_tracks, "me_top_tracks_Short_0_50.json");
+        assert!(USER_CACHE.is_match(&top_tracks));
+        assert!(USER_CACHE.is_match("me_tracks_0_50.json"));
+        let following = SpotCacheKey::FollowedArtists(None, 20).into_raw();
+        assert!(USER_CACHE.is_match(&following));
+        assert!(ME_FOLLOWING_CACHE.is_match(&following));
+        let shows = SpotCacheKey::SavedShows(0, 20).into_raw();
+        assert!(USER_CACHE.is_match(&shows));
+        assert!(ME_SHOWS_CACHE.is_match(&shows));
+        assert!(!USER_CACHE.is_match(&SpotCacheKey::ShowEpisodes("abc", 0, 20).into_raw()));
+        assert!(!ME_TRACKS_CACHE.is_match(&top_tracks));
+    }
+
     #[test]
     fn test_search_query() {
         let query = SearchQuery {
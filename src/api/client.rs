@@ -1,7 +1,7 @@
 use form_urlencoded::Serializer;
 use isahc::config::Configurable;
 use isahc::http::{method::Method, request::Builder, StatusCode, Uri};
-use isahc::{AsyncReadResponseExt, HttpClient, Request};
+use isahc::{AsyncBody, AsyncReadResponseExt, HttpClient, Request, Response};
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::{de::Deserialize, Serialize};
 use serde_json::from_str;
@@ -9,12 +9,26 @@ use std::convert::Into;
 use std::marker::PhantomData;
 use std::str::FromStr;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::sync::Semaphore;
 
 pub use super::api_models::*;
 use super::cache::CacheError;
+use crate::app::models::TimeRange;
 
-const SPOTIFY_HOST: &str = "api.spotify.com";
+const SPOTIFY_API_URL: &str = "https://api.spotify.com";
+
+// Scrolling through large playlists can fire a lot of requests at once
+const MAX_CONCURRENT_REQUESTS: usize = 6;
+
+// Requests that got rate limited or hit a server error are retried this many times
+const MAX_RETRIES: u32 = 3;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+// Past that, we would rather tell the user than leave them waiting
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 // https://url.spec.whatwg.org/#path-percent-encode-set
 const PATH_ENCODE_SET: &AsciiSet = &CONTROLS
@@ -32,6 +46,14 @@ fn make_query_params<'a>() -> Serializer<'a, String> {
     Serializer::new(String::new())
 }
 
+fn time_range_param(time_range: TimeRange) -> &'static str {
+    match time_range {
+        TimeRange::Short => "short_term",
+        TimeRange::Medium => "medium_term",
+        TimeRange::Long => "long_term",
+    }
+}
+
 pub(crate) struct SpotifyRequest<'a, Body, Response> {
     client: &'a SpotifyClient,
     request: Builder,
@@ -41,7 +63,7 @@ pub(crate) struct SpotifyRequest<'a, Body, Response> {
 
 impl<'a, B, R> SpotifyRequest<'a, B, R>
 where
-    B: Into<isahc::AsyncBody>,
+    B: Into<isahc::AsyncBody> + Clone,
 {
     fn method(mut self, method: Method) -> Self {
         self.request = self.request.method(method);
@@ -53,9 +75,10 @@ where
             None => path,
             Some(query) => format!("{}?{}", path, query),
         };
+        let base_url = &self.client.base_url;
         let uri = Uri::builder()
-            .scheme("https")
-            .authority(SPOTIFY_HOST)
+            .scheme(base_url.scheme_str().unwrap_or("https"))
+            .authority(base_url.authority().map(|a| a.as_str()).unwrap_or_default())
             .path_and_query(&path_and_query[..])
             .build()
             .unwrap();
@@ -154,6 +177,8 @@ pub enum SpotifyApiError {
     NoContent,
     #[error("Request failed ({0}): {1}")]
     BadStatus(u16, String),
+    #[error("Rate limited, retry after {0} seconds")]
+    RateLimited(u64),
     #[error(transparent)]
     ClientError(#[from] isahc::Error),
     #[error(transparent)]
@@ -167,20 +192,32 @@ pub enum SpotifyApiError {
 }
 
 pub(crate) struct SpotifyClient {
+    base_url: Uri,
     token: Mutex<Option<String>>,
     client: HttpClient,
+    permits: Semaphore,
+    // Rate limits apply to the whole app, so every request waits once we hit one
+    retry_after: Mutex<Option<Instant>>,
 }
 
 impl SpotifyClient {
     pub(crate) fn new() -> Self {
+        Self::with_base_url(Uri::from_static(SPOTIFY_API_URL))
+    }
+
+    // The base URL should only have a scheme and an authority, e.g. http://localhost:8080
+    pub(crate) fn with_base_url(base_url: Uri) -> Self {
         let mut builder = HttpClient::builder();
         if cfg!(debug_assertions) {
             builder = builder.ssl_options(isahc::config::SslOption::DANGER_ACCEPT_INVALID_CERTS);
         }
         let client = builder.build().unwrap();
         Self {
+            base_url,
             token: Mutex::new(None),
             client,
+            permits: Semaphore::new(MAX_CONCURRENT_REQUESTS),
+            retry_after: Mutex::new(None),
         }
     }
 
@@ -217,14 +254,110 @@ impl SpotifyClient {
             .and_then(|s| u64::from_str(s).ok())
     }
 
+    fn parse_retry_after<B>(response: &Response<B>) -> Option<u64> {
+        response
+            .headers()
+            .get("retry-after")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|s| u64::from_str(s.trim()).ok())
+    }
+
+    fn retry_delay<B>(response: &Response<B>, attempt: u32) -> Duration {
+        Self::parse_retry_after(response)
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| INITIAL_BACKOFF * 2u32.pow(attempt))
+    }
+
+    fn delay_all_requests(&self, delay: Duration) {
+        if let Ok(mut retry_after) = self.retry_after.lock() {
+            let until = Instant::now() + delay;
+            *retry_after = Some(retry_after.map_or(until, |current| current.max(until)));
+        }
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        let delay = self
+            .retry_after
+            .lock()
+            .ok()
+            .and_then(|retry_after| (*retry_after)?.checked_duration_since(Instant::now()));
+        if let Some(delay) = delay {
+            async_std::task::sleep(delay).await;
+        }
+    }
+
+    fn copy_request<B>(parts: &isahc::http::request::Parts, body: B) -> Request<B> {
+        let mut builder = Request::builder()
+            .method(parts.method.clone())
+            .uri(parts.uri.clone())
+            .version(parts.version);
+        if let Some(headers) = builder.headers_mut() {
+            *headers = parts.headers.clone();
+        }
+        builder.body(body).unwrap()
+    }
+
+    // Sends a request, retrying it when rate limited (429) or when the server is having issues (5xx).
+    // The server may have applied a request that failed with a 5xx, so only reads are retried then.
+    // The last response is returned as is when all attempts failed.
+    async fn send_with_retries<B>(
+        &self,
+        request: Request<B>,
+    ) -> Result<Response<AsyncBody>, SpotifyApiError>
+    where
+        B: Into<isahc::AsyncBody> + Clone,
+    {
+        let (parts, body) = request.into_parts();
+        let is_read = parts.method == Method::GET || parts.method == Method::HEAD;
+        let mut attempt = 0;
+        loop {
+            self.wait_for_rate_limit().await;
+
+            let response = {
+                let _permit = self.permits.acquire().await;
+                let request = Self::copy_request(&parts, body.clone());
+                self.client.send_async(request).await?
+            };
+
+            let status = response.status();
+            let should_retry =
+                status == StatusCode::TOO_MANY_REQUESTS || (is_read && status.is_server_error());
+            if !should_retry || attempt >= MAX_RETRIES {
+                return Ok(response);
+            }
+
+            let delay = Self::retry_delay(&response, attempt);
+            if delay > MAX_RETRY_DELAY {
+                return Ok(response);
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                self.delay_all_requests(delay);
+            }
+
+            warn!(
+                "Request to {} failed ({}), retrying in {:?}",
+                parts.uri.path(),
+                status,
+                delay
+            );
+            async_std::task::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn rate_limited_error<B>(response: &Response<B>) -> SpotifyApiError {
+        SpotifyApiError::RateLimited(Self::parse_retry_after(response).unwrap_or(0))
+    }
+
     async fn send_req<B, T>(
         &self,
         request: Request<B>,
     ) -> Result<SpotifyResponse<T>, SpotifyApiError>
     where
-        B: Into<isahc::AsyncBody>,
+        B: Into<isahc::AsyncBody> + Clone,
     {
-        let mut result = self.client.send_async(request).await?;
+        let mut result = self.send_with_retries(request).await?;
 
         let etag = result
             .headers()
@@ -253,6 +386,7 @@ impl SpotifyClient {
                 max_age: cache_control.unwrap_or(10),
                 etag,
             }),
+            StatusCode::TOO_MANY_REQUESTS => Err(Self::rate_limited_error(&result)),
             s => Err(SpotifyApiError::BadStatus(
                 s.as_u16(),
                 result
@@ -265,15 +399,16 @@ impl SpotifyClient {
 
     async fn send_req_no_response<B>(&self, request: Request<B>) -> Result<(), SpotifyApiError>
     where
-        B: Into<isahc::AsyncBody>,
+        B: Into<isahc::AsyncBody> + Clone,
     {
-        let mut result = self.client.send_async(request).await?;
+        let mut result = self.send_with_retries(request).await?;
         match result.status() {
             StatusCode::UNAUTHORIZED => {
                 self.clear_token();
                 Err(SpotifyApiError::InvalidToken)
             }
             StatusCode::NOT_MODIFIED => Ok(()),
+            StatusCode::TOO_MANY_REQUESTS => Err(Self::rate_limited_error(&result)),
             s if s.is_success() => Ok(()),
             s => Err(SpotifyApiError::BadStatus(
                 s.as_u16(),
@@ -321,6 +456,78 @@ impl SpotifyClient {
             .uri(format!("/v1/artists/{}/top-tracks", id), Some(&query))
     }
 
+    pub(crate) fn is_artist_followed(&self, id: &str) -> SpotifyRequest<'_, (), Vec<bool>> {
+        let query = make_query_params()
+            .append_pair("type", "artist")
+            .append_pair("ids", id)
+            .finish();
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/following/contains".to_string(), Some(&query))
+    }
+
+    pub(crate) fn follow_artist(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
+        let query = make_query_params()
+            .append_pair("type", "artist")
+            .append_pair("ids", id)
+            .finish();
+        self.request()
+            .method(Method::PUT)
+            .uri("/v1/me/following".to_string(), Some(&query))
+    }
+
+    pub(crate) fn unfollow_artist(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
+        let query = make_query_params()
+            .append_pair("type", "artist")
+            .append_pair("ids", id)
+            .finish();
+        self.request()
+            .method(Method::DELETE)
+            .uri("/v1/me/following".to_string(), Some(&query))
+    }
+
+    pub(crate) fn get_followed_artists(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), FollowedArtistsPage> {
+        let mut query = make_query_params();
+        query
+            .append_pair("type", "artist")
+            .append_pair("limit", &limit.to_string()[..]);
+        if let Some(after) = after {
+            query.append_pair("after", after);
+        }
+        let query = query.finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/following".to_string(), Some(&query))
+    }
+
+    pub(crate) fn get_recommendations(
+        &self,
+        seed_tracks: &[String],
+        seed_artists: &[String],
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), Recommendations> {
+        let mut params = make_query_params();
+        if !seed_tracks.is_empty() {
+            params.append_pair("seed_tracks", &seed_tracks.join(","));
+        }
+        if !seed_artists.is_empty() {
+            params.append_pair("seed_artists", &seed_artists.join(","));
+        }
+        let query = params
+            .append_pair("limit", &limit.to_string()[..])
+            .append_pair("market", "from_token")
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/recommendations".to_string(), Some(&query))
+    }
+
     pub(crate) fn is_album_saved(&self, id: &str) -> SpotifyRequest<'_, (), Vec<bool>> {
         let query = make_query_params().append_pair("ids", id).finish();
         self.request()
@@ -328,6 +535,18 @@ impl SpotifyClient {
             .uri("/v1/me/albums/contains".to_string(), Some(&query))
     }
 
+    pub(crate) fn contains_saved_tracks(
+        &self,
+        ids: &[String],
+    ) -> SpotifyRequest<'_, (), Vec<bool>> {
+        let query = make_query_params()
+            .append_pair("ids", &ids.join(","))
+            .finish();
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/tracks/contains".to_string(), Some(&query))
+    }
+
     pub(crate) fn save_album(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
         let query = make_query_params().append_pair("ids", id).finish();
         self.request()
@@ -362,6 +581,16 @@ impl SpotifyClient {
             .uri(format!("/v1/albums/{}", id), None)
     }
 
+    pub(crate) fn get_track(&self, id: &str) -> SpotifyRequest<'_, (), TrackItem> {
+        let query = make_query_params()
+            .append_pair("market", "from_token")
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri(format!("/v1/tracks/{}", id), Some(&query))
+    }
+
     pub(crate) fn get_album_tracks(
         &self,
         id: &str,
@@ -378,11 +607,84 @@ impl SpotifyClient {
             .uri(format!("/v1/albums/{}/tracks", id), Some(&query))
     }
 
+    pub(crate) fn get_show(&self, id: &str) -> SpotifyRequest<'_, (), Show> {
+        let query = make_query_params()
+            .append_pair("market", "from_token")
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri(format!("/v1/shows/{}", id), Some(&query))
+    }
+
+    pub(crate) fn get_show_episodes(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), Page<Option<Episode>>> {
+        let query = make_query_params()
+            .append_pair("offset", &offset.to_string()[..])
+            .append_pair("limit", &limit.to_string()[..])
+            .append_pair("market", "from_token")
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri(format!("/v1/shows/{}/episodes", id), Some(&query))
+    }
+
+    pub(crate) fn get_episode(&self, id: &str) -> SpotifyRequest<'_, (), Episode> {
+        let query = make_query_params()
+            .append_pair("market", "from_token")
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri(format!("/v1/episodes/{}", id), Some(&query))
+    }
+
+    pub(crate) fn is_show_saved(&self, id: &str) -> SpotifyRequest<'_, (), Vec<bool>> {
+        let query = make_query_params().append_pair("ids", id).finish();
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/shows/contains".to_string(), Some(&query))
+    }
+
+    pub(crate) fn save_show(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
+        let query = make_query_params().append_pair("ids", id).finish();
+        self.request()
+            .method(Method::PUT)
+            .uri("/v1/me/shows".to_string(), Some(&query))
+    }
+
+    pub(crate) fn remove_saved_show(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
+        let query = make_query_params().append_pair("ids", id).finish();
+        self.request()
+            .method(Method::DELETE)
+            .uri("/v1/me/shows".to_string(), Some(&query))
+    }
+
+    pub(crate) fn get_saved_shows(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), Page<SavedShow>> {
+        let query = make_query_params()
+            .append_pair("offset", &offset.to_string()[..])
+            .append_pair("limit", &limit.to_string()[..])
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/shows".to_string(), Some(&query))
+    }
+
     pub(crate) fn get_playlist(&self, id: &str) -> SpotifyRequest<'_, (), Playlist> {
         let query = make_query_params()
             .append_pair(
                 "fields",
-                "id,name,images,owner,tracks(total,items(is_local,track(name,id,uri,duration_ms,artists(name,id),album(name,id,images,artists))))",
+                "id,name,description,public,collaborative,snapshot_id,images,owner,tracks(total,items(is_local,track(name,id,uri,duration_ms,artists(name,id),album(name,id,images,artists))))",
             )
             .finish();
         self.request()
@@ -428,6 +730,57 @@ impl SpotifyClient {
             .json_body(Uris { uris })
     }
 
+    pub(crate) fn reorder_playlist_tracks(
+        &self,
+        playlist: &str,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+        snapshot_id: Option<String>,
+    ) -> SpotifyRequest<'_, Vec<u8>, PlaylistSnapshot> {
+        self.request()
+            .method(Method::PUT)
+            .uri(format!("/v1/playlists/{}/tracks", playlist), None)
+            .json_body(PlaylistTracksReorder {
+                range_start,
+                range_length,
+                insert_before,
+                snapshot_id,
+            })
+    }
+
+    pub(crate) fn create_playlist(
+        &self,
+        user_id: &str,
+        name: String,
+    ) -> SpotifyRequest<'_, Vec<u8>, Playlist> {
+        let user_id = utf8_percent_encode(user_id, PATH_ENCODE_SET);
+        self.request()
+            .method(Method::POST)
+            .uri(format!("/v1/users/{}/playlists", user_id), None)
+            .json_body(PlaylistDetails {
+                name: Some(name),
+                ..Default::default()
+            })
+    }
+
+    pub(crate) fn update_playlist_details(
+        &self,
+        id: &str,
+        details: PlaylistDetails,
+    ) -> SpotifyRequest<'_, Vec<u8>, ()> {
+        self.request()
+            .method(Method::PUT)
+            .uri(format!("/v1/playlists/{}", id), None)
+            .json_body(details)
+    }
+
+    pub(crate) fn unfollow_playlist(&self, id: &str) -> SpotifyRequest<'_, (), ()> {
+        self.request()
+            .method(Method::DELETE)
+            .uri(format!("/v1/playlists/{}/followers", id), None)
+    }
+
     pub(crate) fn get_saved_albums(
         &self,
         offset: usize,
@@ -458,6 +811,57 @@ impl SpotifyClient {
             .uri("/v1/me/tracks".to_string(), Some(&query))
     }
 
+    pub(crate) fn get_recently_played(
+        &self,
+        before: Option<&str>,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), CursorPage<PlayHistoryItem>> {
+        let mut query = make_query_params();
+        query.append_pair("limit", &limit.to_string()[..]);
+        if let Some(before) = before {
+            query.append_pair("before", before);
+        }
+        let query = query.finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/player/recently-played".to_string(), Some(&query))
+    }
+
+    pub(crate) fn get_top_tracks(
+        &self,
+        time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), Page<TrackItem>> {
+        let query = make_query_params()
+            .append_pair("time_range", time_range_param(time_range))
+            .append_pair("offset", &offset.to_string()[..])
+            .append_pair("limit", &limit.to_string()[..])
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/top/tracks".to_string(), Some(&query))
+    }
+
+    pub(crate) fn get_top_artists(
+        &self,
+        time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+    ) -> SpotifyRequest<'_, (), Page<Artist>> {
+        let query = make_query_params()
+            .append_pair("time_range", time_range_param(time_range))
+            .append_pair("offset", &offset.to_string()[..])
+            .append_pair("limit", &limit.to_string()[..])
+            .finish();
+
+        self.request()
+            .method(Method::GET)
+            .uri("/v1/me/top/artists".to_string(), Some(&query))
+    }
+
     pub(crate) fn get_saved_playlists(
         &self,
         offset: usize,
@@ -476,12 +880,13 @@ impl SpotifyClient {
     pub(crate) fn search(
         &self,
         query: String,
+        types: Vec<SearchType>,
         offset: usize,
         limit: usize,
     ) -> SpotifyRequest<'_, (), RawSearchResults> {
         let query = SearchQuery {
             query,
-            types: vec![SearchType::Album, SearchType::Artist],
+            types,
             limit,
             offset,
         };
@@ -536,6 +941,169 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_retry_delay() {
+        let response = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header("Retry-After", "5")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            SpotifyClient::retry_delay(&response, 0),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            SpotifyClient::retry_delay(&response, 2),
+            Duration::from_secs(5)
+        );
+
+        let response = Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .body(())
+            .unwrap();
+        assert_eq!(SpotifyClient::retry_delay(&response, 0), INITIAL_BACKOFF);
+        assert_eq!(
+            SpotifyClient::retry_delay(&response, 2),
+            INITIAL_BACKOFF * 4
+        );
+    }
+
+    #[test]
+    fn test_create_playlist_encoding() {
+        let client = SpotifyClient::new();
+        let req = client.create_playlist("anna.lafuente❤", "New playlist".to_string());
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/users/anna.lafuente%E2%9D%A4/playlists"
+        );
+        assert_eq!(req.body, br#"{"name":"New playlist"}"#.to_vec());
+    }
+
+    #[test]
+    fn test_reorder_playlist_tracks_body() {
+        let client = SpotifyClient::new();
+        let req = client.reorder_playlist_tracks("abc", 2, 1, 0, Some("snap".to_string()));
+        assert_eq!(
+            req.body,
+            br#"{"range_start":2,"range_length":1,"insert_before":0,"snapshot_id":"snap"}"#
+                .to_vec()
+        );
+
+        let req = client.reorder_playlist_tracks("abc", 0, 2, 5, None);
+        assert_eq!(
+            req.body,
+            br#"{"range_start":0,"range_length":2,"insert_before":5}"#.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_top_items_query() {
+        let client = SpotifyClient::new();
+        let req = client.get_top_tracks(TimeRange::Short, 50, 25);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/me/top/tracks?time_range=short_term&offset=50&limit=25"
+        );
+
+        let req = client.get_recently_played(Some("1700000000000"), 50);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/me/player/recently-played?limit=50&before=1700000000000"
+        );
+    }
+
+    #[test]
+    fn test_followed_artists_query() {
+        let client = SpotifyClient::new();
+        let req = client.get_followed_artists(None, 20);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/me/following?type=artist&limit=20"
+        );
+
+        let req = client.get_followed_artists(Some("artist0"), 20);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/me/following?type=artist&limit=20&after=artist0"
+        );
+    }
+
+    #[test]
+    fn test_show_episodes_query() {
+        let client = SpotifyClient::new();
+        let req = client.get_show_episodes("show0", 20, 10);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/shows/show0/episodes?offset=20&limit=10&market=from_token"
+        );
+    }
+
+    #[test]
+    fn test_episode_query() {
+        let client = SpotifyClient::new();
+        let req = client.get_episode("episode0");
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/episodes/episode0?market=from_token"
+        );
+    }
+
+    #[test]
+    fn test_recommendations_query() {
+        let client = SpotifyClient::new();
+        let req = client.get_recommendations(&["track0".to_string()], &[], 20);
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/recommendations?seed_tracks=track0&limit=20&market=from_token"
+        );
+
+        let req = client.get_recommendations(
+            &["track0".to_string(), "track1".to_string()],
+            &["artist0".to_string()],
+            20,
+        );
+        assert_eq!(
+            req.request
+                .uri_ref()
+                .and_then(|u| u.path_and_query())
+                .unwrap()
+                .as_str(),
+            "/v1/recommendations?seed_tracks=track0%2Ctrack1&seed_artists=artist0&limit=20&market=from_token"
+        );
+    }
+
     #[test]
     fn test_search_query() {
         let query = SearchQuery {
@@ -551,6 +1119,21 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_search_query_tracks_and_playlists() {
+        let query = SearchQuery {
+            query: "test".to_string(),
+            types: vec![SearchType::Track, SearchType::Playlist],
+            limit: 20,
+            offset: 40,
+        };
+
+        assert_eq!(
+            query.into_query_string(),
+            "type=track,playlist&q=test&offset=40&limit=20&market=from_token"
+        );
+    }
+
     #[test]
     fn test_search_query_spaces_and_stuff() {
         let query = SearchQuery {
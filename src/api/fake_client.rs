@@ -0,0 +1,1069 @@
+use futures::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use super::cached_client::{SpotifyApiClient, SpotifyResult};
+use super::client::SpotifyApiError;
+use crate::app::models::*;
+
+// Same page size as the Web API uses when returning the first tracks of a playlist
+const PLAYLIST_FIRST_PAGE_SIZE: usize = 100;
+
+// Same for the first episodes of a show
+const SHOW_FIRST_PAGE_SIZE: usize = 50;
+
+#[derive(Default)]
+struct FakeData {
+    albums: HashMap<String, AlbumFullDescription>,
+    playlists: HashMap<String, (PlaylistDescription, Vec<SongDescription>)>,
+    saved_albums: Vec<String>,
+    saved_tracks: Vec<SongDescription>,
+    saved_playlists: Vec<String>,
+    users: HashMap<String, UserRef>,
+    artists: HashMap<String, ArtistDescription>,
+    followed_artists: Vec<String>,
+    shows: HashMap<String, ShowDescription>,
+    saved_shows: Vec<String>,
+    recently_played: Vec<SongDescription>,
+    top_songs: Vec<SongDescription>,
+    top_artists: Vec<ArtistSummary>,
+    recommendations: Vec<SongDescription>,
+    next_snapshot: usize,
+}
+
+// An in-memory SpotifyApiClient, for tests that need some data to browse but no network.
+// Any call can be made to fail by scripting errors with fail_next.
+#[derive(Default)]
+pub struct FakeSpotifyClient {
+    data: Mutex<FakeData>,
+    failures: Mutex<HashMap<&'static str, VecDeque<SpotifyApiError>>>,
+    calls: Mutex<Vec<&'static str>>,
+    token: Mutex<Option<String>>,
+}
+
+fn page<T: Clone>(items: &[T], offset: usize, limit: usize) -> Vec<T> {
+    items.iter().skip(offset).take(limit).cloned().collect()
+}
+
+// Local files can be put in playlists with a spotify:local: URI, they are left out like the Web API does
+fn song_batch(songs: &[SongDescription], offset: usize, limit: usize) -> SongBatch {
+    let (local, listed): (Vec<_>, Vec<_>) = songs
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(limit)
+        .partition(|(_, song)| song.uri.starts_with("spotify:local:"));
+    SongBatch {
+        songs: listed.into_iter().map(|(_, song)| song.clone()).collect(),
+        batch: Batch {
+            offset,
+            batch_size: limit,
+            total: songs.len(),
+        },
+        skipped: local.into_iter().map(|(i, _)| i).collect(),
+    }
+}
+
+fn episode_batch(episodes: &[EpisodeDescription], offset: usize, limit: usize) -> EpisodeBatch {
+    EpisodeBatch {
+        episodes: page(episodes, offset, limit),
+        batch: Batch {
+            offset,
+            batch_size: limit,
+            total: episodes.len(),
+        },
+    }
+}
+
+impl FakeSpotifyClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_album(self, album: AlbumFullDescription) -> Self {
+        let id = album.description.id.clone();
+        self.data.lock().unwrap().albums.insert(id, album);
+        self
+    }
+
+    // The songs of the description are ignored, the playlist is made of the given songs
+    pub fn with_playlist(self, playlist: PlaylistDescription, songs: Vec<SongDescription>) -> Self {
+        let id = playlist.id.clone();
+        self.data
+            .lock()
+            .unwrap()
+            .playlists
+            .insert(id, (playlist, songs));
+        self
+    }
+
+    pub fn with_saved_album(self, id: &str) -> Self {
+        self.data.lock().unwrap().saved_albums.push(id.to_string());
+        self
+    }
+
+    pub fn with_saved_tracks(self, songs: Vec<SongDescription>) -> Self {
+        self.data.lock().unwrap().saved_tracks.extend(songs);
+        self
+    }
+
+    pub fn with_saved_playlist(self, id: &str) -> Self {
+        self.data
+            .lock()
+            .unwrap()
+            .saved_playlists
+            .push(id.to_string());
+        self
+    }
+
+    pub fn with_user(self, user: UserRef) -> Self {
+        let id = user.id.clone();
+        self.data.lock().unwrap().users.insert(id, user);
+        self
+    }
+
+    pub fn with_artist(self, artist: ArtistDescription) -> Self {
+        let id = artist.id.clone();
+        self.data.lock().unwrap().artists.insert(id, artist);
+        self
+    }
+
+    // The artist must have been added with with_artist
+    pub fn with_followed_artist(self, id: &str) -> Self {
+        self.data
+            .lock()
+            .unwrap()
+            .followed_artists
+            .push(id.to_string());
+        self
+    }
+
+    // All the episodes of the show are given with the description
+    pub fn with_show(self, show: ShowDescription) -> Self {
+        let id = show.id.clone();
+        self.data.lock().unwrap().shows.insert(id, show);
+        self
+    }
+
+    pub fn with_saved_show(self, id: &str) -> Self {
+        self.data.lock().unwrap().saved_shows.push(id.to_string());
+        self
+    }
+
+    // Most recent first
+    pub fn with_recently_played(self, songs: Vec<SongDescription>) -> Self {
+        self.data.lock().unwrap().recently_played.extend(songs);
+        self
+    }
+
+    // The same top items are returned whatever the time range
+    pub fn with_top_songs(self, songs: Vec<SongDescription>) -> Self {
+        self.data.lock().unwrap().top_songs.extend(songs);
+        self
+    }
+
+    pub fn with_top_artists(self, artists: Vec<ArtistSummary>) -> Self {
+        self.data.lock().unwrap().top_artists.extend(artists);
+        self
+    }
+
+    // The same recommendations are returned whatever the seeds
+    pub fn with_recommendations(self, songs: Vec<SongDescription>) -> Self {
+        self.data.lock().unwrap().recommendations.extend(songs);
+        self
+    }
+
+    // The next call to the method with that name will fail with the given error
+    pub fn fail_next(&self, method: &'static str, error: SpotifyApiError) {
+        self.failures
+            .lock()
+            .unwrap()
+            .entry(method)
+            .or_default()
+            .push_back(error);
+    }
+
+    // Names of the methods called so far, in order
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn token(&self) -> Option<String> {
+        self.token.lock().unwrap().clone()
+    }
+
+    pub fn playlist_songs(&self, id: &str) -> Vec<SongDescription> {
+        self.data
+            .lock()
+            .unwrap()
+            .playlists
+            .get(id)
+            .map(|(_, songs)| songs.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn saved_tracks(&self) -> Vec<SongDescription> {
+        self.data.lock().unwrap().saved_tracks.clone()
+    }
+
+    fn call(&self, method: &'static str) -> SpotifyResult<()> {
+        self.calls.lock().unwrap().push(method);
+        let failure = self
+            .failures
+            .lock()
+            .unwrap()
+            .get_mut(method)
+            .and_then(|errors| errors.pop_front());
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn not_found(id: &str) -> SpotifyApiError {
+        SpotifyApiError::BadStatus(404, format!("{} not found", id))
+    }
+
+    fn find_album(&self, id: &str) -> SpotifyResult<AlbumFullDescription> {
+        let data = self.data.lock().unwrap();
+        let mut album = data
+            .albums
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Self::not_found(id))?;
+        album.description.is_liked = data.saved_albums.iter().any(|a| a == id);
+        Ok(album)
+    }
+
+    // Tracks are only known through the albums they belong to
+    fn find_song(&self, id: &str) -> SpotifyResult<SongDescription> {
+        let data = self.data.lock().unwrap();
+        data.albums
+            .values()
+            .flat_map(|album| album.description.songs.songs.iter())
+            .find(|song| song.id == id)
+            .cloned()
+            .ok_or_else(|| Self::not_found(id))
+    }
+
+    fn find_show(&self, id: &str) -> SpotifyResult<ShowDescription> {
+        let data = self.data.lock().unwrap();
+        let show = data.shows.get(id).ok_or_else(|| Self::not_found(id))?;
+        Ok(ShowDescription {
+            episodes: episode_batch(&show.episodes.episodes, 0, SHOW_FIRST_PAGE_SIZE),
+            is_saved: data.saved_shows.iter().any(|s| s == id),
+            ..show.clone()
+        })
+    }
+
+    fn find_playlist(&self, id: &str) -> SpotifyResult<PlaylistDescription> {
+        let data = self.data.lock().unwrap();
+        let (playlist, songs) = data.playlists.get(id).ok_or_else(|| Self::not_found(id))?;
+        Ok(PlaylistDescription {
+            songs: song_batch(songs, 0, PLAYLIST_FIRST_PAGE_SIZE),
+            ..playlist.clone()
+        })
+    }
+
+    fn find_playlists(&self, ids: &[String]) -> SpotifyResult<Vec<PlaylistDescription>> {
+        ids.iter().map(|id| self.find_playlist(id)).collect()
+    }
+
+    fn owned_playlists(&self, user_id: &str) -> Vec<String> {
+        let data = self.data.lock().unwrap();
+        let mut ids: Vec<String> = data
+            .playlists
+            .values()
+            .filter(|(p, _)| p.owner.id == user_id)
+            .map(|(p, _)| p.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn edit_playlist<F>(&self, id: &str, edit: F) -> SpotifyResult<String>
+    where
+        F: FnOnce(&mut PlaylistDescription, &mut Vec<SongDescription>),
+    {
+        let mut data = self.data.lock().unwrap();
+        data.next_snapshot += 1;
+        let snapshot_id = format!("snapshot{}", data.next_snapshot);
+        let (playlist, songs) = data
+            .playlists
+            .get_mut(id)
+            .ok_or_else(|| Self::not_found(id))?;
+        edit(playlist, songs);
+        playlist.snapshot_id = Some(snapshot_id.clone());
+        Ok(snapshot_id)
+    }
+}
+
+impl SpotifyApiClient for FakeSpotifyClient {
+    fn get_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_artist")?;
+            let data = self.data.lock().unwrap();
+            let mut artist = data
+                .artists
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| Self::not_found(&id))?;
+            artist.is_followed = data.followed_artists.contains(&id);
+            Ok(artist)
+        })
+    }
+
+    fn get_album(&self, id: &str) -> BoxFuture<SpotifyResult<AlbumFullDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_album")?;
+            self.find_album(&id)
+        })
+    }
+
+    fn get_track(&self, id: &str) -> BoxFuture<SpotifyResult<SongDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_track")?;
+            self.find_song(&id)
+        })
+    }
+
+    fn get_album_tracks(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<SongBatch>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_album_tracks")?;
+            let album = self.find_album(&id)?;
+            Ok(song_batch(&album.description.songs.songs, offset, limit))
+        })
+    }
+
+    fn get_playlist(&self, id: &str) -> BoxFuture<SpotifyResult<PlaylistDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_playlist")?;
+            self.find_playlist(&id)
+        })
+    }
+
+    fn get_playlist_tracks(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<SongBatch>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_playlist_tracks")?;
+            let data = self.data.lock().unwrap();
+            let (_, songs) = data
+                .playlists
+                .get(&id)
+                .ok_or_else(|| Self::not_found(&id))?;
+            Ok(song_batch(songs, offset, limit))
+        })
+    }
+
+    fn get_saved_albums(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<AlbumDescription>>> {
+        Box::pin(async move {
+            self.call("get_saved_albums")?;
+            let ids = page(&self.data.lock().unwrap().saved_albums, offset, limit);
+            ids.iter()
+                .map(|id| self.find_album(id).map(|a| a.description))
+                .collect()
+        })
+    }
+
+    fn get_saved_tracks(&self, offset: usize, limit: usize) -> BoxFuture<SpotifyResult<SongBatch>> {
+        Box::pin(async move {
+            self.call("get_saved_tracks")?;
+            Ok(song_batch(
+                &self.data.lock().unwrap().saved_tracks,
+                offset,
+                limit,
+            ))
+        })
+    }
+
+    fn save_album(&self, id: &str) -> BoxFuture<SpotifyResult<AlbumDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("save_album")?;
+            let album = self.find_album(&id)?;
+            let mut data = self.data.lock().unwrap();
+            if !data.saved_albums.contains(&id) {
+                data.saved_albums.insert(0, id);
+            }
+            Ok(AlbumDescription {
+                is_liked: true,
+                ..album.description
+            })
+        })
+    }
+
+    fn save_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<()>> {
+        Box::pin(async move {
+            self.call("save_tracks")?;
+            let mut data = self.data.lock().unwrap();
+            let known: Vec<SongDescription> = {
+                let all_songs = data
+                    .albums
+                    .values()
+                    .flat_map(|a| a.description.songs.songs.iter())
+                    .chain(data.playlists.values().flat_map(|(_, songs)| songs.iter()));
+                let mut known: Vec<SongDescription> = vec![];
+                for song in all_songs {
+                    if ids.contains(&song.id) && !known.iter().any(|s| s.id == song.id) {
+                        known.push(song.clone());
+                    }
+                }
+                known
+            };
+            for song in known.into_iter().rev() {
+                if !data.saved_tracks.iter().any(|s| s.id == song.id) {
+                    data.saved_tracks.insert(0, song);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn remove_saved_album(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("remove_saved_album")?;
+            self.data.lock().unwrap().saved_albums.retain(|a| a != &id);
+            Ok(())
+        })
+    }
+
+    fn remove_saved_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<()>> {
+        Box::pin(async move {
+            self.call("remove_saved_tracks")?;
+            self.data
+                .lock()
+                .unwrap()
+                .saved_tracks
+                .retain(|s| !ids.contains(&s.id));
+            Ok(())
+        })
+    }
+
+    fn contains_saved_tracks(&self, ids: Vec<String>) -> BoxFuture<SpotifyResult<Vec<bool>>> {
+        Box::pin(async move {
+            self.call("contains_saved_tracks")?;
+            let data = self.data.lock().unwrap();
+            Ok(ids
+                .iter()
+                .map(|id| data.saved_tracks.iter().any(|s| &s.id == id))
+                .collect())
+        })
+    }
+
+    fn get_followed_artists(
+        &self,
+        after: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<FollowedArtists>> {
+        Box::pin(async move {
+            self.call("get_followed_artists")?;
+            let data = self.data.lock().unwrap();
+            // Cursors are the id of the last artist of the previous page
+            let offset = after
+                .and_then(|after| data.followed_artists.iter().position(|id| *id == after))
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            let ids = page(&data.followed_artists, offset, limit);
+            let after = ids
+                .last()
+                .filter(|_| offset + ids.len() < data.followed_artists.len())
+                .cloned();
+            let artists = ids
+                .iter()
+                .filter_map(|id| data.artists.get(id))
+                .map(|artist| ArtistSummary {
+                    id: artist.id.clone(),
+                    name: artist.name.clone(),
+                    photo: None,
+                })
+                .collect();
+            Ok(FollowedArtists { artists, after })
+        })
+    }
+
+    fn follow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<ArtistSummary>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("follow_artist")?;
+            let mut data = self.data.lock().unwrap();
+            let artist = data
+                .artists
+                .get(&id)
+                .map(|artist| ArtistSummary {
+                    id: artist.id.clone(),
+                    name: artist.name.clone(),
+                    photo: None,
+                })
+                .ok_or_else(|| Self::not_found(&id))?;
+            if !data.followed_artists.contains(&id) {
+                data.followed_artists.push(id);
+            }
+            Ok(artist)
+        })
+    }
+
+    fn unfollow_artist(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("unfollow_artist")?;
+            self.data
+                .lock()
+                .unwrap()
+                .followed_artists
+                .retain(|followed| *followed != id);
+            Ok(())
+        })
+    }
+
+    fn get_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_show")?;
+            self.find_show(&id)
+        })
+    }
+
+    fn get_show_episodes(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<EpisodeBatch>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_show_episodes")?;
+            let data = self.data.lock().unwrap();
+            let show = data.shows.get(&id).ok_or_else(|| Self::not_found(&id))?;
+            Ok(episode_batch(&show.episodes.episodes, offset, limit))
+        })
+    }
+
+    fn get_episode(&self, id: &str) -> BoxFuture<SpotifyResult<EpisodeDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_episode")?;
+            let data = self.data.lock().unwrap();
+            data.shows
+                .values()
+                .flat_map(|show| show.episodes.episodes.iter())
+                .find(|episode| episode.id == id)
+                .cloned()
+                .ok_or_else(|| Self::not_found(&id))
+        })
+    }
+
+    fn get_saved_shows(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<ShowDescription>>> {
+        Box::pin(async move {
+            self.call("get_saved_shows")?;
+            let ids = page(&self.data.lock().unwrap().saved_shows, offset, limit);
+            ids.iter().map(|id| self.find_show(id)).collect()
+        })
+    }
+
+    fn save_show(&self, id: &str) -> BoxFuture<SpotifyResult<ShowDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("save_show")?;
+            let show = self.find_show(&id)?;
+            let mut data = self.data.lock().unwrap();
+            if !data.saved_shows.contains(&id) {
+                data.saved_shows.insert(0, id);
+            }
+            Ok(ShowDescription {
+                is_saved: true,
+                ..show
+            })
+        })
+    }
+
+    fn remove_saved_show(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("remove_saved_show")?;
+            self.data.lock().unwrap().saved_shows.retain(|s| s != &id);
+            Ok(())
+        })
+    }
+
+    fn get_saved_playlists(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<PlaylistDescription>>> {
+        Box::pin(async move {
+            self.call("get_saved_playlists")?;
+            let ids = page(&self.data.lock().unwrap().saved_playlists, offset, limit);
+            self.find_playlists(&ids)
+        })
+    }
+
+    fn add_to_playlist(&self, id: &str, uris: Vec<String>) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("add_to_playlist")?;
+            let added: Vec<SongDescription> = {
+                let data = self.data.lock().unwrap();
+                let known: Vec<&SongDescription> = data
+                    .albums
+                    .values()
+                    .flat_map(|a| a.description.songs.songs.iter())
+                    .chain(data.playlists.values().flat_map(|(_, songs)| songs.iter()))
+                    .chain(data.saved_tracks.iter())
+                    .collect();
+                uris.iter()
+                    .filter_map(|uri| known.iter().find(|s| &s.uri == uri).cloned().cloned())
+                    .collect()
+            };
+            self.edit_playlist(&id, move |_, songs| songs.extend(added))?;
+            Ok(())
+        })
+    }
+
+    fn remove_from_playlist(&self, id: &str, uris: Vec<String>) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("remove_from_playlist")?;
+            self.edit_playlist(&id, |_, songs| songs.retain(|s| !uris.contains(&s.uri)))?;
+            Ok(())
+        })
+    }
+
+    fn reorder_playlist_tracks(
+        &self,
+        id: &str,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+        snapshot_id: Option<String>,
+    ) -> BoxFuture<SpotifyResult<String>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("reorder_playlist_tracks")?;
+            // Stricter than Spotify, which would apply the move to the older version of the playlist
+            let current_snapshot_id = self
+                .data
+                .lock()
+                .unwrap()
+                .playlists
+                .get(&id)
+                .and_then(|(playlist, _)| playlist.snapshot_id.clone());
+            if snapshot_id.is_some() && snapshot_id != current_snapshot_id {
+                return Err(SpotifyApiError::BadStatus(
+                    400,
+                    format!("outdated snapshot of {}", id),
+                ));
+            }
+            self.edit_playlist(&id, |_, songs| {
+                let range_end = usize::min(range_start + range_length, songs.len());
+                let moved: Vec<SongDescription> = songs.drain(range_start..range_end).collect();
+                let insert_at = if insert_before > range_start {
+                    insert_before - moved.len()
+                } else {
+                    insert_before
+                };
+                let insert_at = usize::min(insert_at, songs.len());
+                songs.splice(insert_at..insert_at, moved);
+            })
+        })
+    }
+
+    fn create_playlist(
+        &self,
+        user_id: &str,
+        name: &str,
+    ) -> BoxFuture<SpotifyResult<PlaylistDescription>> {
+        let user_id = user_id.to_owned();
+        let name = name.to_owned();
+        Box::pin(async move {
+            self.call("create_playlist")?;
+            let playlist = {
+                let mut data = self.data.lock().unwrap();
+                let owner = data
+                    .users
+                    .get(&user_id)
+                    .cloned()
+                    .ok_or_else(|| Self::not_found(&user_id))?;
+                let playlist = PlaylistDescription {
+                    id: format!("created_playlist{}", data.playlists.len()),
+                    title: name,
+                    description: None,
+                    public: Some(true),
+                    collaborative: false,
+                    snapshot_id: Some("snapshot0".to_string()),
+                    art: None,
+                    songs: song_batch(&[], 0, PLAYLIST_FIRST_PAGE_SIZE),
+                    owner,
+                };
+                data.playlists
+                    .insert(playlist.id.clone(), (playlist.clone(), vec![]));
+                data.saved_playlists.insert(0, playlist.id.clone());
+                playlist
+            };
+            Ok(playlist)
+        })
+    }
+
+    fn update_playlist_details(
+        &self,
+        id: &str,
+        update: PlaylistDetailsUpdate,
+    ) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("update_playlist_details")?;
+            let mut data = self.data.lock().unwrap();
+            let (playlist, _) = data
+                .playlists
+                .get_mut(&id)
+                .ok_or_else(|| Self::not_found(&id))?;
+            playlist.apply_update(&update);
+            Ok(())
+        })
+    }
+
+    fn unfollow_playlist(&self, id: &str) -> BoxFuture<SpotifyResult<()>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("unfollow_playlist")?;
+            self.data
+                .lock()
+                .unwrap()
+                .saved_playlists
+                .retain(|p| p != &id);
+            Ok(())
+        })
+    }
+
+    fn search(
+        &self,
+        query: &str,
+        categories: Vec<SearchCategory>,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<SearchResults>> {
+        let query = query.to_lowercase();
+        Box::pin(async move {
+            self.call("search")?;
+            let data = self.data.lock().unwrap();
+            let matches = |s: &str| s.to_lowercase().contains(&query);
+            let wants = |c: SearchCategory| categories.contains(&c);
+
+            let mut albums: Vec<AlbumDescription> = data
+                .albums
+                .values()
+                .filter(|a| wants(SearchCategory::Albums) && matches(&a.description.title))
+                .map(|a| a.description.clone())
+                .collect();
+            albums.sort_by(|a, b| a.id.cmp(&b.id));
+
+            let mut artists: Vec<ArtistSummary> = data
+                .artists
+                .values()
+                .filter(|a| wants(SearchCategory::Artists) && matches(&a.name))
+                .map(|a| ArtistSummary {
+                    id: a.id.clone(),
+                    name: a.name.clone(),
+                    photo: None,
+                })
+                .collect();
+            artists.sort_by(|a, b| a.id.cmp(&b.id));
+
+            let mut songs: Vec<SongDescription> = data
+                .albums
+                .values()
+                .flat_map(|a| a.description.songs.songs.iter())
+                .filter(|s| wants(SearchCategory::Songs) && matches(&s.title))
+                .cloned()
+                .collect();
+            songs.sort_by(|a, b| a.id.cmp(&b.id));
+
+            let mut playlists: Vec<PlaylistDescription> = data
+                .playlists
+                .values()
+                .filter(|(p, _)| wants(SearchCategory::Playlists) && matches(&p.title))
+                .map(|(p, songs)| PlaylistDescription {
+                    songs: song_batch(songs, 0, PLAYLIST_FIRST_PAGE_SIZE),
+                    ..p.clone()
+                })
+                .collect();
+            playlists.sort_by(|a, b| a.id.cmp(&b.id));
+
+            Ok(SearchResults {
+                albums: page(&albums, offset, limit),
+                artists: page(&artists, offset, limit),
+                songs: page(&songs, offset, limit),
+                playlists: page(&playlists, offset, limit),
+            })
+        })
+    }
+
+    fn get_artist_albums(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<AlbumDescription>>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_artist_albums")?;
+            let data = self.data.lock().unwrap();
+            let artist = data.artists.get(&id).ok_or_else(|| Self::not_found(&id))?;
+            Ok(page(&artist.albums, offset, limit))
+        })
+    }
+
+    fn get_recently_played(
+        &self,
+        before: Option<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<RecentlyPlayed>> {
+        Box::pin(async move {
+            self.call("get_recently_played")?;
+            // Cursors are simply positions in the history
+            let offset = before.and_then(|b| b.parse::<usize>().ok()).unwrap_or(0);
+            let data = self.data.lock().unwrap();
+            let history = &data.recently_played;
+            let songs = page(history, offset, limit);
+            let before = Some(offset + songs.len())
+                .filter(|end| *end < history.len())
+                .map(|end| end.to_string());
+            Ok(RecentlyPlayed { songs, before })
+        })
+    }
+
+    fn get_recommendations(
+        &self,
+        _seed_tracks: Vec<String>,
+        _seed_artists: Vec<String>,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<SongDescription>>> {
+        Box::pin(async move {
+            self.call("get_recommendations")?;
+            Ok(page(&self.data.lock().unwrap().recommendations, 0, limit))
+        })
+    }
+
+    fn get_top_items(
+        &self,
+        category: TopItemsCategory,
+        _time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<TopItems>> {
+        Box::pin(async move {
+            self.call("get_top_items")?;
+            let data = self.data.lock().unwrap();
+            Ok(match category {
+                TopItemsCategory::Songs => {
+                    TopItems::Songs(song_batch(&data.top_songs, offset, limit))
+                }
+                TopItemsCategory::Artists => {
+                    TopItems::Artists(page(&data.top_artists, offset, limit))
+                }
+            })
+        })
+    }
+
+    fn get_user(&self, id: &str) -> BoxFuture<SpotifyResult<UserDescription>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_user")?;
+            let user = self
+                .data
+                .lock()
+                .unwrap()
+                .users
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| Self::not_found(&id))?;
+            let playlists = self.find_playlists(&self.owned_playlists(&id))?;
+            Ok(UserDescription {
+                id: user.id,
+                name: user.display_name,
+                playlists,
+            })
+        })
+    }
+
+    fn get_user_playlists(
+        &self,
+        id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<SpotifyResult<Vec<PlaylistDescription>>> {
+        let id = id.to_owned();
+        Box::pin(async move {
+            self.call("get_user_playlists")?;
+            let ids = page(&self.owned_playlists(&id), offset, limit);
+            self.find_playlists(&ids)
+        })
+    }
+
+    fn update_token(&self, token: String) {
+        self.token.lock().unwrap().replace(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::app::testing::{album, episode, playlist, show, song, user};
+    use futures::executor::block_on;
+
+    fn fake_client() -> FakeSpotifyClient {
+        FakeSpotifyClient::new()
+            .with_user(user("me"))
+            .with_album(album("album0", vec![song("track0"), song("track1")]))
+            .with_album(album("album1", vec![song("track2")]))
+            .with_saved_album("album1")
+            .with_saved_tracks(vec![song("track1")])
+            .with_playlist(playlist("playlist0", "me"), vec![song("track0")])
+            .with_saved_playlist("playlist0")
+            .with_artist(ArtistDescription {
+                id: "artist0".to_string(),
+                name: "Artist".to_string(),
+                albums: vec![],
+                top_tracks: vec![],
+                is_followed: false,
+            })
+    }
+
+    fn ids(songs: Vec<SongDescription>) -> Vec<String> {
+        songs.into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_saved_items() {
+        let client = fake_client();
+
+        let album = block_on(client.get_album("album0")).unwrap();
+        assert!(!album.description.is_liked);
+        block_on(client.save_album("album0")).unwrap();
+        let saved = block_on(client.get_saved_albums(0, 10)).unwrap();
+        let saved: Vec<String> = saved.into_iter().map(|a| a.id).collect();
+        assert_eq!(saved, vec!["album0", "album1"]);
+
+        block_on(client.save_tracks(vec!["track2".to_string()])).unwrap();
+        assert_eq!(ids(client.saved_tracks()), vec!["track2", "track1"]);
+        block_on(client.remove_saved_tracks(vec!["track1".to_string()])).unwrap();
+        let batch = block_on(client.get_saved_tracks(0, 10)).unwrap();
+        assert_eq!(ids(batch.songs), vec!["track2"]);
+        assert_eq!(batch.batch.total, 1);
+        let contains =
+            block_on(client.contains_saved_tracks(vec!["track1".into(), "track2".into()]));
+        assert_eq!(contains.unwrap(), vec![false, true]);
+    }
+
+    #[test]
+    fn test_followed_artists() {
+        let client = fake_client();
+
+        assert!(!block_on(client.get_artist("artist0")).unwrap().is_followed);
+        let artist = block_on(client.follow_artist("artist0")).unwrap();
+        assert_eq!(artist.name, "Artist");
+        assert!(block_on(client.get_artist("artist0")).unwrap().is_followed);
+
+        let followed = block_on(client.get_followed_artists(None, 10)).unwrap();
+        let followed: Vec<String> = followed.artists.into_iter().map(|a| a.id).collect();
+        assert_eq!(followed, vec!["artist0"]);
+
+        block_on(client.unfollow_artist("artist0")).unwrap();
+        let followed = block_on(client.get_followed_artists(None, 10)).unwrap();
+        assert!(followed.artists.is_empty());
+        assert_eq!(followed.after, None);
+    }
+
+    #[test]
+    fn test_saved_shows() {
+        let client = fake_client().with_show(show(
+            "show0",
+            vec![episode("episode0", "show0"), episode("episode1", "show0")],
+        ));
+
+        let show = block_on(client.get_show("show0")).unwrap();
+        assert!(!show.is_saved);
+        assert_eq!(show.episodes.batch.total, 2);
+        let episodes = block_on(client.get_show_episodes("show0", 1, 10)).unwrap();
+        assert_eq!(episodes.episodes[0].id, "episode1");
+
+        assert!(block_on(client.save_show("show0")).unwrap().is_saved);
+        let saved = block_on(client.get_saved_shows(0, 10)).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].is_saved);
+
+        block_on(client.remove_saved_show("show0")).unwrap();
+        assert!(block_on(client.get_saved_shows(0, 10)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_playlist_edits() {
+        let client = fake_client();
+
+        block_on(client.add_to_playlist(
+            "playlist0",
+            vec![
+                "spotify:track:track1".to_string(),
+                "spotify:track:track2".to_string(),
+            ],
+        ))
+        .unwrap();
+        let snapshot_id =
+            block_on(client.reorder_playlist_tracks("playlist0", 2, 1, 0, None)).unwrap();
+        assert_eq!(snapshot_id, "snapshot2");
+        assert_eq!(
+            ids(client.playlist_songs("playlist0")),
+            vec!["track2", "track0", "track1"]
+        );
+
+        let created = block_on(client.create_playlist("me", "New")).unwrap();
+        let user = block_on(client.get_user("me")).unwrap();
+        assert_eq!(user.playlists.len(), 2);
+        let saved = block_on(client.get_saved_playlists(0, 10)).unwrap();
+        assert_eq!(saved[0].id, created.id);
+    }
+
+    #[test]
+    fn test_scripted_failures() {
+        let client = fake_client();
+        client.update_token("token".to_string());
+        assert_eq!(client.token().as_deref(), Some("token"));
+
+        client.fail_next("search", SpotifyApiError::InvalidToken);
+        let result = block_on(client.search("album", vec![SearchCategory::Albums], 0, 10));
+        assert!(matches!(result, Err(SpotifyApiError::InvalidToken)));
+
+        let results =
+            block_on(client.search("album", vec![SearchCategory::Albums], 0, 10)).unwrap();
+        assert_eq!(results.albums.len(), 2);
+        assert!(results.songs.is_empty());
+
+        let artist = block_on(client.get_artist("artist0")).unwrap();
+        assert_eq!(artist.name, "Artist");
+        assert_eq!(client.calls(), vec!["search", "search", "get_artist"]);
+    }
+}
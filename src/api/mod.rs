@@ -4,8 +4,15 @@ mod client;
 
 pub mod cache;
 
+#[cfg(test)]
+mod fake_client;
+#[cfg(test)]
+mod tests;
+
 pub use cached_client::{CachedSpotifyClient, SpotifyApiClient, SpotifyResult};
 pub use client::SpotifyApiError;
+#[cfg(test)]
+pub use fake_client::FakeSpotifyClient;
 
 pub async fn clear_user_cache() -> Option<()> {
     cache::CacheManager::for_dir("spot/net")?
@@ -0,0 +1,236 @@
+use isahc::http::Uri;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// A tiny HTTP server replaying recorded Web API responses, so that the api module can be tested offline.
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(|s| &s[..])
+    }
+}
+
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+}
+
+struct ServerState {
+    requests: Vec<RecordedRequest>,
+    // responses to serve before the regular fixtures, by path
+    scripted: HashMap<String, VecDeque<MockResponse>>,
+}
+
+pub struct MockServer {
+    base_url: Uri,
+    state: Arc<Mutex<ServerState>>,
+}
+
+fn fixture_for(method: &str, path: &str) -> Option<&'static str> {
+    match (method, path) {
+        ("GET", "/v1/albums/album0") => Some(include_str!("fixtures/album.json")),
+        ("GET", "/v1/me/albums/contains") => Some(include_str!("fixtures/album_saved.json")),
+        ("GET", "/v1/playlists/playlist0") => Some(include_str!("fixtures/playlist.json")),
+        ("PUT", "/v1/playlists/playlist0/tracks")
+        | ("DELETE", "/v1/playlists/playlist0/tracks") => {
+            Some(include_str!("fixtures/playlist_snapshot.json"))
+        }
+        ("GET", "/v1/me/tracks") => Some(include_str!("fixtures/saved_tracks.json")),
+        ("GET", "/v1/tracks/track1") => Some(include_str!("fixtures/track.json")),
+        ("GET", "/v1/search") => Some(include_str!("fixtures/search.json")),
+        _ => None,
+    }
+}
+
+fn etag_for(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("\"{:x}\"", hasher.finish())
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let base_url = format!("http://127.0.0.1:{}", port).parse().unwrap();
+        let state = Arc::new(Mutex::new(ServerState {
+            requests: vec![],
+            scripted: HashMap::new(),
+        }));
+
+        let server_state = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = Self::handle(stream, &server_state);
+            }
+        });
+
+        Self { base_url, state }
+    }
+
+    pub fn base_url(&self) -> Uri {
+        self.base_url.clone()
+    }
+
+    // The next request to that path will get this status instead of the fixture
+    pub fn push_status(&self, path: &str, status: u16, headers: &[(&str, &str)]) {
+        let response = MockResponse {
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..MockResponse::empty(status)
+        };
+        self.state
+            .lock()
+            .unwrap()
+            .scripted
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.path == path)
+            .collect()
+    }
+
+    fn read_request(stream: &mut TcpStream) -> std::io::Result<RecordedRequest> {
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or_default();
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (target.to_string(), None),
+        };
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let len = headers
+            .get("content-length")
+            .and_then(|l| l.parse::<usize>().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body)?;
+
+        Ok(RecordedRequest {
+            method,
+            path,
+            query,
+            headers,
+            body,
+        })
+    }
+
+    fn respond_to(request: &RecordedRequest, state: &mut ServerState) -> MockResponse {
+        let scripted = state
+            .scripted
+            .get_mut(&request.path)
+            .and_then(|responses| responses.pop_front());
+        if let Some(response) = scripted {
+            return response;
+        }
+
+        let content = match fixture_for(&request.method, &request.path) {
+            Some(content) => content,
+            // Mutations just succeed
+            None if request.method != "GET" => return MockResponse::empty(200),
+            None => return MockResponse::empty(404),
+        };
+
+        let etag = etag_for(content);
+        let headers = vec![
+            ("ETag".to_string(), etag.clone()),
+            (
+                "Cache-Control".to_string(),
+                "public, max-age=3600".to_string(),
+            ),
+        ];
+
+        if request.header("if-none-match") == Some(&etag[..]) {
+            MockResponse {
+                headers,
+                ..MockResponse::empty(304)
+            }
+        } else {
+            MockResponse {
+                status: 200,
+                headers,
+                body: content.as_bytes().to_vec(),
+            }
+        }
+    }
+
+    fn handle(mut stream: TcpStream, state: &Mutex<ServerState>) -> std::io::Result<()> {
+        let request = Self::read_request(&mut stream)?;
+        let response = {
+            let mut state = state.lock().unwrap();
+            let response = Self::respond_to(&request, &mut state);
+            state.requests.push(request);
+            response
+        };
+
+        let mut head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+            response.status,
+            response.body.len()
+        );
+        if !response.body.is_empty() {
+            head.push_str("Content-Type: application/json\r\n");
+        }
+        for (name, value) in response.headers.iter() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&response.body)?;
+        stream.flush()
+    }
+}
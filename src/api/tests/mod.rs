@@ -0,0 +1,268 @@
+use futures::executor::block_on;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::cache::CacheManager;
+use super::cached_client::{CachedSpotifyClient, SpotifyApiClient};
+use super::client::{SpotifyApiError, SpotifyClient};
+use crate::app::models::*;
+
+mod mock_server;
+use mock_server::MockServer;
+
+static CACHE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// A cache of its own for each test, removed once the test is over
+struct TempCache {
+    dir: PathBuf,
+    manager: CacheManager,
+}
+
+impl TempCache {
+    fn manager(&self) -> CacheManager {
+        self.manager.clone()
+    }
+}
+
+impl Drop for TempCache {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn temp_cache() -> TempCache {
+    let dir = std::env::temp_dir().join(format!(
+        "spot-tests-{}-{}",
+        std::process::id(),
+        CACHE_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    let manager = CacheManager::for_path(dir.clone()).unwrap();
+    TempCache { dir, manager }
+}
+
+fn client_for(
+    server: &MockServer,
+    cache: CacheManager,
+    token: Option<&str>,
+) -> CachedSpotifyClient {
+    let client =
+        CachedSpotifyClient::with_client(SpotifyClient::with_base_url(server.base_url()), cache);
+    if let Some(token) = token {
+        client.update_token(token.to_string());
+    }
+    client
+}
+
+#[test]
+fn test_get_album() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    let album = block_on(client.get_album("album0")).unwrap();
+    assert_eq!(album.description.title, "Test Album");
+    assert_eq!(album.description.artists_name(), "Test Artist");
+    assert!(album.description.is_liked);
+    assert_eq!(album.release_details.label, "Test Records");
+    let titles: Vec<String> = album
+        .description
+        .songs
+        .songs
+        .iter()
+        .map(|s| s.title.clone())
+        .collect();
+    assert_eq!(titles, vec!["First Track", "Second Track"]);
+
+    let requests = server.requests_to("/v1/albums/album0");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].header("authorization"), Some("Bearer token"));
+}
+
+#[test]
+fn test_get_track() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    let song = block_on(client.get_track("track1")).unwrap();
+    assert_eq!(song.title, "Second Track");
+    assert_eq!(song.track_number, Some(2));
+    assert_eq!(song.disc_number, Some(1));
+    assert_eq!(song.artists_name(), "Guest Artist");
+    assert_eq!(song.album_artists[0].name, "Test Artist");
+
+    let requests = server.requests_to("/v1/tracks/track1");
+    assert_eq!(requests[0].query.as_deref(), Some("market=from_token"));
+}
+
+#[test]
+fn test_fresh_cache_and_revalidation() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    block_on(client.get_album("album0")).unwrap();
+    let album = block_on(client.get_album("album0")).unwrap();
+    assert!(album.description.is_liked);
+
+    // the album itself is still fresh...
+    assert_eq!(server.requests_to("/v1/albums/album0").len(), 1);
+
+    // ...but whether it is saved is always revalidated
+    let requests = server.requests_to("/v1/me/albums/contains");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header("if-none-match"), None);
+    assert!(requests[1].header("if-none-match").is_some());
+}
+
+#[test]
+fn test_etag_not_modified() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    let playlist = block_on(client.get_playlist("playlist0")).unwrap();
+    assert_eq!(playlist.snapshot_id.as_deref(), Some("snapshot0"));
+    // local tracks are skipped
+    assert_eq!(playlist.songs.songs.len(), 2);
+
+    // expires the cached playlist
+    block_on(client.remove_from_playlist("playlist0", vec!["spotify:track:track0".to_string()]))
+        .unwrap();
+
+    let playlist = block_on(client.get_playlist("playlist0")).unwrap();
+    assert_eq!(playlist.title, "Test Playlist");
+    assert_eq!(playlist.songs.songs.len(), 2);
+
+    let requests = server.requests_to("/v1/playlists/playlist0");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header("if-none-match"), None);
+    assert!(requests[1].header("if-none-match").is_some());
+}
+
+fn saved_tracks_requests(server: &MockServer) -> Vec<mock_server::RecordedRequest> {
+    server
+        .requests_to("/v1/me/tracks")
+        .into_iter()
+        .filter(|r| r.method == "GET")
+        .collect()
+}
+
+#[test]
+fn test_cache_policy_without_token() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+    let offline_client = client_for(&server, cache.manager(), None);
+
+    let songs = block_on(client.get_saved_tracks(0, 20)).unwrap();
+    assert_eq!(songs.songs[0].id, "track1");
+
+    // expires saved tracks
+    block_on(client.save_tracks(vec!["track0".to_string()])).unwrap();
+
+    // without a token, whatever is cached is used regardless of its expiry
+    let songs = block_on(offline_client.get_saved_tracks(0, 20)).unwrap();
+    assert_eq!(songs.songs[0].id, "track1");
+    assert_eq!(saved_tracks_requests(&server).len(), 1);
+
+    // nothing cached and no token
+    let result = block_on(offline_client.get_album("album0"));
+    assert!(matches!(result, Err(SpotifyApiError::NoToken)));
+
+    // with a token, the expired entry gets revalidated
+    block_on(client.get_saved_tracks(0, 20)).unwrap();
+    let requests = saved_tracks_requests(&server);
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].header("if-none-match").is_some());
+}
+
+#[test]
+fn test_search() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    let results = block_on(client.search(
+        "test",
+        vec![SearchCategory::Albums, SearchCategory::Songs],
+        0,
+        5,
+    ))
+    .unwrap();
+    assert_eq!(results.albums[0].title, "Test Album");
+    assert_eq!(results.artists[0].name, "Test Artist");
+    assert_eq!(results.songs[0].title, "First Track");
+    assert_eq!(results.playlists[0].description, None);
+
+    let requests = server.requests_to("/v1/search");
+    let query = requests[0].query.as_deref().unwrap_or("");
+    assert!(query.starts_with("type=album,track&q=test"));
+}
+
+#[test]
+fn test_reorder_playlist_tracks() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    let snapshot_id = block_on(client.reorder_playlist_tracks(
+        "playlist0",
+        2,
+        1,
+        0,
+        Some("snapshot0".to_string()),
+    ))
+    .unwrap();
+    assert_eq!(snapshot_id, "snapshot1");
+
+    let requests = server.requests_to("/v1/playlists/playlist0/tracks");
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(
+        requests[0].body,
+        br#"{"range_start":2,"range_length":1,"insert_before":0,"snapshot_id":"snapshot0"}"#
+            .to_vec()
+    );
+}
+
+#[test]
+fn test_rate_limited_retries() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+
+    server.push_status("/v1/search", 429, &[("Retry-After", "0")]);
+    server.push_status("/v1/search", 503, &[("Retry-After", "0")]);
+    let results = block_on(client.search("test", vec![SearchCategory::Albums], 0, 5));
+    assert!(results.is_ok());
+    assert_eq!(server.requests_to("/v1/search").len(), 3);
+
+    for _ in 0..4 {
+        server.push_status("/v1/search", 429, &[("Retry-After", "0")]);
+    }
+    let results = block_on(client.search("test", vec![SearchCategory::Albums], 0, 5));
+    assert!(matches!(results, Err(SpotifyApiError::RateLimited(0))));
+    assert_eq!(server.requests_to("/v1/search").len(), 7);
+}
+
+#[test]
+fn test_no_retries_of_failed_writes() {
+    let server = MockServer::start();
+    let cache = temp_cache();
+    let client = client_for(&server, cache.manager(), Some("token"));
+    let path = "/v1/playlists/playlist0/tracks";
+    let uris = vec!["spotify:track:track1".to_string()];
+
+    server.push_status(path, 500, &[("Retry-After", "0")]);
+    let result = block_on(client.add_to_playlist("playlist0", uris.clone()));
+    assert!(matches!(result, Err(SpotifyApiError::BadStatus(500, _))));
+    assert_eq!(server.requests_to(path).len(), 1);
+
+    // Nothing was done when rate limited, that is still retried
+    server.push_status(path, 429, &[("Retry-After", "0")]);
+    server.push_status(path, 500, &[("Retry-After", "0")]);
+    let result = block_on(client.add_to_playlist("playlist0", uris));
+    assert!(matches!(result, Err(SpotifyApiError::BadStatus(500, _))));
+    assert_eq!(server.requests_to(path).len(), 3);
+}
@@ -1,9 +1,18 @@
 use gettextrs::gettext;
+use rand::seq::SliceRandom;
 use std::sync::Arc;
 
-use crate::api::SpotifyApiClient;
+use crate::api::{SpotifyApiClient, SpotifyApiError, SpotifyResult};
+use crate::app::components::labels;
 use crate::app::models::*;
-use crate::app::AppAction;
+use crate::app::state::PlaybackAction;
+use crate::app::{AppAction, SpotifyUri};
+
+// Albums and playlists are fetched by pages of that size when played from a URI
+const URI_PAGE_SIZE: usize = 50;
+
+// Queuing a huge playlist at once would take forever
+const MAX_QUEUED: usize = 500;
 
 #[derive(Clone)]
 pub struct BatchLoader {
@@ -14,6 +23,7 @@ pub struct BatchLoader {
 pub enum SongsSource {
     Playlist(String),
     Album(String),
+    Show(String),
     SavedTracks,
 }
 
@@ -22,6 +32,7 @@ impl PartialEq for SongsSource {
         match (self, other) {
             (Self::Playlist(l), Self::Playlist(r)) => l == r,
             (Self::Album(l), Self::Album(r)) => l == r,
+            (Self::Show(l), Self::Show(r)) => l == r,
             (Self::SavedTracks, Self::SavedTracks) => true,
             _ => false,
         }
@@ -80,10 +91,22 @@ impl BatchLoader {
                 } = query.batch;
                 api.get_album_tracks(&id, offset, batch_size).await
             }
+            SongsSource::Show(id) => {
+                let Batch {
+                    offset, batch_size, ..
+                } = query.batch;
+                api.get_show_episodes(&id, offset, batch_size)
+                    .await
+                    .map(|episodes| episodes.into())
+            }
         };
 
         match result {
             Ok(batch) => create_action(batch),
+            Err(SpotifyApiError::RateLimited(retry_after)) => {
+                warn!("Rate limited, retry after {} seconds", retry_after);
+                AppAction::ShowNotification(labels::RATE_LIMITED.clone())
+            }
             Err(err) => {
                 error!("Spotify API error: {}", err);
                 AppAction::ShowNotification(gettext(
@@ -93,4 +116,215 @@ impl BatchLoader {
             }
         }
     }
+
+    async fn load_uri(&self, uri: &SpotifyUri<'_>, batch: Batch) -> SpotifyResult<SongBatch> {
+        let Batch {
+            offset, batch_size, ..
+        } = batch;
+        match uri {
+            SpotifyUri::Album(id) => self.api.get_album_tracks(id, offset, batch_size).await,
+            SpotifyUri::Playlist(id) => self.api.get_playlist_tracks(id, offset, batch_size).await,
+            SpotifyUri::Track(id) => {
+                let song = self.api.get_track(id).await?;
+                Ok(SongBatch {
+                    songs: vec![song],
+                    batch: Batch::first_of_size(1),
+                    skipped: vec![],
+                })
+            }
+            SpotifyUri::Artist(id) => {
+                let top_tracks = self.api.get_artist(id).await?.top_tracks;
+                Ok(SongBatch {
+                    batch: Batch {
+                        offset: 0,
+                        batch_size: top_tracks.len(),
+                        total: top_tracks.len(),
+                    },
+                    songs: top_tracks,
+                    skipped: vec![],
+                })
+            }
+            SpotifyUri::Show(_) | SpotifyUri::Episode(_) => self
+                .load_episodes(uri, batch)
+                .await
+                .map(|episodes| episodes.into()),
+            SpotifyUri::User(_) => Err(SpotifyApiError::NoContent),
+        }
+    }
+
+    // Unlike songs, episodes come with where to resume them
+    async fn load_episodes(
+        &self,
+        uri: &SpotifyUri<'_>,
+        batch: Batch,
+    ) -> SpotifyResult<EpisodeBatch> {
+        match uri {
+            SpotifyUri::Show(id) => {
+                self.api
+                    .get_show_episodes(id, batch.offset, batch.batch_size)
+                    .await
+            }
+            SpotifyUri::Episode(id) => {
+                let episode = self.api.get_episode(id).await?;
+                Ok(EpisodeBatch {
+                    episodes: vec![episode],
+                    batch: Batch::first_of_size(1),
+                })
+            }
+            _ => Err(SpotifyApiError::NoContent),
+        }
+    }
+
+    async fn load_first_page(
+        &self,
+        uri: &SpotifyUri<'_>,
+    ) -> SpotifyResult<(SongBatch, Vec<(String, u32)>)> {
+        let batch = Batch::first_of_size(URI_PAGE_SIZE);
+        match uri {
+            SpotifyUri::Show(_) | SpotifyUri::Episode(_) => {
+                let episodes = self.load_episodes(uri, batch).await?;
+                let resume_positions = episodes
+                    .episodes
+                    .iter()
+                    .filter_map(|e| Some((e.id.clone(), e.resume_position?)))
+                    .collect();
+                Ok((episodes.into(), resume_positions))
+            }
+            _ => Ok((self.load_uri(uri, batch).await?, vec![])),
+        }
+    }
+
+    // All the songs a URI stands for: a track or an episode, the tracks of an album or playlist, the episodes of a show, or the top tracks of an artist
+    pub async fn load_all_uri(&self, uri: &SpotifyUri<'_>) -> SpotifyResult<Vec<SongDescription>> {
+        let mut songs = vec![];
+        let mut batch = Some(Batch::first_of_size(URI_PAGE_SIZE));
+        while let Some(current) = batch {
+            let page = self.load_uri(uri, current).await?;
+            songs.extend(page.songs);
+            batch = page.batch.next().filter(|_| songs.len() < MAX_QUEUED);
+        }
+        Ok(songs)
+    }
+
+    pub async fn load_all_uris(
+        &self,
+        uris: &[SpotifyUri<'_>],
+    ) -> SpotifyResult<Vec<SongDescription>> {
+        let mut songs = vec![];
+        for uri in uris {
+            songs.extend(self.load_all_uri(uri).await?);
+        }
+        Ok(songs)
+    }
+
+    // Resolves to the id of the song that will start playing, and the actions replacing the queue with the songs of the URI.
+    // Shuffling is left as is unless specified.
+    pub async fn play_uri(
+        &self,
+        uri: &SpotifyUri<'_>,
+        shuffled: Option<bool>,
+    ) -> SpotifyResult<(String, Vec<AppAction>)> {
+        let (first_page, resume_positions) = self.load_first_page(uri).await?;
+
+        let first = if shuffled == Some(true) {
+            first_page.songs.choose(&mut rand::thread_rng())
+        } else {
+            first_page.songs.first()
+        };
+        let first = first
+            .map(|song| song.id.clone())
+            .ok_or(SpotifyApiError::NoContent)?;
+
+        let load = match uri.songs_source() {
+            Some(source) => PlaybackAction::LoadPagedSongs(source, first_page),
+            None => PlaybackAction::LoadSongs(first_page.songs),
+        };
+        let mut actions: Vec<AppAction> = vec![];
+        if !resume_positions.is_empty() {
+            actions.push(PlaybackAction::SeedResumePositions(resume_positions).into());
+        }
+        actions.push(load.into());
+        if let Some(shuffled) = shuffled {
+            actions.push(PlaybackAction::SetShuffled(shuffled).into());
+        }
+        actions.push(PlaybackAction::Load(first.clone()).into());
+        Ok((first, actions))
+    }
+
+    // Tracks and episodes are played right away, anything else is shown
+    pub async fn open_uri(&self, uri: &SpotifyUri<'_>) -> SpotifyResult<Vec<AppAction>> {
+        match uri {
+            SpotifyUri::Track(_) | SpotifyUri::Episode(_) => Ok(self.play_uri(uri, None).await?.1),
+            _ => Ok(AppAction::View(uri).into_iter().collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::*;
+
+    #[test]
+    fn test_play_uri() {
+        let api = FakeSpotifyClient::new().with_album(album("album0", vec![song("1"), song("2")]));
+        let loader = BatchLoader::new(Arc::new(api));
+
+        let uri = SpotifyUri::parse("spotify:album:album0").unwrap();
+        let (first, actions) = block_on(loader.play_uri(&uri, None)).unwrap();
+        assert_eq!(first, "1");
+        assert!(matches!(
+            &actions[0],
+            AppAction::PlaybackAction(PlaybackAction::LoadPagedSongs(SongsSource::Album(id), _)) if id == "album0"
+        ));
+
+        let songs = block_on(loader.load_all_uri(&uri)).unwrap();
+        assert_eq!(songs.len(), 2);
+    }
+
+    #[test]
+    fn test_open_track_uri() {
+        let api = FakeSpotifyClient::new().with_album(album("album0", vec![song("1"), song("2")]));
+        let loader = BatchLoader::new(Arc::new(api));
+
+        let uri = SpotifyUri::parse("https://open.spotify.com/track/2?si=abc").unwrap();
+        let actions = block_on(loader.open_uri(&uri)).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(
+            &actions[0],
+            AppAction::PlaybackAction(PlaybackAction::LoadSongs(songs)) if songs[0].id == "2"
+        ));
+        assert!(matches!(
+            &actions[1],
+            AppAction::PlaybackAction(PlaybackAction::Load(id)) if id == "2"
+        ));
+    }
+
+    #[test]
+    fn test_open_episode_uri() {
+        let mut episode0 = episode("episode0", "show0");
+        episode0.resume_position = Some(30_000);
+        let api = FakeSpotifyClient::new().with_show(show("show0", vec![episode0]));
+        let loader = BatchLoader::new(Arc::new(api));
+
+        let uri = SpotifyUri::parse("https://open.spotify.com/episode/episode0").unwrap();
+        let actions = block_on(loader.open_uri(&uri)).unwrap();
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(
+            &actions[0],
+            AppAction::PlaybackAction(PlaybackAction::SeedResumePositions(positions))
+                if positions == &vec![("episode0".to_string(), 30_000)]
+        ));
+        assert!(matches!(
+            &actions[1],
+            AppAction::PlaybackAction(PlaybackAction::LoadSongs(songs)) if songs[0].uri == "spotify:episode:episode0"
+        ));
+        assert!(matches!(
+            &actions[2],
+            AppAction::PlaybackAction(PlaybackAction::Load(id)) if id == "episode0"
+        ));
+    }
 }
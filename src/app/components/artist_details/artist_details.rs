@@ -1,13 +1,15 @@
+use gettextrs::gettext;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::CompositeTemplate;
 use std::rc::Rc;
 
+use crate::app::components::share::bind_share_button;
 use crate::app::components::{
     display_add_css_provider, AlbumWidget, Component, EventListener, Playlist,
 };
 use crate::app::{models::*, ListStore};
-use crate::app::{AppEvent, BrowserEvent, Worker};
+use crate::app::{AppEvent, BrowserEvent, SpotifyUri, Worker};
 
 use super::ArtistDetailsModel;
 
@@ -21,6 +23,12 @@ mod imp {
         #[template_child]
         pub scrolled_window: TemplateChild<gtk::ScrolledWindow>,
 
+        #[template_child]
+        pub follow_button: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        pub share_button: TemplateChild<gtk::MenuButton>,
+
         #[template_child]
         pub top_tracks: TemplateChild<gtk::ListView>,
 
@@ -67,6 +75,30 @@ impl ArtistDetailsWidget {
         context.add_class("artist__loaded");
     }
 
+    fn set_followed(&self, is_followed: bool) {
+        let button = &self.imp().follow_button;
+        button.set_sensitive(true);
+        button.set_active(is_followed);
+        button.set_label(&if is_followed {
+            // translators: This is the label of a button that unfollows an artist (the artist is currently followed).
+            gettext("Following")
+        } else {
+            // translators: This is the label of a button that follows an artist.
+            gettext("Follow")
+        });
+    }
+
+    fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        bind_share_button(&self.imp().share_button, uri);
+    }
+
+    fn connect_follow<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp().follow_button.connect_clicked(move |_| f());
+    }
+
     fn connect_bottom_edge<F>(&self, f: F)
     where
         F: Fn() + 'static,
@@ -115,11 +147,18 @@ impl ArtistDetails {
         model.load_artist_details(model.id.clone());
 
         let widget = ArtistDetailsWidget::new();
+        widget.set_share_uri(&SpotifyUri::Artist(&model.id));
 
         widget.connect_bottom_edge(clone!(@weak model => move || {
             model.load_more();
         }));
 
+        widget.connect_follow(clone!(@weak model, @weak widget => move || {
+            // Only reflects the new state once Spotify has confirmed it
+            widget.set_followed(model.is_followed());
+            model.toggle_follow();
+        }));
+
         if let Some(store) = model.get_list_store() {
             widget.bind_artist_releases(
                 worker.clone(),
@@ -161,6 +200,13 @@ impl EventListener for ArtistDetails {
                 if id == &self.model.id =>
             {
                 self.widget.set_loaded();
+                self.widget.set_followed(self.model.is_followed());
+            }
+            AppEvent::BrowserEvent(BrowserEvent::ArtistFollowed(id))
+            | AppEvent::BrowserEvent(BrowserEvent::ArtistUnfollowed(id))
+                if id == &self.model.id =>
+            {
+                self.widget.set_followed(self.model.is_followed());
             }
             _ => {}
         }
@@ -5,7 +5,7 @@ use std::rc::Rc;
 
 use crate::api::SpotifyApiError;
 use crate::app::components::SimpleHeaderBarModel;
-use crate::app::components::{labels, PlaylistModel};
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
 use crate::app::models::*;
 use crate::app::state::SelectionContext;
 use crate::app::state::{
@@ -54,6 +54,34 @@ impl ArtistDetailsModel {
             });
     }
 
+    pub fn is_followed(&self) -> bool {
+        self.app_model
+            .get_state()
+            .browser
+            .artist_state(&self.id)
+            .map(|s| s.is_followed)
+            .unwrap_or(false)
+    }
+
+    pub fn toggle_follow(&self) {
+        let id = self.id.clone();
+        let is_followed = self.is_followed();
+        let api = self.app_model.get_spotify();
+
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                if !is_followed {
+                    api.follow_artist(&id)
+                        .await
+                        .map(|artist| BrowserAction::FollowArtist(Box::new(artist)).into())
+                } else {
+                    api.unfollow_artist(&id)
+                        .await
+                        .map(|_| BrowserAction::UnfollowArtist(id).into())
+                }
+            });
+    }
+
     pub fn open_album(&self, id: String) {
         self.dispatcher.dispatch(AppAction::ViewAlbum(id));
     }
@@ -101,6 +129,10 @@ impl PlaylistModel for ArtistDetailsModel {
             .dispatch(PlaybackAction::Load(id.to_string()).into());
     }
 
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
     fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
         let song = self.song_list_model().get(id)?;
         let song = song.description();
@@ -112,6 +144,11 @@ impl PlaylistModel for ArtistDetailsModel {
         }
         group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
         group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
         group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
 
         Some(group.upcast())
@@ -131,6 +168,7 @@ impl PlaylistModel for ArtistDetailsModel {
         }
 
         menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
         menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
         Some(menu.upcast())
     }
@@ -1,4 +1,6 @@
 use crate::app::components::display_add_css_provider;
+use crate::app::components::share::bind_share_button;
+use crate::app::SpotifyUri;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{glib, CompositeTemplate};
@@ -22,6 +24,9 @@ mod imp {
         #[template_child]
         pub info_button: TemplateChild<gtk::Button>,
 
+        #[template_child]
+        pub share_button: TemplateChild<gtk::MenuButton>,
+
         #[template_child]
         pub album_info: TemplateChild<gtk::Box>,
 
@@ -97,6 +102,10 @@ impl AlbumHeaderWidget {
         });
     }
 
+    pub fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        bind_share_button(&self.imp().share_button, uri);
+    }
+
     pub fn set_artwork(&self, art: &gdk_pixbuf::Pixbuf) {
         self.imp().album_art.set_from_pixbuf(Some(art));
     }
@@ -119,6 +128,10 @@ impl AlbumHeaderWidget {
         widget.year_label.set_halign(gtk::Align::Center);
     }
 
+    pub fn hide_info(&self) {
+        self.imp().info_button.set_visible(false);
+    }
+
     pub fn hide_actions(&self) {
         self.imp().like_button.set_visible(false);
         self.imp().info_button.set_visible(false);
@@ -12,7 +12,7 @@ use crate::app::components::{
 };
 use crate::app::dispatch::Worker;
 use crate::app::loader::ImageLoader;
-use crate::app::{AppEvent, BrowserEvent};
+use crate::app::{AppEvent, BrowserEvent, SpotifyUri};
 
 mod imp {
 
@@ -174,6 +174,11 @@ impl AlbumDetailsWidget {
         self.imp().headerbar.set_title_and_subtitle(album, artist);
     }
 
+    fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        self.imp().header_widget.set_share_uri(uri);
+        self.imp().header_mobile.set_share_uri(uri);
+    }
+
     fn set_artwork(&self, art: &gdk_pixbuf::Pixbuf) {
         self.imp().header_widget.set_artwork(art);
         self.imp().header_mobile.set_artwork(art);
@@ -222,6 +227,7 @@ impl Details {
         widget.connect_liked(clone!(@weak model => move || model.toggle_save_album()));
 
         widget.connect_header_visibility();
+        widget.set_share_uri(&SpotifyUri::Album(&model.id));
 
         widget.connect_bottom_edge(clone!(@weak model => move || {
             model.load_more();
@@ -8,6 +8,7 @@ use crate::api::SpotifyApiError;
 use crate::app::components::labels;
 use crate::app::components::HeaderBarModel;
 use crate::app::components::PlaylistModel;
+use crate::app::components::SavedTracksToggle;
 use crate::app::components::SimpleHeaderBarModel;
 use crate::app::components::SimpleHeaderBarModelWrapper;
 use crate::app::dispatch::ActionDispatcher;
@@ -184,6 +185,10 @@ impl PlaylistModel for DetailsModel {
         }
     }
 
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
     fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
         let song = self.song_list_model().get(id)?;
         let song = song.description();
@@ -194,6 +199,11 @@ impl PlaylistModel for DetailsModel {
             group.add_action(&view_artist);
         }
         group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
         group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
 
         Some(group.upcast())
@@ -212,6 +222,7 @@ impl PlaylistModel for DetailsModel {
         }
 
         menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
         menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
         Some(menu.upcast())
     }
@@ -0,0 +1,103 @@
+use std::cell::Ref;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::app::models::*;
+use crate::app::state::HomeState;
+use crate::app::{ActionDispatcher, AppAction, AppModel, BrowserAction, ListStore};
+
+const PAGE_SIZE: usize = 30;
+
+pub struct FollowedArtistsModel {
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl FollowedArtistsModel {
+    pub fn new(app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            app_model,
+            dispatcher,
+        }
+    }
+
+    fn state(&self) -> Option<Ref<'_, HomeState>> {
+        self.app_model.map_state_opt(|s| s.browser.home_state())
+    }
+
+    pub fn get_list_store(&self) -> Option<impl Deref<Target = ListStore<ArtistModel>> + '_> {
+        Some(Ref::map(self.state()?, |s| &s.followed_artists))
+    }
+
+    pub fn refresh_followed_artists(&self) {
+        let api = self.app_model.get_spotify();
+
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                api.get_followed_artists(None, PAGE_SIZE)
+                    .await
+                    .map(|artists| BrowserAction::SetFollowedArtists(Box::new(artists)).into())
+            });
+    }
+
+    pub fn has_artists(&self) -> bool {
+        self.get_list_store()
+            .map(|list| list.len() > 0)
+            .unwrap_or(false)
+    }
+
+    pub fn load_more_artists(&self) -> Option<()> {
+        let api = self.app_model.get_spotify();
+        let after = self.state()?.next_followed_artists.clone()?;
+
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                api.get_followed_artists(Some(after), PAGE_SIZE)
+                    .await
+                    .map(|artists| BrowserAction::AppendFollowedArtists(Box::new(artists)).into())
+            });
+
+        Some(())
+    }
+
+    pub fn open_artist(&self, id: String) {
+        self.dispatcher.dispatch(AppAction::ViewArtist(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::{user, TestApp};
+
+    fn artist(id: &str) -> ArtistDescription {
+        ArtistDescription {
+            id: id.to_string(),
+            name: format!("Artist {}", id),
+            albums: vec![],
+            top_tracks: vec![],
+            is_followed: false,
+        }
+    }
+
+    #[test]
+    fn test_load_more_artists() {
+        let api = (0..40).fold(FakeSpotifyClient::new().with_user(user("me")), |api, i| {
+            let id = format!("artist{}", i);
+            api.with_artist(artist(&id)).with_followed_artist(&id)
+        });
+        let app = TestApp::logged_in(api, "me");
+        let model = FollowedArtistsModel::new(app.model.clone(), app.dispatcher());
+
+        model.refresh_followed_artists();
+        app.run_until_idle();
+        assert_eq!(model.get_list_store().unwrap().len(), 30);
+
+        assert!(model.load_more_artists().is_some());
+        app.run_until_idle();
+        assert_eq!(model.get_list_store().unwrap().len(), 40);
+        assert!(model.load_more_artists().is_none());
+    }
+}
@@ -0,0 +1,5 @@
+mod followed_artists;
+mod followed_artists_model;
+
+pub use followed_artists::*;
+pub use followed_artists_model::*;
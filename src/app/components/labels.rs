@@ -4,14 +4,41 @@ lazy_static! {
     // translators: This is part of a contextual menu attached to a single track; this entry allows viewing the album containing a specific track.
     pub static ref VIEW_ALBUM: String = gettext("View album");
 
+    // translators: This is part of a contextual menu attached to a single podcast episode in the play queue; this entry allows viewing the show the episode belongs to.
+    pub static ref VIEW_SHOW: String = gettext("View show");
+
     // translators: This is part of a contextual menu attached to a single track; the intent is to copy the link (public URL) to a specific track.
     pub static ref COPY_LINK: String = gettext("Copy link");
 
+    // translators: This is part of a menu to share an album, playlist, artist or user; the intent is to copy its Spotify URI (spotify:album:...) rather than its link.
+    pub static ref COPY_URI: String = gettext("Copy Spotify URI");
+
+    // translators: This is part of a menu in selection mode; this entry copies the links to the selected tracks, one per line.
+    pub static ref COPY_LINKS: String = gettext("Copy links");
+
+    // translators: This is part of a menu in selection mode; this entry copies the selected tracks as "Artist – Title", one per line.
+    pub static ref COPY_AS_TEXT: String = gettext("Copy as text");
+
     // translators: This is part of a contextual menu attached to a single track; this entry adds a track at the end of the play queue.
     pub static ref ADD_TO_QUEUE: String = gettext("Add to queue");
 
+    // translators: This is part of a contextual menu attached to a single track; this entry plays the track followed by similar ones.
+    pub static ref START_RADIO: String = gettext("Start radio");
+
     // translators: This is part of a contextual menu attached to a single track; this entry removes a track from the play queue.
     pub static ref REMOVE_FROM_QUEUE: String = gettext("Remove from queue");
+
+    // translators: This is the tooltip of the heart shown on each track that is not in the user's library yet.
+    pub static ref SAVE_TRACK: String = gettext("Save to library");
+
+    // translators: This is the tooltip of the heart shown on each track that is already in the user's library.
+    pub static ref UNSAVE_TRACK: String = gettext("Remove from library");
+
+    // translators: This is the first entry of the lists of audio outputs (in the settings and the playback bar); it stands for whatever output the system uses by default.
+    pub static ref DEFAULT_OUTPUT: String = gettext("Default output");
+
+    // translators: This notification shows up when Spotify refuses requests because too many were made in a short time.
+    pub static ref RATE_LIMITED: String = gettext("Spotify is limiting requests, please try again in a moment");
 }
 
 pub fn add_to_playlist_label(playlist: &str) -> String {
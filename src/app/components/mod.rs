@@ -58,6 +58,21 @@ pub use now_playing::*;
 mod saved_tracks;
 pub use saved_tracks::*;
 
+mod followed_artists;
+pub use followed_artists::*;
+
+mod saved_shows;
+pub use saved_shows::*;
+
+mod show_details;
+pub use show_details::*;
+
+mod recently_played;
+pub use recently_played::*;
+
+mod top_items;
+pub use top_items::*;
+
 mod user_menu;
 pub use user_menu::*;
 
@@ -79,6 +94,7 @@ pub use selection::*;
 mod headerbar;
 pub use headerbar::*;
 
+pub mod share;
 pub mod utils;
 
 pub mod labels;
@@ -91,7 +107,7 @@ pub fn expose_widgets() {
 }
 
 impl dyn ActionDispatcher {
-    fn call_spotify_and_dispatch<F, C>(&self, call: C)
+    pub(crate) fn call_spotify_and_dispatch<F, C>(&self, call: C)
     where
         C: 'static + Send + Clone + FnOnce() -> F,
         F: Send + Future<Output = Result<AppAction, SpotifyApiError>>,
@@ -99,7 +115,7 @@ impl dyn ActionDispatcher {
         self.call_spotify_and_dispatch_many(move || async { call().await.map(|a| vec![a]) })
     }
 
-    fn call_spotify_and_dispatch_many<F, C>(&self, call: C)
+    pub(crate) fn call_spotify_and_dispatch_many<F, C>(&self, call: C)
     where
         C: 'static + Send + Clone + FnOnce() -> F,
         F: Send + Future<Output = Result<Vec<AppAction>, SpotifyApiError>>,
@@ -110,6 +126,10 @@ impl dyn ActionDispatcher {
             match result {
                 Ok(actions) => actions,
                 Err(SpotifyApiError::NoToken) => vec![],
+                Err(SpotifyApiError::RateLimited(retry_after)) => {
+                    warn!("Rate limited, retry after {} seconds", retry_after);
+                    vec![AppAction::ShowNotification(labels::RATE_LIMITED.clone())]
+                }
                 Err(SpotifyApiError::InvalidToken) => {
                     let mut retried = call().await.unwrap_or_else(|_| Vec::new());
                     retried.push(LoginAction::RefreshToken.into());
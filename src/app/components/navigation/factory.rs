@@ -95,6 +95,73 @@ impl ScreenFactory {
         )
     }
 
+    pub fn make_followed_artists(&self) -> impl ListenerComponent {
+        let model =
+            FollowedArtistsModel::new(Rc::clone(&self.app_model), self.dispatcher.box_clone());
+        let screen_model = DefaultHeaderBarModel::new(
+            Some(gettext("Followed artists")),
+            None,
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        );
+        StandardScreen::new(
+            FollowedArtists::new(self.worker.clone(), model),
+            &self.leaflet,
+            Rc::new(screen_model),
+        )
+    }
+
+    pub fn make_saved_shows(&self) -> impl ListenerComponent {
+        let model = SavedShowsModel::new(Rc::clone(&self.app_model), self.dispatcher.box_clone());
+        let screen_model = DefaultHeaderBarModel::new(
+            Some(gettext("Podcasts")),
+            None,
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        );
+        StandardScreen::new(
+            SavedShows::new(self.worker.clone(), model),
+            &self.leaflet,
+            Rc::new(screen_model),
+        )
+    }
+
+    pub fn make_recently_played(&self) -> impl ListenerComponent {
+        let screen_model = DefaultHeaderBarModel::new(
+            Some(gettext("Recently played")),
+            Some(SelectionContext::Default),
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        );
+        let model = Rc::new(RecentlyPlayedModel::new(
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        ));
+        StandardScreen::new(
+            RecentlyPlayed::new(model, self.worker.clone()),
+            &self.leaflet,
+            Rc::new(screen_model),
+        )
+    }
+
+    pub fn make_top_items(&self) -> impl ListenerComponent {
+        let screen_model = DefaultHeaderBarModel::new(
+            Some(gettext("Top tracks and artists")),
+            Some(SelectionContext::Default),
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        );
+        let model = Rc::new(TopItemsModel::new(
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        ));
+        StandardScreen::new(
+            TopItems::new(model, self.worker.clone()),
+            &self.leaflet,
+            Rc::new(screen_model),
+        )
+    }
+
     pub fn make_album_details(&self, id: String) -> impl ListenerComponent {
         let model = Rc::new(DetailsModel::new(
             id,
@@ -104,6 +171,15 @@ impl ScreenFactory {
         Details::new(model, self.worker.clone(), &self.leaflet)
     }
 
+    pub fn make_show_details(&self, id: String) -> impl ListenerComponent {
+        let model = Rc::new(ShowDetailsModel::new(
+            id,
+            Rc::clone(&self.app_model),
+            self.dispatcher.box_clone(),
+        ));
+        ShowDetails::new(model, self.worker.clone(), &self.leaflet)
+    }
+
     pub fn make_search_results(&self) -> impl ListenerComponent {
         let model =
             SearchResultsModel::new(Rc::clone(&self.app_model), self.dispatcher.box_clone());
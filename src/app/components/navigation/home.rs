@@ -4,13 +4,18 @@ use gtk::prelude::*;
 use crate::app::components::sidebar_listbox::{SideBarItem, SideBarRow};
 use crate::app::components::{Component, EventListener, SavedPlaylistsModel, ScreenFactory};
 use crate::app::models::AlbumModel;
+use crate::app::state::ScreenName;
 use crate::app::{AppEvent, BrowserEvent};
 
 const LIBRARY: &str = "library";
 const SAVED_TRACKS: &str = "saved_tracks";
+const FOLLOWED_ARTISTS: &str = "followed_artists";
+const SAVED_SHOWS: &str = "saved_shows";
 const NOW_PLAYING: &str = "now_playing";
+const RECENTLY_PLAYED: &str = "recently_played";
+const TOP_ITEMS: &str = "top_items";
 const SAVED_PLAYLISTS: &str = "saved_playlists";
-const NUM_FIXED_ENTRIES: u32 = 5;
+const NUM_FIXED_ENTRIES: u32 = 9;
 const NUM_PLAYLISTS: usize = 20;
 
 fn add_to_stack_and_listbox(
@@ -54,6 +59,8 @@ impl HomePane {
         let library = screen_factory.make_library();
         let saved_playlists = screen_factory.make_saved_playlists();
         let saved_tracks = screen_factory.make_saved_tracks();
+        let followed_artists = screen_factory.make_followed_artists();
+        let saved_shows = screen_factory.make_saved_shows();
         let now_playing = screen_factory.make_now_playing();
 
         let saved_playlists_model = screen_factory.make_saved_playlists_model();
@@ -79,6 +86,26 @@ impl HomePane {
             "starred-symbolic",
             false,
         );
+        add_to_stack_and_listbox(
+            &stack,
+            &list_store,
+            followed_artists.get_root_widget(),
+            FOLLOWED_ARTISTS,
+            // translators: This is a sidebar entry to browse to the artists the user follows.
+            &gettext("Followed artists"),
+            "avatar-default-symbolic",
+            false,
+        );
+        add_to_stack_and_listbox(
+            &stack,
+            &list_store,
+            saved_shows.get_root_widget(),
+            SAVED_SHOWS,
+            // translators: This is a sidebar entry to browse to the podcasts the user saved.
+            &gettext("Podcasts"),
+            "audio-input-microphone-symbolic",
+            false,
+        );
         add_to_stack_and_listbox(
             &stack,
             &list_store,
@@ -88,6 +115,21 @@ impl HomePane {
             "music-queue-symbolic",
             false,
         );
+        // These two open a screen of their own rather than a page of the home stack
+        list_store.append(&SideBarItem::new(
+            RECENTLY_PLAYED,
+            // translators: This is a sidebar entry to browse to the tracks the user played recently.
+            &gettext("Recently played"),
+            "document-open-recent-symbolic",
+            false,
+        ));
+        list_store.append(&SideBarItem::new(
+            TOP_ITEMS,
+            // translators: This is a sidebar entry to browse to the tracks and artists the user listens to the most.
+            &gettext("Top tracks and artists"),
+            "view-sort-descending-symbolic",
+            false,
+        ));
         list_store.append(&SideBarItem::new(
             SAVED_PLAYLISTS,
             // translators: This is a sidebar entry that marks that the entries below are playlists.
@@ -114,22 +156,31 @@ impl HomePane {
                 Box::new(library),
                 Box::new(saved_playlists),
                 Box::new(saved_tracks),
+                Box::new(followed_artists),
+                Box::new(saved_shows),
                 Box::new(now_playing),
             ],
             saved_playlists_model,
         }
     }
 
-    pub fn connect_navigated<F: Fn() + 'static>(&self, f: F) {
+    pub fn connect_navigated<F, G>(&self, f: F, on_screen: G)
+    where
+        F: Fn() + 'static,
+        G: Fn(ScreenName) + 'static,
+    {
         let model = self.saved_playlists_model.clone();
         self.listbox
             .connect_row_activated(clone!(@weak self.stack as stack => move |_, row| {
                 let id = row.downcast_ref::<SideBarRow>().unwrap().id();
                 match id.as_str() {
-                    LIBRARY | SAVED_TRACKS | NOW_PLAYING | SAVED_PLAYLISTS => {
+                    LIBRARY | SAVED_TRACKS | FOLLOWED_ARTISTS | SAVED_SHOWS | NOW_PLAYING
+                    | SAVED_PLAYLISTS => {
                         stack.set_visible_child_name(&id);
                         f();
                     },
+                    RECENTLY_PLAYED => on_screen(ScreenName::RecentlyPlayed),
+                    TOP_ITEMS => on_screen(ScreenName::TopItems),
                     _ => model.open_playlist(id),
                 }
             }));
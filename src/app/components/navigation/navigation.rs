@@ -68,6 +68,10 @@ impl Navigation {
                 leaflet.navigate(NavigationDirection::Forward);
                 model.go_home();
             }),
+            clone!(@weak self.model as model, @weak self.leaflet as leaflet => move |name| {
+                leaflet.navigate(NavigationDirection::Forward);
+                model.open_screen(name);
+            }),
         );
 
         Box::new(home)
@@ -91,6 +95,11 @@ impl Navigation {
                 Box::new(self.screen_factory.make_playlist_details(id.to_owned()))
             }
             ScreenName::User(id) => Box::new(self.screen_factory.make_user_details(id.to_owned())),
+            ScreenName::RecentlyPlayed => Box::new(self.screen_factory.make_recently_played()),
+            ScreenName::TopItems => Box::new(self.screen_factory.make_top_items()),
+            ScreenName::ShowDetails(id) => {
+                Box::new(self.screen_factory.make_show_details(id.to_owned()))
+            }
         };
 
         let widget = component.get_root_widget().clone();
@@ -21,6 +21,13 @@ impl NavigationModel {
             .dispatch(BrowserAction::NavigationPopTo(ScreenName::Home).into())
     }
 
+    // Screens opened from the sidebar replace whatever was browsed from home
+    pub fn open_screen(&self, name: ScreenName) {
+        self.go_home();
+        self.dispatcher
+            .dispatch(BrowserAction::NavigationPush(name).into())
+    }
+
     pub fn visible_child_name(&self) -> impl Deref<Target = ScreenName> + '_ {
         self.app_model.map_state(|s| s.browser.current_screen())
     }
@@ -4,6 +4,7 @@ use gtk::CompositeTemplate;
 use std::rc::Rc;
 
 use super::NowPlayingModel;
+use crate::app::components::utils::{links_drop_target, paste_links_controller};
 use crate::app::components::{Component, EventListener, Playlist};
 use crate::app::{state::PlaybackEvent, AppEvent, Worker};
 use libadwaita::subclass::prelude::BinImpl;
@@ -83,6 +84,17 @@ impl NowPlaying {
             model.load_more();
         }));
 
+        // Links pasted or dropped here are queued rather than opened
+        widget.add_controller(links_drop_target(
+            clone!(@weak model => @default-return false, move |text| model.queue_links(text)),
+        ));
+        widget.add_controller(paste_links_controller(
+            gtk::ShortcutScope::Managed,
+            clone!(@weak model => move |text| {
+                model.queue_links(text);
+            }),
+        ));
+
         let playlist = Playlist::new(widget.song_list_widget().clone(), model.clone(), worker);
 
         Self {
@@ -107,6 +119,7 @@ impl EventListener for NowPlaying {
     fn on_event(&mut self, event: &AppEvent) {
         if let AppEvent::PlaybackEvent(PlaybackEvent::TrackChanged(_)) = event {
             self.model.load_more();
+            self.model.autoplay();
         }
         self.broadcast_event(event);
     }
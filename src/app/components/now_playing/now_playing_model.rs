@@ -1,16 +1,21 @@
 use gettextrs::gettext;
 use gio::prelude::*;
 use gio::SimpleActionGroup;
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::rc::Rc;
 
 use crate::app::components::SimpleHeaderBarModel;
-use crate::app::components::{labels, PlaylistModel};
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
 use crate::app::models::SongDescription;
 use crate::app::models::SongListModel;
 use crate::app::state::SelectionContext;
 use crate::app::state::{PlaybackAction, PlaybackState, SelectionAction, SelectionState};
-use crate::app::{ActionDispatcher, AppAction, AppEvent, AppModel};
+use crate::app::{ActionDispatcher, AppAction, AppEvent, AppModel, SpotifyUri};
+
+// Spotify doesn't take more than 5 seeds
+const AUTOPLAY_SEEDS: usize = 5;
+const AUTOPLAY_SIZE: usize = 20;
 
 pub struct NowPlayingModel {
     app_model: Rc<AppModel>,
@@ -47,6 +52,78 @@ impl NowPlayingModel {
 
         Some(())
     }
+
+    // Queues songs similar to the last ones played once the queue is about to run out
+    pub fn autoplay(&self) -> Option<()> {
+        let state = self.app_model.get_state();
+        if !state.settings.settings.autoplay || !state.playback.is_playing_last() {
+            return None;
+        }
+
+        let seed_tracks: Vec<String> = state
+            .playback
+            .played_songs()
+            // Episodes can't seed recommendations
+            .filter(|s| !s.is_episode())
+            .take(AUTOPLAY_SEEDS)
+            .map(|s| s.id)
+            .collect();
+        if seed_tracks.is_empty() {
+            return None;
+        }
+        // Anything still in the queue was played recently enough not to be repeated
+        let queued: HashSet<String> = state
+            .playback
+            .songs()
+            .collect()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+
+        let api = self.app_model.get_spotify();
+        self.dispatcher
+            .call_spotify_and_dispatch_many(move || async move {
+                api.get_recommendations(seed_tracks, vec![], AUTOPLAY_SIZE)
+                    .await
+                    .map(|songs| {
+                        let mut seen = queued;
+                        let songs: Vec<SongDescription> = songs
+                            .into_iter()
+                            .filter(|s| seen.insert(s.id.clone()))
+                            .collect();
+                        if songs.is_empty() {
+                            vec![]
+                        } else {
+                            vec![PlaybackAction::Queue(songs).into()]
+                        }
+                    })
+            });
+
+        Some(())
+    }
+
+    // Appends whatever the pasted or dropped links stand for to the queue
+    pub fn queue_links(&self, text: &str) -> bool {
+        let uris: Vec<String> = SpotifyUri::parse_all(text)
+            .iter()
+            .map(|uri| uri.to_string())
+            .collect();
+        if uris.is_empty() {
+            return false;
+        }
+
+        let loader = self.app_model.get_batch_loader();
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                let uris: Vec<SpotifyUri> = uris
+                    .iter()
+                    .filter_map(|uri| SpotifyUri::parse(uri))
+                    .collect();
+                let songs = loader.load_all_uris(&uris).await?;
+                Ok(PlaybackAction::Queue(songs).into())
+            });
+        true
+    }
 }
 
 impl PlaylistModel for NowPlayingModel {
@@ -67,6 +144,10 @@ impl PlaylistModel for NowPlayingModel {
         false // too buggy for now
     }
 
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
     fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
         let queue = self.queue();
         let song = queue.songs().get(id)?;
@@ -89,7 +170,11 @@ impl PlaylistModel for NowPlayingModel {
         let song = song.description();
 
         let menu = gio::Menu::new();
-        menu.append(Some(&*labels::VIEW_ALBUM), Some("song.view_album"));
+        if song.is_episode() {
+            menu.append(Some(&*labels::VIEW_SHOW), Some("song.view_album"));
+        } else {
+            menu.append(Some(&*labels::VIEW_ALBUM), Some("song.view_album"));
+        }
         for artist in song.artists.iter() {
             menu.append(
                 Some(&labels::more_from_label(&artist.name)),
@@ -127,6 +212,15 @@ impl PlaylistModel for NowPlayingModel {
         let selection = self.app_model.map_state(|s| &s.selection);
         Some(Box::new(selection))
     }
+
+    fn can_reorder(&self) -> bool {
+        !self.queue().is_shuffled() && !self.is_selection_enabled()
+    }
+
+    fn reorder_songs(&self, range_start: usize, range_length: usize, insert_before: usize) {
+        self.dispatcher
+            .dispatch(PlaybackAction::MoveRange(range_start, range_length, insert_before).into());
+    }
 }
 
 impl SimpleHeaderBarModel for NowPlayingModel {
@@ -148,3 +242,82 @@ impl SimpleHeaderBarModel for NowPlayingModel {
             .dispatch(SelectionAction::Select(songs).into());
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::{album, song, TestApp};
+    use crate::app::AppState;
+
+    fn app_with_autoplay(autoplay: bool) -> TestApp {
+        let api = FakeSpotifyClient::new().with_recommendations(vec![
+            song("a"),
+            song("c"),
+            song("d"),
+            song("c"),
+        ]);
+        let mut state = AppState::new();
+        state.settings.settings.autoplay = autoplay;
+        let app = TestApp::with_state(api, state);
+        app.dispatch(PlaybackAction::LoadSongs(vec![song("a"), song("b")]).into());
+        app.run_until_idle();
+        app
+    }
+
+    fn queued_ids(app: &TestApp) -> Vec<String> {
+        let songs: Vec<SongDescription> = app.state().playback.songs().collect();
+        songs.into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_autoplay_at_end_of_queue() {
+        let app = app_with_autoplay(true);
+        let model = NowPlayingModel::new(app.model.clone(), app.dispatcher());
+
+        app.dispatch(PlaybackAction::Load("a".to_owned()).into());
+        app.run_until_idle();
+        assert!(model.autoplay().is_none());
+
+        app.dispatch(PlaybackAction::Load("b".to_owned()).into());
+        app.run_until_idle();
+        assert!(model.autoplay().is_some());
+        app.run_until_idle();
+
+        // Songs already in the queue aren't repeated
+        assert_eq!(queued_ids(&app), vec!["a", "b", "c", "d"]);
+        assert!(!app.state().playback.is_playing_last());
+    }
+
+    #[test]
+    fn test_queue_links() {
+        let api = FakeSpotifyClient::new()
+            .with_album(album("album0", vec![song("c"), song("d")]))
+            .with_album(album("album1", vec![song("b")]));
+        let app = TestApp::new(api);
+        app.dispatch(PlaybackAction::LoadSongs(vec![song("a")]).into());
+        app.run_until_idle();
+        let model = NowPlayingModel::new(app.model.clone(), app.dispatcher());
+
+        assert!(!model.queue_links("nothing to see here"));
+        assert!(model.queue_links("https://open.spotify.com/album/album0?si=abc\nspotify:track:b"));
+        app.run_until_idle();
+
+        assert_eq!(queued_ids(&app), vec!["a", "c", "d", "b"]);
+    }
+
+    #[test]
+    fn test_autoplay_disabled() {
+        let app = app_with_autoplay(false);
+        let model = NowPlayingModel::new(app.model.clone(), app.dispatcher());
+
+        app.dispatch(PlaybackAction::Load("b".to_owned()).into());
+        app.run_until_idle();
+        assert!(model.autoplay().is_none());
+        app.run_until_idle();
+
+        assert_eq!(queued_ids(&app), vec!["a", "b"]);
+        assert!(app.api.calls().is_empty());
+    }
+}
@@ -3,10 +3,14 @@ use std::rc::Rc;
 
 use crate::app::components::EventListener;
 use crate::app::models::SongDescription;
-use crate::app::state::{PlaybackAction, PlaybackEvent, RepeatMode, ScreenName, SelectionEvent};
+use crate::app::state::{
+    PlaybackAction, PlaybackEvent, RepeatMode, ScreenName, SelectionEvent, SettingsAction,
+    SettingsEvent,
+};
 use crate::app::{
     ActionDispatcher, AppAction, AppEvent, AppModel, AppState, BrowserAction, Worker,
 };
+use crate::player::{list_pulseaudio_sinks, AudioBackend};
 
 use super::playback_widget::PlaybackWidget;
 
@@ -71,6 +75,31 @@ impl PlaybackModel {
         self.dispatcher
             .dispatch(PlaybackAction::Seek(position).into());
     }
+
+    fn volume(&self) -> f64 {
+        self.state().playback.volume()
+    }
+
+    // The slider also moves when the volume is set from elsewhere, no need to set it again then
+    fn set_volume(&self, volume: f64) {
+        if (volume - self.volume()).abs() > f64::EPSILON {
+            self.dispatcher
+                .dispatch(PlaybackAction::SetVolume(volume).into());
+        }
+    }
+
+    // None when the audio backend has no sinks to pick from
+    fn audio_sink(&self) -> Option<Option<String>> {
+        match &self.state().settings.settings.player_settings.backend {
+            AudioBackend::PulseAudio(sink) => Some(sink.clone()),
+            _ => None,
+        }
+    }
+
+    fn set_audio_sink(&self, sink: Option<String>) {
+        self.dispatcher
+            .dispatch(SettingsAction::SetAudioSink(sink).into());
+    }
 }
 
 pub struct PlaybackControl {
@@ -91,6 +120,26 @@ impl PlaybackControl {
         widget.connect_seek(clone!(@weak model => move |position| model.seek_to(position)));
         widget.connect_now_playing_clicked(clone!(@weak model => move || model.go_home()));
 
+        widget.set_volume(model.volume());
+        widget
+            .connect_volume_changed(clone!(@weak model => move |volume| model.set_volume(volume)));
+
+        // Sinks come and go, they are listed again every time the switcher opens.
+        // Listing them blocks, so it is done off the main thread.
+        widget.set_output_switcher_visible(model.audio_sink().is_some());
+        let sinks_worker = worker.clone();
+        widget.connect_output_switcher_opened(clone!(@weak model, @weak widget => move || {
+            let current = model.audio_sink().flatten();
+            sinks_worker.send_local_task(clone!(@weak widget => async move {
+                let sinks = gio::spawn_blocking(list_pulseaudio_sinks)
+                    .await
+                    .unwrap_or_default();
+                widget.set_outputs(&sinks, current.as_deref());
+            }));
+        }));
+        widget
+            .connect_output_selected(clone!(@weak model => move |sink| model.set_audio_sink(sink)));
+
         Self {
             model,
             widget,
@@ -124,6 +173,11 @@ impl PlaybackControl {
         }
     }
 
+    fn update_output_switcher(&self) {
+        self.widget
+            .set_output_switcher_visible(self.model.audio_sink().is_some());
+    }
+
     fn sync_seek(&self, pos: u32) {
         self.widget.set_seek_position(pos as f64);
     }
@@ -149,10 +203,16 @@ impl EventListener for PlaybackControl {
                 self.update_playing();
                 self.update_current_info();
             }
+            AppEvent::PlaybackEvent(PlaybackEvent::VolumeSet(volume)) => {
+                self.widget.set_volume(*volume);
+            }
             AppEvent::PlaybackEvent(PlaybackEvent::SeekSynced(pos))
             | AppEvent::PlaybackEvent(PlaybackEvent::TrackSeeked(pos)) => {
                 self.sync_seek(*pos);
             }
+            AppEvent::SettingsEvent(SettingsEvent::PlayerSettingsChanged) => {
+                self.update_output_switcher();
+            }
             AppEvent::SelectionEvent(SelectionEvent::SelectionModeChanged(active)) => {
                 self.widget.set_seekbar_visible(!active);
             }
@@ -1,12 +1,14 @@
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{glib, CompositeTemplate};
+use std::cell::RefCell;
 
-use crate::app::components::display_add_css_provider;
 use crate::app::components::utils::{format_duration, Clock, Debouncer};
+use crate::app::components::{display_add_css_provider, labels};
 use crate::app::loader::ImageLoader;
 use crate::app::state::RepeatMode;
 use crate::app::Worker;
+use crate::player::AudioSink;
 
 use super::playback_controls::PlaybackControlsWidget;
 use super::playback_info::PlaybackInfoWidget;
@@ -39,6 +41,21 @@ mod imp {
         #[template_child]
         pub track_duration: TemplateChild<gtk::Label>,
 
+        #[template_child]
+        pub volume_button: TemplateChild<gtk::VolumeButton>,
+
+        #[template_child]
+        pub output_button: TemplateChild<gtk::MenuButton>,
+
+        #[template_child]
+        pub output_popover: TemplateChild<gtk::Popover>,
+
+        #[template_child]
+        pub output_list: TemplateChild<gtk::ListBox>,
+
+        // The sink behind each row of the output list, None being the default one
+        pub outputs: RefCell<Vec<Option<String>>>,
+
         pub clock: Clock,
     }
 
@@ -166,6 +183,73 @@ impl PlaybackWidget {
         );
     }
 
+    pub fn set_volume(&self, volume: f64) {
+        self.imp().volume_button.set_value(volume);
+    }
+
+    pub fn connect_volume_changed<F>(&self, f: F)
+    where
+        F: Fn(f64) + 'static,
+    {
+        self.imp()
+            .volume_button
+            .connect_value_changed(move |_, volume| f(volume));
+    }
+
+    pub fn set_output_switcher_visible(&self, visible: bool) {
+        self.imp().output_button.set_visible(visible);
+    }
+
+    pub fn set_outputs(&self, sinks: &[AudioSink], current: Option<&str>) {
+        let widget = self.imp();
+        while let Some(row) = widget.output_list.first_child() {
+            widget.output_list.remove(&row);
+        }
+
+        let outputs = std::iter::once((None, labels::DEFAULT_OUTPUT.as_str())).chain(
+            sinks
+                .iter()
+                .map(|sink| (Some(sink.name.as_str()), sink.description.as_str())),
+        );
+        let mut names = vec![];
+        for (name, description) in outputs {
+            let row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+            let label = gtk::Label::new(Some(description));
+            label.set_hexpand(true);
+            label.set_xalign(0.0);
+            let check = gtk::Image::from_icon_name("object-select-symbolic");
+            check.set_opacity(if name == current { 1.0 } else { 0.0 });
+            row.append(&label);
+            row.append(&check);
+            widget.output_list.append(&row);
+            names.push(name.map(str::to_string));
+        }
+        widget.outputs.replace(names);
+    }
+
+    pub fn connect_output_switcher_opened<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp().output_popover.connect_show(move |_| f());
+    }
+
+    pub fn connect_output_selected<F>(&self, f: F)
+    where
+        F: Fn(Option<String>) + 'static,
+    {
+        self.imp()
+            .output_list
+            .connect_row_activated(clone!(@weak self as _self => move |_, row| {
+                let widget = _self.imp();
+                let output = widget.outputs.borrow().get(row.index() as usize).cloned();
+                if let Some(output) = output {
+                    widget.output_popover.popdown();
+                    f(output);
+                }
+            }));
+    }
+
     pub fn set_playing(&self, is_playing: bool) {
         let widget = self.imp();
         widget.controls.set_playing(is_playing);
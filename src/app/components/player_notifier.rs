@@ -1,26 +1,62 @@
 use futures::channel::mpsc::UnboundedSender;
-use librespot::core::spotify_id::SpotifyId;
+use librespot::core::spotify_id::{SpotifyAudioType, SpotifyId};
+use std::rc::Rc;
 
+use crate::app::components::utils::Debouncer;
 use crate::app::components::EventListener;
 use crate::app::state::{LoginAction, LoginEvent, LoginStartedEvent, PlaybackEvent, SettingsEvent};
-use crate::app::{AppAction, AppEvent};
+use crate::app::{AppAction, AppEvent, AppModel};
 use crate::player::Command;
+use crate::settings::{save_audio_sink, save_resume_positions, save_volume};
 
 pub struct PlayerNotifier {
+    app_model: Rc<AppModel>,
     action_sender: UnboundedSender<AppAction>,
     sender: UnboundedSender<Command>,
+    volume_debouncer: Debouncer,
 }
 
 impl PlayerNotifier {
     pub fn new(
+        app_model: Rc<AppModel>,
         action_sender: UnboundedSender<AppAction>,
         sender: UnboundedSender<Command>,
     ) -> Self {
         Self {
+            app_model,
             action_sender,
             sender,
+            volume_debouncer: Debouncer::new(),
         }
     }
+
+    // Dragging the volume slider sets it many times in a row, only the last one is worth saving
+    fn save_volume(&self, volume: f64) {
+        self.volume_debouncer.debounce(500, move || {
+            save_volume(volume);
+        });
+    }
+
+    // Ids alone do not tell tracks and episodes apart, their uri does
+    fn spotify_id(&self, id: &str) -> Option<SpotifyId> {
+        let state = self.app_model.get_state();
+        let uri = state
+            .playback
+            .songs()
+            .get(id)
+            .map(|song| song.description().uri.clone());
+        uri.and_then(|uri| SpotifyId::from_uri(&uri).ok())
+            .or_else(|| SpotifyId::from_base62(id).ok())
+    }
+
+    fn load_command(&self, id: &str) -> Option<Command> {
+        let track = self.spotify_id(id)?;
+        let position = Some(track)
+            .filter(|t| t.audio_type == SpotifyAudioType::Podcast)
+            .and_then(|_| self.app_model.get_state().playback.resume_position(id))
+            .unwrap_or(0);
+        Some(Command::PlayerLoad { track, position })
+    }
 }
 
 impl EventListener for PlayerNotifier {
@@ -30,13 +66,16 @@ impl EventListener for PlayerNotifier {
             AppEvent::PlaybackEvent(PlaybackEvent::PlaybackResumed) => Some(Command::PlayerResume),
             AppEvent::PlaybackEvent(PlaybackEvent::PlaybackStopped) => Some(Command::PlayerStop),
             AppEvent::PlaybackEvent(PlaybackEvent::VolumeSet(volume)) => {
+                self.save_volume(*volume);
                 Some(Command::PlayerSetVolume(*volume))
             }
-            AppEvent::PlaybackEvent(PlaybackEvent::TrackChanged(id)) => {
-                SpotifyId::from_base62(id).ok().map(Command::PlayerLoad)
-            }
+            AppEvent::PlaybackEvent(PlaybackEvent::TrackChanged(id)) => self.load_command(id),
             AppEvent::PlaybackEvent(PlaybackEvent::Preload(id)) => {
-                SpotifyId::from_base62(id).ok().map(Command::PlayerPreload)
+                self.spotify_id(id).map(Command::PlayerPreload)
+            }
+            AppEvent::PlaybackEvent(PlaybackEvent::ResumePositionsChanged) => {
+                save_resume_positions(self.app_model.get_state().playback.resume_positions());
+                None
             }
             AppEvent::PlaybackEvent(PlaybackEvent::TrackSeeked(position)) => {
                 Some(Command::PlayerSeek(*position))
@@ -60,6 +99,10 @@ impl EventListener for PlayerNotifier {
             AppEvent::SettingsEvent(SettingsEvent::PlayerSettingsChanged) => {
                 Some(Command::ReloadSettings)
             }
+            AppEvent::SettingsEvent(SettingsEvent::AudioSinkChanged(sink)) => {
+                save_audio_sink(sink.as_deref());
+                Some(Command::SwitchAudioSink(sink.clone()))
+            }
             _ => None,
         };
 
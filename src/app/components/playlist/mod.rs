@@ -6,3 +6,6 @@ pub use song::*;
 
 mod song_actions;
 pub use song_actions::*;
+
+mod saved_tracks_toggle;
+pub use saved_tracks_toggle::*;
@@ -1,12 +1,13 @@
+use gdk::prelude::*;
 use gio::prelude::*;
 use gtk::prelude::*;
 use std::ops::Deref;
 use std::rc::Rc;
 
 use crate::app::components::utils::{ancestor, AnimatorDefault};
-use crate::app::components::{Component, EventListener, SongWidget};
+use crate::app::components::{Component, EventListener, SavedTracksToggle, SongWidget};
 use crate::app::models::{SongListModel, SongModel, SongState};
-use crate::app::state::{PlaybackEvent, SelectionEvent, SelectionState};
+use crate::app::state::{BrowserEvent, PlaybackEvent, SelectionEvent, SelectionState};
 use crate::app::{AppEvent, Worker};
 
 pub trait PlaylistModel {
@@ -47,18 +48,31 @@ pub trait PlaylistModel {
             .unwrap_or(false)
     }
 
+    // Songs get a heart to save them to the library, unless this returns None
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        None
+    }
+
     fn song_state(&self, id: &str) -> SongState {
         let is_playing = self.current_song_id().map(|s| s.eq(id)).unwrap_or(false);
         let is_selected = self
             .selection()
             .map(|s| s.is_song_selected(id))
             .unwrap_or(false);
+        let is_saved = self.saved_tracks().map(|s| s.is_saved(id)).unwrap_or(false);
         SongState {
             is_selected,
             is_playing,
+            is_saved,
         }
     }
 
+    fn can_reorder(&self) -> bool {
+        false
+    }
+
+    fn reorder_songs(&self, _range_start: usize, _range_length: usize, _insert_before: usize) {}
+
     fn toggle_select(&self, id: &str) {
         if let Some(selection) = self.selection() {
             if selection.is_song_selected(id) {
@@ -95,9 +109,17 @@ where
         listview.set_model(Some(&selection_model));
         Self::set_selection_active(&listview, model.is_selection_enabled());
 
-        factory.connect_setup(|_, item| {
-            item.set_child(Some(&SongWidget::new()));
-        });
+        factory.connect_setup(clone!(@weak model => move |_, item| {
+            let widget = SongWidget::new();
+            widget.connect_save_clicked(clone!(@weak item, @weak model => move || {
+                let song_model = item.item().and_then(|i| i.downcast::<SongModel>().ok());
+                if let (Some(song_model), Some(saved_tracks)) = (song_model, model.saved_tracks()) {
+                    saved_tracks.toggle(song_model.into_description());
+                }
+            }));
+            Self::setup_reordering(&widget, item, model);
+            item.set_child(Some(&widget));
+        }));
 
         factory.connect_bind(clone!(@weak model => move |_, item| {
             let song_model = item.item().unwrap().downcast::<SongModel>().unwrap();
@@ -105,6 +127,10 @@ where
 
             let widget = item.child().unwrap().downcast::<SongWidget>().unwrap();
             widget.bind(&song_model, worker.clone(), model.show_song_covers());
+            widget.set_can_save(
+                model.saved_tracks().is_some()
+                    && SavedTracksToggle::can_save(&song_model.description()),
+            );
 
             let id = &song_model.get_id();
             widget.set_actions(model.actions_for(id).as_ref());
@@ -127,6 +153,13 @@ where
             }
         }));
 
+        Self::check_saved_songs(&*model, &list_model, 0, list_model.partial_len() as u32);
+        list_model.connect_items_changed(
+            clone!(@weak model => move |list_model, position, _, added| {
+                Self::check_saved_songs(&*model, list_model, position, added);
+            }),
+        );
+
         let press_gesture = gtk::GestureLongPress::new();
         press_gesture.set_touch_only(false);
         press_gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
@@ -142,6 +175,98 @@ where
         }
     }
 
+    // Songs are dragged around as themselves, so that a song dropped from another list is turned down
+    fn setup_reordering(widget: &SongWidget, item: &gtk::ListItem, model: Rc<Model>) {
+        let drag_source = gtk::DragSource::new();
+        drag_source.set_actions(gdk::DragAction::MOVE);
+        drag_source.connect_prepare(
+            clone!(@weak item, @weak model => @default-return None, move |_, _, _| {
+                if !model.can_reorder() {
+                    return None;
+                }
+                let song_model = item.item()?;
+                Some(gdk::ContentProvider::for_value(&song_model.to_value()))
+            }),
+        );
+        drag_source.connect_drag_begin(clone!(@weak widget => move |source, _| {
+            let paintable = gtk::WidgetPaintable::new(Some(&widget));
+            source.set_icon(Some(&paintable), 0, 0);
+        }));
+        widget.add_controller(drag_source);
+
+        let drop_target = gtk::DropTarget::new(SongModel::static_type(), gdk::DragAction::MOVE);
+        drop_target.connect_motion(
+            clone!(@weak widget, @weak model => @default-return gdk::DragAction::empty(), move |_, _, y| {
+                if !model.can_reorder() {
+                    Self::set_drop_indicator(&widget, None);
+                    return gdk::DragAction::empty();
+                }
+                let below = y > f64::from(widget.height()) / 2.0;
+                Self::set_drop_indicator(&widget, Some(below));
+                gdk::DragAction::MOVE
+            }),
+        );
+        drop_target.connect_leave(clone!(@weak widget => move |_| {
+            Self::set_drop_indicator(&widget, None);
+        }));
+        drop_target.connect_drop(
+            clone!(@weak widget, @weak item, @weak model => @default-return false, move |_, value, _, y| {
+                Self::set_drop_indicator(&widget, None);
+                if !model.can_reorder() {
+                    return false;
+                }
+                let from = match value
+                    .get::<SongModel>()
+                    .ok()
+                    .and_then(|song_model| Self::dragged_song_position(&model, &song_model))
+                {
+                    Some(from) => from,
+                    None => return false,
+                };
+                let below = y > f64::from(widget.height()) / 2.0;
+                let insert_before = item.position() as usize + usize::from(below);
+                if insert_before == from || insert_before == from + 1 {
+                    return false;
+                }
+                model.reorder_songs(from, 1, insert_before);
+                true
+            }),
+        );
+        widget.add_controller(drop_target);
+    }
+
+    // Where the dragged song is, provided it was dragged from this very list
+    fn dragged_song_position(model: &Model, song_model: &SongModel) -> Option<usize> {
+        let list_model = model.song_list_model();
+        let id = song_model.get_id();
+        list_model
+            .get(&id)
+            .filter(|listed| listed == song_model)
+            .and_then(|_| list_model.find_index(&id))
+    }
+
+    // Finds out whether newly listed songs are saved, for their heart to show it
+    fn check_saved_songs(model: &Model, list_model: &SongListModel, position: u32, added: u32) {
+        if let Some(saved_tracks) = model.saved_tracks() {
+            let songs: Vec<_> = (position..position + added)
+                .filter_map(|i| list_model.index_continuous(i as usize))
+                .map(|song| song.into_description())
+                .collect();
+            saved_tracks.check(&songs);
+        }
+    }
+
+    fn set_drop_indicator(widget: &SongWidget, below: Option<bool>) {
+        let context = widget.style_context();
+        context.remove_class("song--drop-above");
+        context.remove_class("song--drop-below");
+        match below {
+            Some(true) => context.add_class("song--drop-below"),
+            Some(false) => context.add_class("song--drop-above"),
+            None => {}
+        }
+    }
+
     fn autoscroll_to_playing(&self, index: usize) {
         let len = self.model.song_list_model().partial_len() as f64;
         let scrolled_window: Option<gtk::ScrolledWindow> = ancestor(&self.listview);
@@ -178,6 +303,14 @@ where
         });
     }
 
+    fn update_saved(&self) {
+        if let Some(saved_tracks) = self.model.saved_tracks() {
+            self.model.song_list_model().for_each(|_, model_song| {
+                model_song.set_saved(saved_tracks.is_saved(&model_song.get_id()));
+            });
+        }
+    }
+
     fn set_selection_active(listview: &gtk::ListView, active: bool) {
         let class_name = "playlist--selectable";
         let context = listview.style_context();
@@ -195,10 +328,12 @@ impl SongModel {
         SongState {
             is_playing,
             is_selected,
+            is_saved,
         }: SongState,
     ) {
         self.set_playing(is_playing);
         self.set_selected(is_selected);
+        self.set_saved(is_saved);
     }
 }
 
@@ -218,6 +353,9 @@ where
                 Self::set_selection_active(&self.listview, self.model.is_selection_enabled());
                 self.update_list();
             }
+            AppEvent::BrowserEvent(BrowserEvent::SavedTracksUpdated) => {
+                self.update_saved();
+            }
             _ => {}
         }
     }
@@ -0,0 +1,114 @@
+use std::rc::Rc;
+
+use crate::app::models::SongDescription;
+use crate::app::state::BrowserAction;
+use crate::app::{ActionDispatcher, AppModel, SpotifyUri};
+
+// Backs the heart shown on song rows with the tracks saved in the user's library
+pub struct SavedTracksToggle {
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl SavedTracksToggle {
+    pub fn new(app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            app_model,
+            dispatcher,
+        }
+    }
+
+    // What models showing the heart return from PlaylistModel::saved_tracks
+    pub fn for_model(app_model: &Rc<AppModel>, dispatcher: &dyn ActionDispatcher) -> Option<Self> {
+        Some(Self::new(Rc::clone(app_model), dispatcher.box_clone()))
+    }
+
+    // Episodes go through a different API, they get no heart
+    pub fn can_save(song: &SongDescription) -> bool {
+        matches!(SpotifyUri::parse(&song.uri), Some(SpotifyUri::Track(_)))
+    }
+
+    fn known_status(&self, id: &str) -> Option<bool> {
+        self.app_model
+            .get_state()
+            .browser
+            .home_state()
+            .and_then(|home| home.is_track_saved(id))
+    }
+
+    pub fn is_saved(&self, id: &str) -> bool {
+        self.known_status(id).unwrap_or(false)
+    }
+
+    pub fn toggle(&self, song: SongDescription) {
+        let api = self.app_model.get_spotify();
+        if self.is_saved(&song.id) {
+            self.dispatcher
+                .call_spotify_and_dispatch(move || async move {
+                    api.remove_saved_tracks(vec![song.id.clone()]).await?;
+                    Ok(BrowserAction::RemoveSavedTracks(vec![song.id]).into())
+                });
+        } else {
+            self.dispatcher
+                .call_spotify_and_dispatch(move || async move {
+                    api.save_tracks(vec![song.id.clone()]).await?;
+                    Ok(BrowserAction::SaveTracks(vec![song]).into())
+                });
+        }
+    }
+
+    // Asks Spotify about the songs we don't know the status of yet
+    pub fn check<'s>(&self, songs: impl IntoIterator<Item = &'s SongDescription>) {
+        let ids: Vec<String> = songs
+            .into_iter()
+            .filter(|song| Self::can_save(song) && self.known_status(&song.id).is_none())
+            .map(|song| song.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        let api = self.app_model.get_spotify();
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                let saved = api.contains_saved_tracks(ids.clone()).await?;
+                Ok(BrowserAction::SetTracksSaved(ids.into_iter().zip(saved).collect()).into())
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::{album, episode, song, TestApp};
+
+    #[test]
+    fn test_check_and_toggle() {
+        let api = FakeSpotifyClient::new()
+            .with_album(album("album0", vec![song("1"), song("2")]))
+            .with_saved_tracks(vec![song("2")]);
+        let app = TestApp::new(api);
+        let toggle = SavedTracksToggle::new(app.model.clone(), app.dispatcher());
+
+        let episode: SongDescription = (&episode("episode0", "show0")).into();
+        toggle.check(&[song("1"), song("2"), episode]);
+        app.run_until_idle();
+        assert!(!toggle.is_saved("1"));
+        assert!(toggle.is_saved("2"));
+        assert_eq!(app.api.calls(), vec!["contains_saved_tracks"]);
+
+        // Already known, no need to ask again
+        toggle.check(&[song("1")]);
+        app.run_until_idle();
+        assert_eq!(app.api.calls().len(), 1);
+
+        toggle.toggle(song("1"));
+        toggle.toggle(song("2"));
+        app.run_until_idle();
+        assert!(toggle.is_saved("1"));
+        assert!(!toggle.is_saved("2"));
+        let saved: Vec<String> = app.api.saved_tracks().into_iter().map(|s| s.id).collect();
+        assert_eq!(saved, vec!["1"]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::app::components::display_add_css_provider;
+use crate::app::components::{display_add_css_provider, labels};
 use crate::app::loader::ImageLoader;
 use crate::app::models::SongModel;
 
@@ -15,6 +15,7 @@ mod imp {
     use super::*;
 
     const SONG_CLASS: &str = "song--playing";
+    const SAVED_CLASS: &str = "song__save--saved";
 
     #[derive(Debug, Default, CompositeTemplate)]
     #[template(resource = "/dev/alextren/Spot/components/song.ui")]
@@ -37,6 +38,9 @@ mod imp {
         #[template_child]
         pub song_length: TemplateChild<gtk::Label>,
 
+        #[template_child]
+        pub save_btn: TemplateChild<gtk::Button>,
+
         #[template_child]
         pub menu_btn: TemplateChild<gtk::MenuButton>,
 
@@ -60,9 +64,10 @@ mod imp {
     }
 
     lazy_static! {
-        static ref PROPERTIES: [glib::ParamSpec; 2] = [
+        static ref PROPERTIES: [glib::ParamSpec; 3] = [
             glib::ParamSpecBoolean::builder("playing").build(),
-            glib::ParamSpecBoolean::builder("selected").build()
+            glib::ParamSpecBoolean::builder("selected").build(),
+            glib::ParamSpecBoolean::builder("saved").build()
         ];
     }
 
@@ -90,6 +95,19 @@ mod imp {
                         .expect("type conformity checked by `Object::set_property`");
                     self.song_checkbox.set_active(is_selected);
                 }
+                "saved" => {
+                    let is_saved = value
+                        .get()
+                        .expect("type conformity checked by `Object::set_property`");
+                    let context = self.save_btn.style_context();
+                    if is_saved {
+                        context.add_class(SAVED_CLASS);
+                        self.save_btn.set_tooltip_text(Some(&*labels::UNSAVE_TRACK));
+                    } else {
+                        context.remove_class(SAVED_CLASS);
+                        self.save_btn.set_tooltip_text(Some(&*labels::SAVE_TRACK));
+                    }
+                }
                 _ => unimplemented!(),
             }
         }
@@ -98,6 +116,11 @@ mod imp {
             match pspec.name() {
                 "playing" => self.obj().style_context().has_class(SONG_CLASS).to_value(),
                 "selected" => self.song_checkbox.is_active().to_value(),
+                "saved" => self
+                    .save_btn
+                    .style_context()
+                    .has_class(SAVED_CLASS)
+                    .to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -143,6 +166,17 @@ impl SongWidget {
         }
     }
 
+    pub fn set_can_save(&self, can_save: bool) {
+        self.imp().save_btn.set_visible(can_save);
+    }
+
+    pub fn connect_save_clicked<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp().save_btn.connect_clicked(move |_| f());
+    }
+
     fn set_show_cover(&self, show_cover: bool) {
         let song_class = "song--cover";
         let context = self.style_context();
@@ -178,6 +212,7 @@ impl SongWidget {
         model.bind_duration(&*widget.song_length, "label");
         model.bind_playing(self, "playing");
         model.bind_selected(self, "selected");
+        model.bind_saved(self, "saved");
 
         self.set_show_cover(show_cover);
         if show_cover {
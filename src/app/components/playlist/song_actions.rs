@@ -1,10 +1,14 @@
-use gdk::prelude::*;
 use gio::SimpleAction;
+use std::sync::Arc;
 
+use crate::api::SpotifyApiClient;
+use crate::app::components::share::copy_to_clipboard;
 use crate::app::models::SongDescription;
 use crate::app::state::{AppAction, PlaybackAction};
 use crate::app::ActionDispatcher;
 
+const RADIO_SIZE: usize = 50;
+
 impl SongDescription {
     pub fn make_queue_action(
         &self,
@@ -32,16 +36,40 @@ impl SongDescription {
         dequeue
     }
 
+    // Replaces the queue with the song followed by similar ones
+    pub fn make_radio_action(
+        &self,
+        api: Arc<dyn SpotifyApiClient + Send + Sync>,
+        dispatcher: Box<dyn ActionDispatcher>,
+        name: Option<&str>,
+    ) -> SimpleAction {
+        let radio = SimpleAction::new(name.unwrap_or("radio"), None);
+        let song = self.clone();
+        radio.connect_activate(move |_, _| {
+            let api = api.clone();
+            let song = song.clone();
+            dispatcher.call_spotify_and_dispatch_many(move || async move {
+                let seed_artists = song.artists.iter().take(1).map(|a| a.id.clone()).collect();
+                api.get_recommendations(vec![song.id.clone()], seed_artists, RADIO_SIZE)
+                    .await
+                    .map(|songs| {
+                        let id = song.id.clone();
+                        let mut tracks = vec![song];
+                        tracks.extend(songs.into_iter().filter(|s| s.id != id));
+                        vec![
+                            PlaybackAction::LoadSongs(tracks).into(),
+                            PlaybackAction::Load(id).into(),
+                        ]
+                    })
+            });
+        });
+        radio
+    }
+
     pub fn make_link_action(&self, name: Option<&str>) -> SimpleAction {
-        let track_id = self.id.clone();
+        let link = self.link();
         let copy_link = SimpleAction::new(name.unwrap_or("copy_link"), None);
-        copy_link.connect_activate(move |_, _| {
-            let link = format!("https://open.spotify.com/track/{}", &track_id);
-            let clipboard = gdk::Display::default().unwrap().clipboard();
-            clipboard
-                .set_content(Some(&gdk::ContentProvider::for_value(&link.to_value())))
-                .expect("Failed to set clipboard content");
-        });
+        copy_link.connect_activate(move |_, _| copy_to_clipboard(&link));
         copy_link
     }
 
@@ -51,9 +79,14 @@ impl SongDescription {
         name: Option<&str>,
     ) -> SimpleAction {
         let album_id = self.album.id.clone();
+        let is_episode = self.is_episode();
         let view_album = SimpleAction::new(name.unwrap_or("view_album"), None);
         view_album.connect_activate(move |_, _| {
-            dispatcher.dispatch(AppAction::ViewAlbum(album_id.clone()));
+            if is_episode {
+                dispatcher.dispatch(AppAction::ViewShow(album_id.clone()));
+            } else {
+                dispatcher.dispatch(AppAction::ViewAlbum(album_id.clone()));
+            }
         });
         view_album
     }
@@ -1,5 +1,7 @@
 mod playlist_details;
 mod playlist_details_model;
+mod playlist_edit;
 
 pub use playlist_details::*;
 pub use playlist_details_model::*;
+pub use playlist_edit::*;
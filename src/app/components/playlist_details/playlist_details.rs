@@ -3,13 +3,14 @@ use gtk::subclass::prelude::*;
 use gtk::CompositeTemplate;
 use std::rc::Rc;
 
-use super::PlaylistDetailsModel;
+use super::{PlaylistDetailsModel, PlaylistEditWindow};
 use crate::app::components::AlbumHeaderWidget;
 
 use crate::app::components::{Component, EventListener, Playlist};
 use crate::app::dispatch::Worker;
 use crate::app::loader::ImageLoader;
-use crate::app::{AppEvent, BrowserEvent};
+use crate::app::state::LoginEvent;
+use crate::app::{AppEvent, BrowserEvent, SpotifyUri};
 use libadwaita::subclass::prelude::BinImpl;
 
 mod imp {
@@ -33,6 +34,9 @@ mod imp {
 
         #[template_child]
         pub tracks: TemplateChild<gtk::ListView>,
+
+        #[template_child]
+        pub edit_button: TemplateChild<gtk::Button>,
     }
 
     #[glib::object_subclass]
@@ -127,6 +131,22 @@ impl PlaylistDetailsWidget {
             .set_album_and_artist_and_year(album, artist, None);
     }
 
+    fn set_editable(&self, editable: bool) {
+        self.imp().edit_button.set_visible(editable);
+    }
+
+    fn connect_edit<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp().edit_button.connect_clicked(move |_| f());
+    }
+
+    fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        self.imp().header_widget.set_share_uri(uri);
+        self.imp().header_mobile.set_share_uri(uri);
+    }
+
     fn set_artwork(&self, art: &gdk_pixbuf::Pixbuf) {
         self.imp().header_widget.set_artwork(art);
         self.imp().header_mobile.set_artwork(art);
@@ -161,6 +181,7 @@ impl PlaylistDetails {
         ));
 
         widget.connect_header();
+        widget.set_share_uri(&SpotifyUri::Playlist(&model.id));
 
         widget.connect_bottom_edge(clone!(@weak model => move || {
             model.load_more_tracks();
@@ -170,6 +191,32 @@ impl PlaylistDetails {
             model.view_owner();
         }));
 
+        let edit_window = PlaylistEditWindow::new();
+
+        edit_window.connect_save(
+            clone!(@weak model => move |title, description, public, collaborative| {
+                model.update_details(title, description, public, collaborative);
+            }),
+        );
+
+        edit_window.connect_delete(clone!(@weak model => move || {
+            model.unfollow();
+        }));
+
+        widget.connect_edit(
+            clone!(@weak model, @weak widget, @strong edit_window => move || {
+                if let Some(info) = model.get_playlist_info() {
+                    edit_window.set_details(
+                        &info.title,
+                        info.description.as_deref(),
+                        info.public.unwrap_or(false),
+                        info.collaborative,
+                    );
+                    edit_window.present_for(&widget);
+                }
+            }),
+        );
+
         Self {
             model,
             worker,
@@ -178,6 +225,17 @@ impl PlaylistDetails {
         }
     }
 
+    fn update_title(&self) {
+        if let Some(info) = self.model.get_playlist_info() {
+            self.widget
+                .set_album_and_artist(&info.title[..], &info.owner.display_name[..]);
+        }
+    }
+
+    fn update_editable(&self) {
+        self.widget.set_editable(self.model.is_playlist_editable());
+    }
+
     fn update_details(&self) {
         if let Some(info) = self.model.get_playlist_info() {
             let title = &info.title[..];
@@ -185,6 +243,7 @@ impl PlaylistDetails {
             let art_url = info.art.as_ref();
 
             self.widget.set_album_and_artist(title, owner);
+            self.update_editable();
 
             if let Some(art_url) = art_url.cloned() {
                 let widget = self.widget.downgrade();
@@ -222,6 +281,12 @@ impl EventListener for PlaylistDetails {
             {
                 self.update_details()
             }
+            AppEvent::BrowserEvent(BrowserEvent::PlaylistDetailsUpdated(id))
+                if id == &self.model.id =>
+            {
+                self.update_title()
+            }
+            AppEvent::LoginEvent(LoginEvent::UserPlaylistsLoaded) => self.update_editable(),
             _ => {}
         }
         self.broadcast_event(event);
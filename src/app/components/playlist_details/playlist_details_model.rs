@@ -1,14 +1,19 @@
+use futures::lock::Mutex;
+use gettextrs::gettext;
 use gio::prelude::*;
 use gio::SimpleActionGroup;
 use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::api::SpotifyApiError;
 use crate::app::components::SimpleHeaderBarModel;
-use crate::app::components::{labels, PlaylistModel};
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
 use crate::app::models::*;
 use crate::app::state::SelectionContext;
-use crate::app::state::{BrowserAction, PlaybackAction, SelectionAction, SelectionState};
+use crate::app::state::{
+    BrowserAction, LoginAction, PlaybackAction, SelectionAction, SelectionState,
+};
 use crate::app::{ActionDispatcher, AppAction, AppEvent, AppModel, BatchQuery, SongsSource};
 
 pub struct PlaylistDetailsModel {
@@ -16,6 +21,9 @@ pub struct PlaylistDetailsModel {
     _editable_selection_context: SelectionContext,
     app_model: Rc<AppModel>,
     dispatcher: Box<dyn ActionDispatcher>,
+    // Reorders are saved one at a time, each on top of the snapshot the previous one returned.
+    // The snapshot they started from comes first, any other means the playlist changed since.
+    reorder_snapshots: Arc<Mutex<Vec<String>>>,
 }
 
 impl PlaylistDetailsModel {
@@ -25,12 +33,20 @@ impl PlaylistDetailsModel {
             _editable_selection_context: SelectionContext::EditablePlaylist(id),
             app_model,
             dispatcher,
+            reorder_snapshots: Arc::new(Mutex::new(vec![])),
         }
     }
 
-    fn is_playlist_editable(&self) -> bool {
+    pub fn is_playlist_editable(&self) -> bool {
         let state = self.app_model.get_state();
-        state.logged_user.playlists.iter().any(|p| p.id == self.id)
+        let is_owner = state
+            .browser
+            .playlist_details_state(&self.id)
+            .and_then(|s| s.playlist.as_ref())
+            .zip(state.logged_user.user.as_ref())
+            .map(|(playlist, user)| &playlist.owner.id == user)
+            .unwrap_or(false);
+        is_owner || state.logged_user.playlists.iter().any(|p| p.id == self.id)
     }
 
     pub fn get_playlist_info(&self) -> Option<impl Deref<Target = PlaylistDescription> + '_> {
@@ -85,6 +101,147 @@ impl PlaylistDetailsModel {
         Some(())
     }
 
+    pub fn update_details(
+        &self,
+        title: String,
+        description: String,
+        public: bool,
+        collaborative: bool,
+    ) -> Option<()> {
+        let update = {
+            let info = self.get_playlist_info()?;
+            let current_description = info.description.as_deref().unwrap_or("");
+            PlaylistDetailsUpdate {
+                title: Some(title).filter(|t| t != &info.title),
+                description: Some(description).filter(|d| d != current_description),
+                public: Some(public).filter(|p| Some(*p) != info.public),
+                collaborative: Some(collaborative).filter(|c| *c != info.collaborative),
+            }
+        };
+
+        let has_changes = update.title.is_some()
+            || update.description.is_some()
+            || update.public.is_some()
+            || update.collaborative.is_some();
+        if !has_changes {
+            return None;
+        }
+
+        let api = self.app_model.get_spotify();
+        let id = self.id.clone();
+        self.dispatcher
+            .call_spotify_and_dispatch_many(move || async move {
+                api.update_playlist_details(&id, update.clone()).await?;
+                let mut actions: Vec<AppAction> = vec![];
+                if let Some(title) = update.title.clone() {
+                    let summary = PlaylistSummary {
+                        id: id.clone(),
+                        title,
+                    };
+                    actions.push(LoginAction::UpdateUserPlaylist(summary).into());
+                }
+                actions.push(BrowserAction::UpdatePlaylistDetails(id, update).into());
+                Ok(actions)
+            });
+
+        Some(())
+    }
+
+    pub fn unfollow(&self) {
+        let api = self.app_model.get_spotify();
+        let id = self.id.clone();
+        self.dispatcher
+            .call_spotify_and_dispatch_many(move || async move {
+                api.unfollow_playlist(&id).await?;
+                Ok(vec![
+                    BrowserAction::NavigationPop.into(),
+                    BrowserAction::RemovePlaylist(id.clone()).into(),
+                    LoginAction::RemoveUserPlaylist(id).into(),
+                ])
+            });
+    }
+
+    // Spotify counts the entries we leave out of the list, such as local files
+    fn remote_reorder(
+        &self,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+    ) -> (usize, usize, usize) {
+        self.app_model
+            .get_state()
+            .browser
+            .playlist_details_state(&self.id)
+            .map(|state| state.remote_reorder(range_start, range_length, insert_before))
+            .unwrap_or((range_start, range_length, insert_before))
+    }
+
+    // Saves a move that was already made to the list, given in positions of the list and in those of Spotify
+    fn persist_reorder(
+        &self,
+        (range_start, range_length, insert_before): (usize, usize, usize),
+        (remote_start, remote_length, remote_insert_before): (usize, usize, usize),
+    ) {
+        let api = self.app_model.get_spotify();
+        let id = self.id.clone();
+        let snapshot_id = self
+            .get_playlist_info()
+            .and_then(|info| info.snapshot_id.clone());
+        let reorder_snapshots = Arc::clone(&self.reorder_snapshots);
+        self.dispatcher
+            .call_spotify_and_dispatch_many(move || async move {
+                let mut snapshots = reorder_snapshots.lock().await;
+                let is_known = snapshot_id
+                    .as_ref()
+                    .map(|id| snapshots.contains(id))
+                    .unwrap_or(false);
+                if !is_known {
+                    // Reloaded or edited some other way, the snapshots of our reorders are outdated
+                    snapshots.clear();
+                    snapshots.extend(snapshot_id.clone());
+                }
+                let snapshot_id = snapshots.last().cloned();
+                let result = api
+                    .reorder_playlist_tracks(
+                        &id,
+                        remote_start,
+                        remote_length,
+                        remote_insert_before,
+                        snapshot_id,
+                    )
+                    .await;
+                match result {
+                    Ok(snapshot_id) => {
+                        snapshots.push(snapshot_id.clone());
+                        Ok(vec![
+                            BrowserAction::SetPlaylistSnapshot(id, snapshot_id).into()
+                        ])
+                    }
+                    Err(SpotifyApiError::InvalidToken) => Err(SpotifyApiError::InvalidToken),
+                    Err(e) => {
+                        error!("Could not reorder playlist: {}", e);
+                        // Put the tracks back where they were, as the playlist was left untouched
+                        let (start, insert_before) = if insert_before < range_start {
+                            (insert_before, range_start + range_length)
+                        } else {
+                            (insert_before - range_length, range_start)
+                        };
+                        Ok(vec![
+                            BrowserAction::MovePlaylistTracks(
+                                id,
+                                start,
+                                range_length,
+                                insert_before,
+                            )
+                            .into(),
+                            // translators: This notification shows up when moving tracks in a playlist failed
+                            AppAction::ShowNotification(gettext("Could not reorder the playlist")),
+                        ])
+                    }
+                }
+            });
+    }
+
     pub fn view_owner(&self) {
         if let Some(playlist) = self.get_playlist_info() {
             let owner = &playlist.owner.id;
@@ -120,6 +277,10 @@ impl PlaylistModel for PlaylistDetailsModel {
         }
     }
 
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
     fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
         let song = self.song_list_model().get(id)?;
         let song = song.description();
@@ -131,6 +292,11 @@ impl PlaylistModel for PlaylistDetailsModel {
         }
         group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
         group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
         group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
 
         Some(group.upcast())
@@ -150,6 +316,7 @@ impl PlaylistModel for PlaylistDetailsModel {
         }
 
         menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
         menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
 
         Some(menu.upcast())
@@ -178,6 +345,25 @@ impl PlaylistModel for PlaylistDetailsModel {
     fn selection(&self) -> Option<Box<dyn Deref<Target = SelectionState> + '_>> {
         Some(Box::new(self.app_model.map_state(|s| &s.selection)))
     }
+
+    fn can_reorder(&self) -> bool {
+        self.is_playlist_editable() && !self.is_selection_enabled()
+    }
+
+    fn reorder_songs(&self, range_start: usize, range_length: usize, insert_before: usize) {
+        let local = (range_start, range_length, insert_before);
+        let remote = self.remote_reorder(range_start, range_length, insert_before);
+        self.dispatcher.dispatch(
+            BrowserAction::MovePlaylistTracks(
+                self.id.clone(),
+                range_start,
+                range_length,
+                insert_before,
+            )
+            .into(),
+        );
+        self.persist_reorder(local, remote);
+    }
 }
 
 impl SimpleHeaderBarModel for PlaylistDetailsModel {
@@ -203,3 +389,220 @@ impl SimpleHeaderBarModel for PlaylistDetailsModel {
             .dispatch(SelectionAction::Select(songs).into());
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::state::{LoginEvent, PlaybackEvent};
+    use crate::app::testing::{playlist, song, user, TestApp};
+
+    fn playlist_app(song_count: usize) -> TestApp {
+        let songs = (0..song_count)
+            .map(|i| song(&format!("song{}", i)))
+            .collect();
+        let api = FakeSpotifyClient::new()
+            .with_user(user("me"))
+            .with_playlist(playlist("playlist0", "me"), songs);
+        TestApp::logged_in(api, "me")
+    }
+
+    fn open_playlist(app: &TestApp) -> PlaylistDetailsModel {
+        app.dispatch(AppAction::ViewPlaylist("playlist0".to_owned()));
+        app.run_until_idle();
+        PlaylistDetailsModel::new("playlist0".to_owned(), app.model.clone(), app.dispatcher())
+    }
+
+    fn song_ids(model: &PlaylistDetailsModel) -> Vec<String> {
+        let songs: Vec<SongDescription> = model.song_list_model().collect();
+        songs.into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_load_more_play_next() {
+        let app = playlist_app(150);
+        let model = open_playlist(&app);
+
+        model.load_playlist_info();
+        let events = app.run_until_idle();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AppEvent::BrowserEvent(BrowserEvent::PlaylistDetailsLoaded(id)) if id == "playlist0"
+        )));
+        assert!(model.is_playlist_editable());
+        assert_eq!(model.song_list_model().partial_len(), 100);
+
+        model.load_more_tracks();
+        app.run_until_idle();
+        assert_eq!(model.song_list_model().partial_len(), 150);
+        assert!(model.load_more_tracks().is_none());
+
+        model.play_song_at(120, "song120");
+        let events = app.run_until_idle();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AppEvent::PlaybackEvent(PlaybackEvent::TrackChanged(id)) if id == "song120"
+        )));
+
+        app.dispatch(PlaybackAction::Next.into());
+        app.run_until_idle();
+        assert_eq!(
+            app.state().playback.current_song_id().as_deref(),
+            Some("song121")
+        );
+        assert_eq!(app.api.calls(), vec!["get_playlist", "get_playlist_tracks"]);
+    }
+
+    #[test]
+    fn test_load_failures() {
+        let app = playlist_app(10);
+        let model = open_playlist(&app);
+
+        app.api
+            .fail_next("get_playlist", SpotifyApiError::NoContent);
+        model.load_playlist_info();
+        let events = app.run_until_idle();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, AppEvent::NotificationShown(_))));
+        assert!(model.get_playlist_info().is_none());
+
+        // retried once, then a new token is requested
+        app.api
+            .fail_next("get_playlist", SpotifyApiError::InvalidToken);
+        model.load_playlist_info();
+        let events = app.run_until_idle();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, AppEvent::LoginEvent(LoginEvent::FreshTokenRequested))));
+        let title = model.get_playlist_info().map(|p| p.title.clone());
+        assert_eq!(title.as_deref(), Some("Playlist playlist0"));
+    }
+
+    #[test]
+    fn test_reorder_songs() {
+        let app = playlist_app(3);
+        let model = open_playlist(&app);
+        model.load_playlist_info();
+        app.run_until_idle();
+
+        model.reorder_songs(0, 1, 3);
+        app.run_until_idle();
+        assert_eq!(song_ids(&model), vec!["song1", "song2", "song0"]);
+        let remote: Vec<String> = app
+            .api
+            .playlist_songs("playlist0")
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(remote, vec!["song1", "song2", "song0"]);
+        let snapshot_id = model
+            .get_playlist_info()
+            .and_then(|p| p.snapshot_id.clone());
+        assert_eq!(snapshot_id.as_deref(), Some("snapshot1"));
+
+        // the move is undone when it could not be saved
+        app.api
+            .fail_next("reorder_playlist_tracks", SpotifyApiError::NoContent);
+        model.reorder_songs(2, 1, 0);
+        let events = app.run_until_idle();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, AppEvent::NotificationShown(_))));
+        assert_eq!(song_ids(&model), vec!["song1", "song2", "song0"]);
+
+        // a move made while the previous one is being saved builds on its snapshot
+        model.reorder_songs(0, 1, 3);
+        model.reorder_songs(0, 1, 3);
+        app.run_until_idle();
+        assert_eq!(song_ids(&model), vec!["song0", "song1", "song2"]);
+        let remote: Vec<String> = app
+            .api
+            .playlist_songs("playlist0")
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(remote, vec!["song0", "song1", "song2"]);
+    }
+
+    #[test]
+    fn test_reorder_songs_after_other_edits() {
+        let app = playlist_app(3);
+        let model = open_playlist(&app);
+        model.load_playlist_info();
+        app.run_until_idle();
+
+        model.reorder_songs(0, 1, 3);
+        app.run_until_idle();
+
+        // edited from somewhere else, the reload brings a newer snapshot than the reorder's
+        block_on(
+            app.api
+                .add_to_playlist("playlist0", vec!["spotify:track:song0".to_owned()]),
+        )
+        .unwrap();
+        model.load_playlist_info();
+        app.run_until_idle();
+
+        model.reorder_songs(0, 1, 2);
+        let events = app.run_until_idle();
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, AppEvent::NotificationShown(_))));
+        let snapshot_id = model
+            .get_playlist_info()
+            .and_then(|p| p.snapshot_id.clone());
+        assert_eq!(snapshot_id.as_deref(), Some("snapshot3"));
+
+        // removing songs drops the snapshot, the next reorder goes without one
+        let uris = vec!["spotify:track:song1".to_owned()];
+        block_on(app.api.remove_from_playlist("playlist0", uris.clone())).unwrap();
+        app.dispatch(BrowserAction::RemoveTracksFromPlaylist("playlist0".to_owned(), uris).into());
+        app.run_until_idle();
+        model.reorder_songs(0, 1, 2);
+        let events = app.run_until_idle();
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, AppEvent::NotificationShown(_))));
+        let snapshot_id = model
+            .get_playlist_info()
+            .and_then(|p| p.snapshot_id.clone());
+        assert_eq!(snapshot_id.as_deref(), Some("snapshot5"));
+    }
+
+    #[test]
+    fn test_reorder_songs_after_local_track() {
+        let mut local_song = song("local0");
+        local_song.uri = "spotify:local:local0".to_owned();
+        let songs = vec![local_song, song("song0"), song("song1"), song("song2")];
+        let api = FakeSpotifyClient::new()
+            .with_user(user("me"))
+            .with_playlist(playlist("playlist0", "me"), songs);
+        let app = TestApp::logged_in(api, "me");
+        let model = open_playlist(&app);
+        model.load_playlist_info();
+        app.run_until_idle();
+        assert_eq!(song_ids(&model), vec!["song0", "song1", "song2"]);
+
+        let remote_ids = || -> Vec<String> {
+            app.api
+                .playlist_songs("playlist0")
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+
+        model.reorder_songs(2, 1, 0);
+        app.run_until_idle();
+        assert_eq!(song_ids(&model), vec!["song2", "song0", "song1"]);
+        assert_eq!(remote_ids(), vec!["local0", "song2", "song0", "song1"]);
+
+        model.reorder_songs(0, 1, 3);
+        app.run_until_idle();
+        assert_eq!(song_ids(&model), vec!["song0", "song1", "song2"]);
+        assert_eq!(remote_ids(), vec!["local0", "song0", "song1", "song2"]);
+    }
+}
@@ -0,0 +1,205 @@
+use gettextrs::*;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::CompositeTemplate;
+use libadwaita::subclass::prelude::*;
+
+mod imp {
+
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/dev/alextren/Spot/components/playlist_edit.ui")]
+    pub struct PlaylistEditWindow {
+        #[template_child]
+        pub window_title: TemplateChild<libadwaita::WindowTitle>,
+
+        #[template_child]
+        pub cancel_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub save_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub name_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub description_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub description_entry: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub public_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub public_switch: TemplateChild<gtk::Switch>,
+
+        #[template_child]
+        pub collaborative_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub collaborative_switch: TemplateChild<gtk::Switch>,
+
+        #[template_child]
+        pub delete_button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PlaylistEditWindow {
+        const NAME: &'static str = "PlaylistEditWindow";
+        type Type = super::PlaylistEditWindow;
+        type ParentType = libadwaita::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PlaylistEditWindow {}
+    impl WidgetImpl for PlaylistEditWindow {}
+    impl AdwWindowImpl for PlaylistEditWindow {}
+    impl WindowImpl for PlaylistEditWindow {}
+}
+
+glib::wrapper! {
+    pub struct PlaylistEditWindow(ObjectSubclass<imp::PlaylistEditWindow>) @extends gtk::Widget, gtk::Window, libadwaita::Window;
+}
+
+impl PlaylistEditWindow {
+    pub fn new() -> Self {
+        let window: Self = glib::Object::new();
+        window.connect_widgets();
+        window
+    }
+
+    fn connect_widgets(&self) {
+        let widget = self.imp();
+        self.set_default_widget(Some(&*widget.save_button));
+
+        widget
+            .cancel_button
+            .connect_clicked(clone!(@weak self as _self => move |_| _self.close()));
+
+        widget
+            .name_entry
+            .connect_changed(clone!(@weak self as _self => move |entry| {
+                let is_valid = !entry.text().trim().is_empty();
+                _self.imp().save_button.set_sensitive(is_valid);
+            }));
+
+        // Spotify does not allow collaborative playlists to be public
+        widget
+            .collaborative_switch
+            .bind_property("active", &*widget.public_switch, "sensitive")
+            .invert_boolean()
+            .sync_create()
+            .build();
+        widget
+            .collaborative_switch
+            .connect_active_notify(clone!(@weak self as _self => move |s| {
+                if s.is_active() {
+                    _self.imp().public_switch.set_active(false);
+                }
+            }));
+    }
+
+    // When creating a playlist, only its name is asked for
+    pub fn new_for_creation() -> Self {
+        let window = Self::new();
+        let widget = window.imp();
+        // translators: Title of the dialog used to create a new playlist
+        widget.window_title.set_title(&gettext("New Playlist"));
+        // translators: Button that confirms the creation of a new playlist
+        widget.save_button.set_label(&gettext("Create"));
+        widget.description_row.set_visible(false);
+        widget.public_row.set_visible(false);
+        widget.collaborative_row.set_visible(false);
+        widget.delete_button.set_visible(false);
+        widget.save_button.set_sensitive(false);
+        window
+    }
+
+    pub fn set_details(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        public: bool,
+        collaborative: bool,
+    ) {
+        let widget = self.imp();
+        widget.name_entry.set_text(name);
+        widget.description_entry.set_text(description.unwrap_or(""));
+        widget.collaborative_switch.set_active(collaborative);
+        widget.public_switch.set_active(public);
+    }
+
+    pub fn present_for(&self, parent: &impl IsA<gtk::Widget>) {
+        self.set_transient_for(
+            parent
+                .root()
+                .and_then(|r| r.downcast::<gtk::Window>().ok())
+                .as_ref(),
+        );
+        self.imp().name_entry.grab_focus();
+        self.present();
+    }
+
+    pub fn connect_save<F>(&self, f: F)
+    where
+        F: Fn(String, String, bool, bool) + 'static,
+    {
+        self.imp()
+            .save_button
+            .connect_clicked(clone!(@weak self as _self => move |_| {
+                let widget = _self.imp();
+                let name = widget.name_entry.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                f(
+                    name,
+                    widget.description_entry.text().trim().to_string(),
+                    widget.public_switch.is_active(),
+                    widget.collaborative_switch.is_active(),
+                );
+                _self.close();
+            }));
+    }
+
+    pub fn connect_delete<F>(&self, f: F)
+    where
+        F: Fn() + Clone + 'static,
+    {
+        self.imp()
+            .delete_button
+            .connect_clicked(clone!(@weak self as _self => move |_| {
+                let dialog = gtk::MessageDialog::builder()
+                    .transient_for(&_self)
+                    .modal(true)
+                    .message_type(gtk::MessageType::Warning)
+                    // translators: Confirmation asked before deleting a playlist
+                    .text(gettext("Delete this playlist?"))
+                    .secondary_text(gettext("It will be removed from your library."))
+                    .build();
+                dialog.add_button(&gettext("Cancel"), gtk::ResponseType::Cancel);
+                // translators: Button that confirms the deletion of a playlist
+                let delete = dialog.add_button(&gettext("Delete"), gtk::ResponseType::Accept);
+                delete.add_css_class("destructive-action");
+
+                let f = f.clone();
+                dialog.connect_response(clone!(@weak _self => move |dialog, response| {
+                    dialog.close();
+                    if response == gtk::ResponseType::Accept {
+                        _self.close();
+                        f();
+                    }
+                }));
+                dialog.present();
+            }));
+    }
+}
@@ -0,0 +1,5 @@
+mod recently_played;
+pub use recently_played::*;
+
+mod recently_played_model;
+pub use recently_played_model::*;
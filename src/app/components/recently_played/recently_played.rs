@@ -0,0 +1,110 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::CompositeTemplate;
+use std::rc::Rc;
+
+use super::RecentlyPlayedModel;
+use crate::app::components::{Component, EventListener, Playlist};
+use crate::app::{AppEvent, Worker};
+use libadwaita::subclass::prelude::BinImpl;
+
+mod imp {
+
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/dev/alextren/Spot/components/recently_played.ui")]
+    pub struct RecentlyPlayedWidget {
+        #[template_child]
+        pub song_list: TemplateChild<gtk::ListView>,
+
+        #[template_child]
+        pub scrolled_window: TemplateChild<gtk::ScrolledWindow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RecentlyPlayedWidget {
+        const NAME: &'static str = "RecentlyPlayedWidget";
+        type Type = super::RecentlyPlayedWidget;
+        type ParentType = libadwaita::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RecentlyPlayedWidget {}
+    impl WidgetImpl for RecentlyPlayedWidget {}
+    impl BinImpl for RecentlyPlayedWidget {}
+}
+
+glib::wrapper! {
+    pub struct RecentlyPlayedWidget(ObjectSubclass<imp::RecentlyPlayedWidget>) @extends gtk::Widget, libadwaita::Bin;
+}
+
+impl RecentlyPlayedWidget {
+    fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn connect_bottom_edge<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp()
+            .scrolled_window
+            .connect_edge_reached(move |_, pos| {
+                if let gtk::PositionType::Bottom = pos {
+                    f()
+                }
+            });
+    }
+
+    fn song_list_widget(&self) -> &gtk::ListView {
+        self.imp().song_list.as_ref()
+    }
+}
+
+pub struct RecentlyPlayed {
+    widget: RecentlyPlayedWidget,
+    children: Vec<Box<dyn EventListener>>,
+}
+
+impl RecentlyPlayed {
+    pub fn new(model: Rc<RecentlyPlayedModel>, worker: Worker) -> Self {
+        model.load_initial();
+
+        let widget = RecentlyPlayedWidget::new();
+
+        widget.connect_bottom_edge(clone!(@weak model => move || {
+            model.load_more();
+        }));
+
+        let playlist = Playlist::new(widget.song_list_widget().clone(), model, worker);
+
+        Self {
+            widget,
+            children: vec![Box::new(playlist)],
+        }
+    }
+}
+
+impl Component for RecentlyPlayed {
+    fn get_root_widget(&self) -> &gtk::Widget {
+        self.widget.upcast_ref()
+    }
+
+    fn get_children(&mut self) -> Option<&mut Vec<Box<dyn EventListener>>> {
+        Some(&mut self.children)
+    }
+}
+
+impl EventListener for RecentlyPlayed {
+    fn on_event(&mut self, event: &AppEvent) {
+        self.broadcast_event(event);
+    }
+}
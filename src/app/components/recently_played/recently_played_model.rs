@@ -0,0 +1,185 @@
+use gio::prelude::*;
+use gio::SimpleActionGroup;
+use std::cell::Ref;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
+use crate::app::models::*;
+use crate::app::state::{
+    PlaybackAction, RecentlyPlayedState, SelectionAction, SelectionContext, SelectionState,
+};
+use crate::app::{ActionDispatcher, AppAction, AppModel, BrowserAction};
+
+// The API doesn't return more than 50 plays at once
+const PAGE_SIZE: usize = 50;
+
+pub struct RecentlyPlayedModel {
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl RecentlyPlayedModel {
+    pub fn new(app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            app_model,
+            dispatcher,
+        }
+    }
+
+    fn state(&self) -> Option<Ref<'_, RecentlyPlayedState>> {
+        self.app_model
+            .map_state_opt(|s| s.browser.recently_played_state())
+    }
+
+    pub fn load_initial(&self) {
+        let api = self.app_model.get_spotify();
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                api.get_recently_played(None, PAGE_SIZE)
+                    .await
+                    .map(|recently_played| {
+                        BrowserAction::SetRecentlyPlayed(Box::new(recently_played)).into()
+                    })
+            });
+    }
+
+    pub fn load_more(&self) -> Option<()> {
+        let api = self.app_model.get_spotify();
+        let before = self.state()?.before.clone()?;
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                api.get_recently_played(Some(before), PAGE_SIZE)
+                    .await
+                    .map(|recently_played| {
+                        BrowserAction::AppendRecentlyPlayed(Box::new(recently_played)).into()
+                    })
+            });
+        Some(())
+    }
+}
+
+impl PlaylistModel for RecentlyPlayedModel {
+    fn song_list_model(&self) -> SongListModel {
+        self.state()
+            .expect("illegal attempt to read recently_played_state")
+            .songs
+            .clone()
+    }
+
+    fn current_song_id(&self) -> Option<String> {
+        self.app_model.get_state().playback.current_song_id()
+    }
+
+    fn play_song_at(&self, _pos: usize, id: &str) {
+        let tracks: Vec<SongDescription> = self.song_list_model().collect();
+        self.dispatcher
+            .dispatch(PlaybackAction::LoadSongs(tracks).into());
+        self.dispatcher
+            .dispatch(PlaybackAction::Load(id.to_string()).into());
+    }
+
+    fn autoscroll_to_playing(&self) -> bool {
+        false
+    }
+
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
+    fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let group = SimpleActionGroup::new();
+
+        for view_artist in song.make_artist_actions(self.dispatcher.box_clone(), None) {
+            group.add_action(&view_artist);
+        }
+        group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
+        group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
+        group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
+
+        Some(group.upcast())
+    }
+
+    fn menu_for(&self, id: &str) -> Option<gio::MenuModel> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let menu = gio::Menu::new();
+        menu.append(Some(&*labels::VIEW_ALBUM), Some("song.view_album"));
+        for artist in song.artists.iter() {
+            menu.append(
+                Some(&labels::more_from_label(&artist.name)),
+                Some(&format!("song.view_artist_{}", artist.id)),
+            );
+        }
+
+        menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
+        menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
+        Some(menu.upcast())
+    }
+
+    fn select_song(&self, id: &str) {
+        let song = self.song_list_model().get(id);
+        if let Some(song) = song {
+            self.dispatcher
+                .dispatch(SelectionAction::Select(vec![song.into_description()]).into());
+        }
+    }
+
+    fn deselect_song(&self, id: &str) {
+        self.dispatcher
+            .dispatch(SelectionAction::Deselect(vec![id.to_string()]).into());
+    }
+
+    fn enable_selection(&self) -> bool {
+        self.dispatcher
+            .dispatch(AppAction::EnableSelection(SelectionContext::Default));
+        true
+    }
+
+    fn selection(&self) -> Option<Box<dyn Deref<Target = SelectionState> + '_>> {
+        Some(Box::new(self.app_model.map_state(|s| &s.selection)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::state::ScreenName;
+    use crate::app::testing::{song, user, TestApp};
+
+    #[test]
+    fn test_load_more_repeated_plays() {
+        // 60 different songs, the first 10 of which were played twice
+        let plays = (0..70).map(|i| song(&format!("song{}", i % 60))).collect();
+        let api = FakeSpotifyClient::new()
+            .with_user(user("me"))
+            .with_recently_played(plays);
+        let app = TestApp::logged_in(api, "me");
+        app.dispatch(BrowserAction::NavigationPush(ScreenName::RecentlyPlayed).into());
+        app.run_until_idle();
+
+        let model = RecentlyPlayedModel::new(app.model.clone(), app.dispatcher());
+        model.load_initial();
+        app.run_until_idle();
+        assert_eq!(model.song_list_model().partial_len(), 50);
+
+        assert!(model.load_more().is_some());
+        app.run_until_idle();
+        assert_eq!(model.song_list_model().partial_len(), 60);
+
+        // The whole history is loaded
+        assert!(model.load_more().is_none());
+    }
+}
@@ -0,0 +1,5 @@
+mod saved_shows;
+mod saved_shows_model;
+
+pub use saved_shows::*;
+pub use saved_shows_model::*;
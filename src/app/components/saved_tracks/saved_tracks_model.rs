@@ -3,7 +3,7 @@ use gio::SimpleActionGroup;
 use std::ops::Deref;
 use std::rc::Rc;
 
-use crate::app::components::{labels, PlaylistModel};
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
 use crate::app::models::*;
 use crate::app::state::SelectionContext;
 use crate::app::state::{PlaybackAction, SelectionAction, SelectionState};
@@ -86,6 +86,10 @@ impl PlaylistModel for SavedTracksModel {
         true
     }
 
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
     fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
         let song = self.song_list_model().get(id)?;
         let song = song.description();
@@ -97,6 +101,11 @@ impl PlaylistModel for SavedTracksModel {
         }
         group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
         group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
 
         Some(group.upcast())
     }
@@ -115,6 +124,7 @@ impl PlaylistModel for SavedTracksModel {
         }
 
         menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
 
         Some(menu.upcast())
     }
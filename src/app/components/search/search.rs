@@ -4,10 +4,11 @@ use gtk::CompositeTemplate;
 use std::rc::Rc;
 
 use crate::app::components::utils::{wrap_flowbox_item, Debouncer};
-use crate::app::components::{AlbumWidget, ArtistWidget, Component, EventListener};
+use crate::app::components::{AlbumWidget, ArtistWidget, Component, EventListener, Playlist};
 use crate::app::dispatch::Worker;
-use crate::app::models::{AlbumModel, ArtistModel};
+use crate::app::models::{AlbumModel, ArtistModel, SearchCategory};
 use crate::app::state::{AppEvent, BrowserEvent};
+use crate::app::ListStore;
 
 use super::SearchResultsModel;
 mod imp {
@@ -30,13 +31,52 @@ mod imp {
         pub status_page: TemplateChild<libadwaita::StatusPage>,
 
         #[template_child]
-        pub search_results: TemplateChild<gtk::Widget>,
+        pub search_results: TemplateChild<gtk::ScrolledWindow>,
+
+        #[template_child]
+        pub songs_section: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        pub songs_see_all: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub song_results: TemplateChild<gtk::ListView>,
+
+        #[template_child]
+        pub albums_section: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        pub albums_see_all: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub albums_scroll: TemplateChild<gtk::ScrolledWindow>,
 
         #[template_child]
         pub albums_results: TemplateChild<gtk::FlowBox>,
 
+        #[template_child]
+        pub artists_section: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        pub artists_see_all: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub artists_scroll: TemplateChild<gtk::ScrolledWindow>,
+
         #[template_child]
         pub artist_results: TemplateChild<gtk::FlowBox>,
+
+        #[template_child]
+        pub playlists_section: TemplateChild<gtk::Box>,
+
+        #[template_child]
+        pub playlists_see_all: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub playlists_scroll: TemplateChild<gtk::ScrolledWindow>,
+
+        #[template_child]
+        pub playlist_results: TemplateChild<gtk::FlowBox>,
     }
 
     #[glib::object_subclass]
@@ -108,31 +148,116 @@ impl SearchResultsWidget {
             }));
     }
 
-    fn bind_albums_results<F>(&self, worker: Worker, store: &gio::ListStore, on_album_pressed: F)
+    fn connect_bottom_edge<F>(&self, f: F)
     where
-        F: Fn(String) + Clone + 'static,
+        F: Fn() + 'static,
     {
         self.imp()
-            .albums_results
-            .bind_model(Some(store), move |item| {
-                wrap_flowbox_item(item, |album_model| {
-                    let f = on_album_pressed.clone();
-                    let album = AlbumWidget::for_model(album_model, worker.clone());
-                    album.connect_album_pressed(clone!(@weak album_model => move |_| {
-                        f(album_model.uri());
-                    }));
-                    album
-                })
+            .search_results
+            .connect_edge_reached(move |_, pos| {
+                if let gtk::PositionType::Bottom = pos {
+                    f()
+                }
             });
     }
 
-    fn bind_artists_results<F>(&self, worker: Worker, store: &gio::ListStore, on_artist_pressed: F)
+    fn connect_see_all<F>(&self, f: F)
     where
+        F: Fn(SearchCategory) + Clone + 'static,
+    {
+        for category in SearchCategory::all() {
+            let f = f.clone();
+            self.see_all_button(category)
+                .connect_clicked(move |_| f(category));
+        }
+    }
+
+    fn song_results_widget(&self) -> &gtk::ListView {
+        self.imp().song_results.as_ref()
+    }
+
+    fn section(&self, category: SearchCategory) -> &gtk::Box {
+        let widget = self.imp();
+        match category {
+            SearchCategory::Songs => &*widget.songs_section,
+            SearchCategory::Albums => &*widget.albums_section,
+            SearchCategory::Artists => &*widget.artists_section,
+            SearchCategory::Playlists => &*widget.playlists_section,
+        }
+    }
+
+    fn see_all_button(&self, category: SearchCategory) -> &gtk::Button {
+        let widget = self.imp();
+        match category {
+            SearchCategory::Songs => &*widget.songs_see_all,
+            SearchCategory::Albums => &*widget.albums_see_all,
+            SearchCategory::Artists => &*widget.artists_see_all,
+            SearchCategory::Playlists => &*widget.playlists_see_all,
+        }
+    }
+
+    // In the overview, results are laid out on a single row that scrolls horizontally;
+    // when browsing a single category, they wrap into a grid that grows vertically.
+    fn set_category(&self, category: Option<SearchCategory>) {
+        for c in SearchCategory::all() {
+            self.section(c)
+                .set_visible(category.map(|cat| cat == c).unwrap_or(true));
+            self.see_all_button(c).set_visible(category.is_none());
+        }
+
+        let widget = self.imp();
+        let grids = [
+            (&*widget.albums_results, &*widget.albums_scroll),
+            (&*widget.artist_results, &*widget.artists_scroll),
+            (&*widget.playlist_results, &*widget.playlists_scroll),
+        ];
+        for (flowbox, scrolled_window) in grids {
+            if category.is_some() {
+                flowbox.set_orientation(gtk::Orientation::Horizontal);
+                flowbox.set_max_children_per_line(20);
+                scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Never);
+            } else {
+                flowbox.set_orientation(gtk::Orientation::Vertical);
+                flowbox.set_max_children_per_line(1);
+                scrolled_window.set_hscrollbar_policy(gtk::PolicyType::Automatic);
+            }
+        }
+
+        widget.search_results.vadjustment().set_value(0.0);
+    }
+
+    fn bind_albums_results<F>(
+        &self,
+        flowbox: &gtk::FlowBox,
+        worker: Worker,
+        store: &ListStore<AlbumModel>,
+        on_album_pressed: F,
+    ) where
+        F: Fn(String) + Clone + 'static,
+    {
+        flowbox.bind_model(Some(store.unsafe_store()), move |item| {
+            wrap_flowbox_item(item, |album_model| {
+                let f = on_album_pressed.clone();
+                let album = AlbumWidget::for_model(album_model, worker.clone());
+                album.connect_album_pressed(clone!(@weak album_model => move |_| {
+                    f(album_model.uri());
+                }));
+                album
+            })
+        });
+    }
+
+    fn bind_artists_results<F>(
+        &self,
+        worker: Worker,
+        store: &ListStore<ArtistModel>,
+        on_artist_pressed: F,
+    ) where
         F: Fn(String) + Clone + 'static,
     {
         self.imp()
             .artist_results
-            .bind_model(Some(store), move |item| {
+            .bind_model(Some(store.unsafe_store()), move |item| {
                 wrap_flowbox_item(item, |artist_model| {
                     let f = on_artist_pressed.clone();
                     let artist = ArtistWidget::for_model(artist_model, worker.clone());
@@ -148,9 +273,8 @@ impl SearchResultsWidget {
 pub struct SearchResults {
     widget: SearchResultsWidget,
     model: Rc<SearchResultsModel>,
-    album_results_model: gio::ListStore,
-    artist_results_model: gio::ListStore,
     debouncer: Debouncer,
+    children: Vec<Box<dyn EventListener>>,
 }
 
 impl SearchResults {
@@ -158,9 +282,6 @@ impl SearchResults {
         let model = Rc::new(model);
         let widget = SearchResultsWidget::new();
 
-        let album_results_model = gio::ListStore::new(AlbumModel::static_type());
-        let artist_results_model = gio::ListStore::new(ArtistModel::static_type());
-
         widget.bind_to_leaflet(leaflet);
 
         widget.connect_go_back(clone!(@weak model => move || {
@@ -171,53 +292,59 @@ impl SearchResults {
             model.search(q);
         }));
 
-        widget.bind_albums_results(
-            worker.clone(),
-            &album_results_model,
-            clone!(@weak model => move |uri| {
-                model.open_album(uri);
-            }),
-        );
+        widget.connect_see_all(clone!(@weak model => move |category| {
+            model.show_category(category);
+        }));
 
-        widget.bind_artists_results(
+        widget.connect_bottom_edge(clone!(@weak model => move || {
+            model.load_more();
+        }));
+
+        if let Some(store) = model.get_album_results() {
+            widget.bind_albums_results(
+                &widget.imp().albums_results,
+                worker.clone(),
+                &store,
+                clone!(@weak model => move |uri| {
+                    model.open_album(uri);
+                }),
+            );
+        }
+
+        if let Some(store) = model.get_artist_results() {
+            widget.bind_artists_results(
+                worker.clone(),
+                &store,
+                clone!(@weak model => move |id| {
+                    model.open_artist(id);
+                }),
+            );
+        }
+
+        if let Some(store) = model.get_playlist_results() {
+            widget.bind_albums_results(
+                &widget.imp().playlist_results,
+                worker.clone(),
+                &store,
+                clone!(@weak model => move |id| {
+                    model.open_playlist(id);
+                }),
+            );
+        }
+
+        let playlist = Playlist::new(
+            widget.song_results_widget().clone(),
+            Rc::clone(&model),
             worker,
-            &artist_results_model,
-            clone!(@weak model => move |id| {
-                model.open_artist(id);
-            }),
         );
 
+        widget.set_category(model.category());
+
         Self {
             widget,
             model,
-            album_results_model,
-            artist_results_model,
             debouncer: Debouncer::new(),
-        }
-    }
-
-    fn update_results(&self) {
-        if let Some(results) = self.model.get_album_results() {
-            self.album_results_model.remove_all();
-            for album in results.iter() {
-                self.album_results_model.append(&AlbumModel::new(
-                    &album.artists_name(),
-                    &album.title,
-                    album.year(),
-                    album.art.as_ref(),
-                    &album.id,
-                ));
-            }
-        }
-        if let Some(results) = self.model.get_artist_results() {
-            self.artist_results_model.remove_all();
-            for artist in results.iter() {
-                self.artist_results_model.append(&ArtistModel::new(
-                    &artist.name,
-                    &artist.photo,
-                    &artist.id,
-                ));
-            }
+            children: vec![Box::new(playlist)],
         }
     }
 
@@ -233,6 +360,10 @@ impl Component for SearchResults {
     fn get_root_widget(&self) -> &gtk::Widget {
         self.widget.as_ref()
     }
+
+    fn get_children(&mut self) -> Option<&mut Vec<Box<dyn EventListener>>> {
+        Some(&mut self.children)
+    }
 }
 
 impl EventListener for SearchResults {
@@ -242,10 +373,14 @@ impl EventListener for SearchResults {
                 self.get_root_widget().grab_focus();
                 self.update_search_query();
             }
-            AppEvent::BrowserEvent(BrowserEvent::SearchResultsUpdated) => {
-                self.update_results();
+            AppEvent::BrowserEvent(BrowserEvent::SearchCategoryChanged(category)) => {
+                self.widget.set_category(*category);
+                if category.is_some() {
+                    self.model.load_more();
+                }
             }
             _ => {}
         }
+        self.broadcast_event(app_event);
     }
 }
@@ -1,13 +1,24 @@
+use gio::prelude::*;
+use gio::SimpleActionGroup;
+use std::cell::Ref;
 use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
 use crate::app::dispatch::ActionDispatcher;
 use crate::app::models::*;
-use crate::app::state::{AppAction, AppModel, BrowserAction};
+use crate::app::state::{
+    AppAction, AppModel, BrowserAction, PlaybackAction, SearchState, SelectionAction,
+    SelectionContext, SelectionState,
+};
+use crate::app::ListStore;
 
 pub struct SearchResultsModel {
     app_model: Rc<AppModel>,
     dispatcher: Box<dyn ActionDispatcher>,
+    // The page being loaded, so that scrolling does not request it again in the meantime
+    pending_page: Arc<Mutex<Option<(String, SearchCategory, usize)>>>,
 }
 
 impl SearchResultsModel {
@@ -15,12 +26,21 @@ impl SearchResultsModel {
         Self {
             app_model,
             dispatcher,
+            pending_page: Arc::new(Mutex::new(None)),
         }
     }
 
+    fn state(&self) -> Option<Ref<'_, SearchState>> {
+        self.app_model.map_state_opt(|s| s.browser.search_state())
+    }
+
     pub fn go_back(&self) {
-        self.dispatcher
-            .dispatch(BrowserAction::NavigationPop.into());
+        if self.category().is_some() {
+            self.show_overview();
+        } else {
+            self.dispatcher
+                .dispatch(BrowserAction::NavigationPop.into());
+        }
     }
 
     pub fn search(&self, query: String) {
@@ -35,25 +55,77 @@ impl SearchResultsModel {
 
     pub fn fetch_results(&self) {
         let api = self.app_model.get_spotify();
+        let limit = match self.state() {
+            Some(state) => state.overview_size,
+            None => return,
+        };
         if let Some(query) = self.get_query() {
             let query = query.to_owned();
             self.dispatcher
                 .call_spotify_and_dispatch(move || async move {
-                    api.search(&query, 0, 5)
+                    api.search(&query, SearchCategory::all(), 0, limit)
                         .await
                         .map(|results| BrowserAction::SetSearchResults(Box::new(results)).into())
                 });
         }
     }
 
-    pub fn get_album_results(&self) -> Option<impl Deref<Target = Vec<AlbumDescription>> + '_> {
-        self.app_model
-            .map_state_opt(|s| Some(&s.browser.search_state()?.album_results))
+    pub fn category(&self) -> Option<SearchCategory> {
+        self.state()?.category()
     }
 
-    pub fn get_artist_results(&self) -> Option<impl Deref<Target = Vec<ArtistSummary>> + '_> {
-        self.app_model
-            .map_state_opt(|s| Some(&s.browser.search_state()?.artist_results))
+    pub fn show_category(&self, category: SearchCategory) {
+        self.dispatcher
+            .dispatch(BrowserAction::ShowSearchCategory(Some(category)).into());
+    }
+
+    pub fn show_overview(&self) {
+        self.dispatcher
+            .dispatch(BrowserAction::ShowSearchCategory(None).into());
+    }
+
+    pub fn load_more(&self) -> Option<()> {
+        let api = self.app_model.get_spotify();
+        let query = self.get_query()?.to_owned();
+
+        let state = self.state()?;
+        let next_page = state.category_page.as_ref()?;
+        let category = next_page.data;
+        let batch_size = next_page.batch_size;
+        let offset = next_page.next_offset?;
+
+        let page = Some((query.clone(), category, offset));
+        if *self.pending_page.lock().unwrap() == page {
+            return None;
+        }
+        *self.pending_page.lock().unwrap() = page;
+
+        let pending_page = Arc::clone(&self.pending_page);
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                let results = api.search(&query, vec![category], offset, batch_size).await;
+                // Asking again is fine once it failed
+                if results.is_err() {
+                    *pending_page.lock().unwrap() = None;
+                }
+                results.map(|results| {
+                    BrowserAction::AppendSearchResults(query, category, Box::new(results)).into()
+                })
+            });
+
+        Some(())
+    }
+
+    pub fn get_album_results(&self) -> Option<impl Deref<Target = ListStore<AlbumModel>> + '_> {
+        Some(Ref::map(self.state()?, |s| &s.album_results))
+    }
+
+    pub fn get_artist_results(&self) -> Option<impl Deref<Target = ListStore<ArtistModel>> + '_> {
+        Some(Ref::map(self.state()?, |s| &s.artist_results))
+    }
+
+    pub fn get_playlist_results(&self) -> Option<impl Deref<Target = ListStore<AlbumModel>> + '_> {
+        Some(Ref::map(self.state()?, |s| &s.playlist_results))
     }
 
     pub fn open_album(&self, id: String) {
@@ -63,4 +135,125 @@ impl SearchResultsModel {
     pub fn open_artist(&self, id: String) {
         self.dispatcher.dispatch(AppAction::ViewArtist(id));
     }
+
+    pub fn open_playlist(&self, id: String) {
+        self.dispatcher.dispatch(AppAction::ViewPlaylist(id));
+    }
+}
+
+impl PlaylistModel for SearchResultsModel {
+    fn song_list_model(&self) -> SongListModel {
+        self.state()
+            .expect("illegal attempt to read search_state")
+            .song_results
+            .clone()
+    }
+
+    fn current_song_id(&self) -> Option<String> {
+        self.app_model.get_state().playback.current_song_id()
+    }
+
+    fn play_song_at(&self, _pos: usize, id: &str) {
+        let tracks: Vec<SongDescription> = self.song_list_model().collect();
+        self.dispatcher
+            .dispatch(PlaybackAction::LoadSongs(tracks).into());
+        self.dispatcher
+            .dispatch(PlaybackAction::Load(id.to_string()).into());
+    }
+
+    fn autoscroll_to_playing(&self) -> bool {
+        false
+    }
+
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
+    fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let group = SimpleActionGroup::new();
+
+        for view_artist in song.make_artist_actions(self.dispatcher.box_clone(), None) {
+            group.add_action(&view_artist);
+        }
+        group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
+        group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
+        group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
+
+        Some(group.upcast())
+    }
+
+    fn menu_for(&self, id: &str) -> Option<gio::MenuModel> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let menu = gio::Menu::new();
+        menu.append(Some(&*labels::VIEW_ALBUM), Some("song.view_album"));
+        for artist in song.artists.iter() {
+            menu.append(
+                Some(&labels::more_from_label(&artist.name)),
+                Some(&format!("song.view_artist_{}", artist.id)),
+            );
+        }
+
+        menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
+        menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
+        Some(menu.upcast())
+    }
+
+    fn select_song(&self, id: &str) {
+        let song = self.song_list_model().get(id);
+        if let Some(song) = song {
+            self.dispatcher
+                .dispatch(SelectionAction::Select(vec![song.into_description()]).into());
+        }
+    }
+
+    fn deselect_song(&self, id: &str) {
+        self.dispatcher
+            .dispatch(SelectionAction::Deselect(vec![id.to_string()]).into());
+    }
+
+    fn enable_selection(&self) -> bool {
+        self.dispatcher
+            .dispatch(AppAction::EnableSelection(SelectionContext::Default));
+        true
+    }
+
+    fn selection(&self) -> Option<Box<dyn Deref<Target = SelectionState> + '_>> {
+        Some(Box::new(self.app_model.map_state(|s| &s.selection)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::{album, TestApp};
+
+    #[test]
+    fn test_load_more_once() {
+        let api = FakeSpotifyClient::new().with_album(album("album0", vec![]));
+        let app = TestApp::new(api);
+        let model = SearchResultsModel::new(app.model.clone(), app.dispatcher());
+        model.search("album".to_owned());
+        model.show_category(SearchCategory::Albums);
+        app.run_until_idle();
+
+        // the page is still on its way when asked for again
+        assert!(model.load_more().is_some());
+        assert!(model.load_more().is_none());
+        app.run_until_idle();
+        assert_eq!(app.api.calls(), vec!["search"]);
+        assert_eq!(model.get_album_results().unwrap().len(), 1);
+    }
 }
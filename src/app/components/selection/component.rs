@@ -3,6 +3,7 @@ use gtk::prelude::*;
 use std::ops::Deref;
 use std::rc::Rc;
 
+use crate::app::components::share::{copy_to_clipboard, songs_as_links, songs_as_text};
 use crate::app::components::{Component, EventListener};
 use crate::app::models::PlaylistSummary;
 use crate::app::state::{
@@ -68,6 +69,14 @@ impl SelectionToolbarModel {
             })
     }
 
+    pub fn copy_selection_links(&self) {
+        copy_to_clipboard(&songs_as_links(self.selection().peek_selection()));
+    }
+
+    pub fn copy_selection_text(&self) {
+        copy_to_clipboard(&songs_as_text(self.selection().peek_selection()));
+    }
+
     fn remove_saved_tracks(&self) {
         let api = self.app_model.get_spotify();
         let ids: Vec<String> = self
@@ -144,11 +153,16 @@ impl SelectionToolbar {
         widget.connect_queue(clone!(@weak model => move || model.queue_selection()));
         widget.connect_remove(clone!(@weak model => move || model.remove_selection()));
         widget.connect_save(clone!(@weak model => move || model.save_selection()));
+        widget.connect_copy(
+            clone!(@weak model => move || model.copy_selection_links()),
+            clone!(@weak model => move || model.copy_selection_text()),
+        );
         Self { model, widget }
     }
 
     fn update_active_tools(&self) {
         let count = self.model.selected_count();
+        self.widget.set_copy(SelectionToolState::Visible(count > 0));
         match self.model.selection().context {
             SelectionContext::Default => {
                 self.widget.set_move(SelectionToolState::Hidden);
@@ -34,6 +34,9 @@ mod imp {
 
         #[template_child]
         pub save: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub copy: TemplateChild<gtk::MenuButton>,
     }
 
     #[glib::object_subclass]
@@ -118,6 +121,29 @@ impl SelectionToolbarWidget {
         self.imp().remove.connect_clicked(move |_| f());
     }
 
+    pub fn connect_copy<F, G>(&self, on_copy_links: F, on_copy_text: G)
+    where
+        F: Fn() + 'static,
+        G: Fn() + 'static,
+    {
+        let action_group = SimpleActionGroup::new();
+        let links = SimpleAction::new("links", None);
+        links.connect_activate(move |_, _| on_copy_links());
+        action_group.add_action(&links);
+        let text = SimpleAction::new("text", None);
+        text.connect_activate(move |_, _| on_copy_text());
+        action_group.add_action(&text);
+
+        let menu = gio::Menu::new();
+        menu.append(Some(&*labels::COPY_LINKS), Some("copy.links"));
+        menu.append(Some(&*labels::COPY_AS_TEXT), Some("copy.text"));
+
+        self.imp().copy.set_menu_model(Some(&menu));
+        self.imp()
+            .copy
+            .insert_action_group("copy", Some(&action_group));
+    }
+
     pub fn set_move(&self, state: SelectionToolState) {
         self.imp().move_up.set_sensitive(state.sensitive());
         self.imp().move_up.set_visible(state.visible());
@@ -145,6 +171,11 @@ impl SelectionToolbarWidget {
         self.imp().save.set_visible(state.visible());
     }
 
+    pub fn set_copy(&self, state: SelectionToolState) {
+        self.imp().copy.set_sensitive(state.sensitive());
+        self.imp().copy.set_visible(state.visible());
+    }
+
     pub fn set_visible(&self, visible: bool) {
         gtk::Widget::set_visible(self.upcast_ref(), visible);
         self.imp().action_bar.set_revealed(visible);
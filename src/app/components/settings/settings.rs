@@ -1,5 +1,6 @@
-use crate::app::components::EventListener;
+use crate::app::components::{labels, EventListener};
 use crate::app::AppEvent;
+use crate::player::{list_pulseaudio_sinks, AudioSink};
 use crate::settings::SpotSettings;
 
 use gtk::prelude::*;
@@ -31,9 +32,75 @@ mod imp {
         #[template_child]
         pub audio_backend: TemplateChild<libadwaita::ComboRow>,
 
+        #[template_child]
+        pub pulseaudio_sink: TemplateChild<libadwaita::ComboRow>,
+
+        #[template_child]
+        pub pipe_path: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub pipe_path_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub subprocess_command: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub subprocess_command_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub sample_format: TemplateChild<libadwaita::ComboRow>,
+
+        #[template_child]
+        pub mixer: TemplateChild<libadwaita::ComboRow>,
+
+        #[template_child]
+        pub alsa_mixer_control: TemplateChild<gtk::Entry>,
+
+        #[template_child]
+        pub alsa_mixer_control_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub alsa_mixer_card: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub alsa_mixer_card_row: TemplateChild<libadwaita::ActionRow>,
+
         #[template_child]
         pub gapless_playback: TemplateChild<libadwaita::ActionRow>,
 
+        #[template_child]
+        pub autoplay: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub normalisation: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub normalisation_type: TemplateChild<libadwaita::ComboRow>,
+
+        #[template_child]
+        pub normalisation_pregain: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub normalisation_pregain_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub normalisation_limiter: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub normalisation_threshold: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub normalisation_threshold_row: TemplateChild<libadwaita::ActionRow>,
+
+        #[template_child]
+        pub volume_control: TemplateChild<libadwaita::ComboRow>,
+
+        #[template_child]
+        pub volume_range: TemplateChild<gtk::SpinButton>,
+
+        #[template_child]
+        pub volume_range_row: TemplateChild<libadwaita::ActionRow>,
+
         #[template_child]
         pub ap_port: TemplateChild<gtk::Entry>,
 
@@ -72,7 +139,10 @@ impl SettingsWindow {
         let window: Self = glib::Object::new();
 
         window.bind_backend_and_device();
+        window.bind_mixer_and_control();
         window.bind_settings();
+        window.bind_pulseaudio_sink();
+        window.bind_volume_rows();
         window.connect_theme_select();
         window
     }
@@ -93,12 +163,159 @@ impl SettingsWindow {
             .bind_property("selected", alsa_device_row, "visible")
             .transform_to(|_, value: u32| Some(value == 1))
             .build();
+        audio_backend
+            .bind_property("selected", &*widget.pulseaudio_sink, "visible")
+            .transform_to(|_, value: u32| Some(value == 0))
+            .build();
+        audio_backend
+            .bind_property("selected", &*widget.pipe_path_row, "visible")
+            .transform_to(|_, value: u32| Some(value == 2))
+            .build();
+        audio_backend
+            .bind_property("selected", &*widget.subprocess_command_row, "visible")
+            .transform_to(|_, value: u32| Some(value == 3))
+            .build();
+        audio_backend
+            .bind_property("selected", &*widget.sample_format, "visible")
+            .transform_to(|_, value: u32| Some(value == 2 || value == 3))
+            .build();
 
         if audio_backend.selected() == 0 {
             alsa_device_row.set_visible(false);
+            widget.pipe_path_row.set_visible(false);
+            widget.subprocess_command_row.set_visible(false);
+            widget.sample_format.set_visible(false);
+        }
+    }
+
+    // Listing the sinks blocks, so it waits for the PulseAudio backend to be picked
+    fn bind_pulseaudio_sink(&self) {
+        let audio_backend = self
+            .imp()
+            .audio_backend
+            .downcast_ref::<libadwaita::ComboRow>()
+            .unwrap();
+        if audio_backend.selected() == 0 {
+            self.fill_pulseaudio_sinks();
+            return;
+        }
+        audio_backend.connect_selected_notify(clone!(@weak self as window => move |row| {
+            let is_filled = window.imp().pulseaudio_sink.model().is_some();
+            if row.selected() == 0 && !is_filled {
+                window.fill_pulseaudio_sinks();
+            }
+        }));
+    }
+
+    // The sinks are only known at runtime, and listing them blocks the thread it runs on
+    fn fill_pulseaudio_sinks(&self) {
+        let weak_self = self.downgrade();
+        glib::MainContext::default().spawn_local(async move {
+            let sinks = gio::spawn_blocking(list_pulseaudio_sinks)
+                .await
+                .unwrap_or_default();
+            if let Some(window) = weak_self.upgrade() {
+                window.set_pulseaudio_sinks(sinks);
+            }
+        });
+    }
+
+    // The first entry stands for the default sink
+    fn set_pulseaudio_sinks(&self, mut sinks: Vec<AudioSink>) {
+        let widget = self.imp();
+        let pulseaudio_sink = widget
+            .pulseaudio_sink
+            .downcast_ref::<libadwaita::ComboRow>()
+            .unwrap();
+        // Filled already, by a listing that got there first
+        if pulseaudio_sink.model().is_some() {
+            return;
+        }
+
+        let settings = gio::Settings::new(SETTINGS);
+        let current = settings.string("pulseaudio-sink").to_string();
+
+        // An unplugged sink is kept around rather than silently replaced with the default one
+        if !current.is_empty() && !sinks.iter().any(|sink| sink.name == current) {
+            sinks.push(AudioSink {
+                name: current.clone(),
+                description: current.clone(),
+            });
+        }
+
+        let model = gtk::StringList::new(&[]);
+        model.append(&labels::DEFAULT_OUTPUT);
+        for sink in sinks.iter() {
+            model.append(&sink.description);
+        }
+
+        pulseaudio_sink.set_model(Some(&model));
+        let selected = sinks
+            .iter()
+            .position(|sink| sink.name == current)
+            .map(|i| i as u32 + 1)
+            .unwrap_or(0);
+        pulseaudio_sink.set_selected(selected);
+
+        pulseaudio_sink.connect_selected_notify(move |row| {
+            let name = match row.selected() {
+                0 => None,
+                i => sinks.get(i as usize - 1).map(|sink| sink.name.as_str()),
+            };
+            let _ = settings.set_string("pulseaudio-sink", name.unwrap_or_default());
+        });
+    }
+
+    fn bind_mixer_and_control(&self) {
+        let widget = self.imp();
+
+        let mixer = widget.mixer.downcast_ref::<libadwaita::ComboRow>().unwrap();
+        let alsa_rows: [&libadwaita::ActionRow; 2] =
+            [&widget.alsa_mixer_control_row, &widget.alsa_mixer_card_row];
+        for row in alsa_rows {
+            mixer
+                .bind_property("selected", row, "visible")
+                .transform_to(|_, value: u32| Some(value == 1))
+                .build();
+
+            if mixer.selected() == 0 {
+                row.set_visible(false);
+            }
         }
     }
 
+    // Only show what applies to the chosen normalisation and volume control
+    fn bind_volume_rows(&self) {
+        let widget = self.imp();
+
+        let normalisation = widget.normalisation.activatable_widget().unwrap();
+        let normalisation_rows: [&gtk::Widget; 4] = [
+            widget.normalisation_type.upcast_ref(),
+            widget.normalisation_pregain_row.upcast_ref(),
+            widget.normalisation_limiter.upcast_ref(),
+            widget.normalisation_threshold_row.upcast_ref(),
+        ];
+        for row in normalisation_rows {
+            normalisation
+                .bind_property("active", row, "sensitive")
+                .flags(glib::BindingFlags::SYNC_CREATE)
+                .build();
+        }
+
+        let limiter = widget.normalisation_limiter.activatable_widget().unwrap();
+        limiter
+            .bind_property("active", &*widget.normalisation_threshold_row, "visible")
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+
+        widget
+            .volume_control
+            .bind_property("selected", &*widget.volume_range_row, "visible")
+            .transform_to(|_, value: u32| Some(value == 1 || value == 2))
+            .flags(glib::BindingFlags::SYNC_CREATE)
+            .build();
+    }
+
     fn bind_settings(&self) {
         let widget = self.imp();
         let settings = gio::Settings::new(SETTINGS);
@@ -147,6 +364,8 @@ impl SettingsWindow {
                     match s {
                         "pulseaudio" => 0,
                         "alsa" => 1,
+                        "pipe" => 2,
+                        "subprocess" => 3,
                         _ => unreachable!(),
                     }
                     .to_value()
@@ -157,6 +376,80 @@ impl SettingsWindow {
                     match u {
                         0 => "pulseaudio",
                         1 => "alsa",
+                        2 => "pipe",
+                        3 => "subprocess",
+                        _ => unreachable!(),
+                    }
+                    .to_variant()
+                })
+            })
+            .build();
+
+        let pipe_path = widget.pipe_path.downcast_ref::<gtk::Entry>().unwrap();
+        settings.bind("pipe-path", pipe_path, "text").build();
+
+        let subprocess_command = widget
+            .subprocess_command
+            .downcast_ref::<gtk::Entry>()
+            .unwrap();
+        settings
+            .bind("subprocess-command", subprocess_command, "text")
+            .build();
+
+        let sample_format = widget
+            .sample_format
+            .downcast_ref::<libadwaita::ComboRow>()
+            .unwrap();
+        settings
+            .bind("sample-format", sample_format, "selected")
+            .mapping(|variant, _| {
+                variant.str().map(|s| {
+                    match s {
+                        "f64" => 0,
+                        "f32" => 1,
+                        "s32" => 2,
+                        "s24" => 3,
+                        "s24-3" => 4,
+                        "s16" => 5,
+                        _ => unreachable!(),
+                    }
+                    .to_value()
+                })
+            })
+            .set_mapping(|value, _| {
+                value.get::<u32>().ok().map(|u| {
+                    match u {
+                        0 => "f64",
+                        1 => "f32",
+                        2 => "s32",
+                        3 => "s24",
+                        4 => "s24-3",
+                        5 => "s16",
+                        _ => unreachable!(),
+                    }
+                    .to_variant()
+                })
+            })
+            .build();
+
+        let mixer = widget.mixer.downcast_ref::<libadwaita::ComboRow>().unwrap();
+        settings
+            .bind("mixer", mixer, "selected")
+            .mapping(|variant, _| {
+                variant.str().map(|s| {
+                    match s {
+                        "soft" => 0,
+                        "alsa" => 1,
+                        _ => unreachable!(),
+                    }
+                    .to_value()
+                })
+            })
+            .set_mapping(|value, _| {
+                value.get::<u32>().ok().map(|u| {
+                    match u {
+                        0 => "soft",
+                        1 => "alsa",
                         _ => unreachable!(),
                     }
                     .to_variant()
@@ -164,6 +457,24 @@ impl SettingsWindow {
             })
             .build();
 
+        let alsa_mixer_control = widget
+            .alsa_mixer_control
+            .downcast_ref::<gtk::Entry>()
+            .unwrap();
+        settings
+            .bind("alsa-mixer-control", alsa_mixer_control, "text")
+            .build();
+
+        let alsa_mixer_card = widget
+            .alsa_mixer_card
+            .downcast_ref::<gtk::SpinButton>()
+            .unwrap();
+        settings
+            .bind("alsa-mixer-card", alsa_mixer_card, "value")
+            .mapping(|variant, _| variant.get::<u32>().map(|u| f64::from(u).to_value()))
+            .set_mapping(|value, _| value.get::<f64>().ok().map(|f| (f as u32).to_variant()))
+            .build();
+
         let gapless_playback = widget
             .gapless_playback
             .downcast_ref::<libadwaita::ActionRow>()
@@ -176,6 +487,124 @@ impl SettingsWindow {
             )
             .build();
 
+        let autoplay = widget
+            .autoplay
+            .downcast_ref::<libadwaita::ActionRow>()
+            .unwrap();
+        settings
+            .bind(
+                "autoplay",
+                &autoplay.activatable_widget().unwrap(),
+                "active",
+            )
+            .build();
+
+        let normalisation = widget
+            .normalisation
+            .downcast_ref::<libadwaita::ActionRow>()
+            .unwrap();
+        settings
+            .bind(
+                "normalisation",
+                &normalisation.activatable_widget().unwrap(),
+                "active",
+            )
+            .build();
+
+        let normalisation_type = widget
+            .normalisation_type
+            .downcast_ref::<libadwaita::ComboRow>()
+            .unwrap();
+        settings
+            .bind("normalisation-type", normalisation_type, "selected")
+            .mapping(|variant, _| {
+                variant.str().map(|s| {
+                    match s {
+                        "track" => 0,
+                        "album" => 1,
+                        _ => unreachable!(),
+                    }
+                    .to_value()
+                })
+            })
+            .set_mapping(|value, _| {
+                value.get::<u32>().ok().map(|u| {
+                    match u {
+                        0 => "track",
+                        1 => "album",
+                        _ => unreachable!(),
+                    }
+                    .to_variant()
+                })
+            })
+            .build();
+
+        let normalisation_pregain = widget
+            .normalisation_pregain
+            .downcast_ref::<gtk::SpinButton>()
+            .unwrap();
+        settings
+            .bind("normalisation-pregain", normalisation_pregain, "value")
+            .build();
+
+        let normalisation_limiter = widget
+            .normalisation_limiter
+            .downcast_ref::<libadwaita::ActionRow>()
+            .unwrap();
+        settings
+            .bind(
+                "normalisation-limiter",
+                &normalisation_limiter.activatable_widget().unwrap(),
+                "active",
+            )
+            .build();
+
+        let normalisation_threshold = widget
+            .normalisation_threshold
+            .downcast_ref::<gtk::SpinButton>()
+            .unwrap();
+        settings
+            .bind("normalisation-threshold", normalisation_threshold, "value")
+            .build();
+
+        let volume_control = widget
+            .volume_control
+            .downcast_ref::<libadwaita::ComboRow>()
+            .unwrap();
+        settings
+            .bind("volume-control", volume_control, "selected")
+            .mapping(|variant, _| {
+                variant.str().map(|s| {
+                    match s {
+                        "linear" => 0,
+                        "log" => 1,
+                        "cubic" => 2,
+                        "fixed" => 3,
+                        _ => unreachable!(),
+                    }
+                    .to_value()
+                })
+            })
+            .set_mapping(|value, _| {
+                value.get::<u32>().ok().map(|u| {
+                    match u {
+                        0 => "linear",
+                        1 => "log",
+                        2 => "cubic",
+                        3 => "fixed",
+                        _ => unreachable!(),
+                    }
+                    .to_variant()
+                })
+            })
+            .build();
+
+        let volume_range = widget
+            .volume_range
+            .downcast_ref::<gtk::SpinButton>()
+            .unwrap();
+        settings.bind("volume-range", volume_range, "value").build();
+
         let ap_port = widget.ap_port.downcast_ref::<gtk::Entry>().unwrap();
         settings
             .bind("ap-port", ap_port, "text")
@@ -254,7 +683,13 @@ impl Settings {
 
         settings_window.connect_close(move || {
             let new_settings = SpotSettings::new_from_gsettings().unwrap_or_default();
-            if model.settings().player_settings != new_settings.player_settings {
+            let old_settings = model.settings().player_settings;
+            // Switching sinks keeps playing, anything else restarts the player
+            if old_settings != new_settings.player_settings
+                && old_settings
+                    .switched_sink(&new_settings.player_settings)
+                    .is_none()
+            {
                 model.stop_player();
             }
             model.set_settings();
@@ -0,0 +1,66 @@
+use gio::{SimpleAction, SimpleActionGroup};
+use gtk::prelude::*;
+
+use crate::app::components::labels;
+use crate::app::models::SongDescription;
+use crate::app::SpotifyUri;
+
+pub fn copy_to_clipboard(text: &str) {
+    let clipboard = gdk::Display::default().unwrap().clipboard();
+    clipboard
+        .set_content(Some(&gdk::ContentProvider::for_value(&text.to_value())))
+        .expect("Failed to set clipboard content");
+}
+
+fn make_copy_action(name: &str, text: String) -> SimpleAction {
+    let copy = SimpleAction::new(name, None);
+    copy.connect_activate(move |_, _| copy_to_clipboard(&text));
+    copy
+}
+
+// Lets a menu button copy the link or the URI of whatever a page shows
+pub fn bind_share_button(button: &gtk::MenuButton, uri: &SpotifyUri<'_>) {
+    let group = SimpleActionGroup::new();
+    group.add_action(&make_copy_action("copy_link", uri.link()));
+    group.add_action(&make_copy_action("copy_uri", uri.to_string()));
+
+    let menu = gio::Menu::new();
+    menu.append(Some(&*labels::COPY_LINK), Some("share.copy_link"));
+    menu.append(Some(&*labels::COPY_URI), Some("share.copy_uri"));
+
+    button.insert_action_group("share", Some(&group));
+    button.set_menu_model(Some(&menu));
+    button.set_visible(true);
+}
+
+// One link per line, which chat apps then expand
+pub fn songs_as_links<'a>(songs: impl Iterator<Item = &'a SongDescription>) -> String {
+    songs.map(|song| song.link()).collect::<Vec<_>>().join("\n")
+}
+
+pub fn songs_as_text<'a>(songs: impl Iterator<Item = &'a SongDescription>) -> String {
+    songs
+        .map(|song| format!("{} – {}", song.artists_name(), song.title))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::app::testing::song;
+
+    #[test]
+    fn test_songs_as_text() {
+        let songs = vec![song("1"), song("2")];
+        assert_eq!(
+            songs_as_links(songs.iter()),
+            "https://open.spotify.com/track/1\nhttps://open.spotify.com/track/2"
+        );
+        assert_eq!(
+            songs_as_text(songs.iter()),
+            "Artist – Song 1\nArtist – Song 2"
+        );
+    }
+}
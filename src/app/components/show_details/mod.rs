@@ -0,0 +1,5 @@
+mod show_details;
+mod show_details_model;
+
+pub use show_details::*;
+pub use show_details_model::*;
@@ -0,0 +1,299 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::CompositeTemplate;
+use std::rc::Rc;
+
+use super::ShowDetailsModel;
+
+use crate::app::components::{
+    AlbumHeaderWidget, Component, EventListener, HeaderBarComponent, HeaderBarWidget, Playlist,
+};
+use crate::app::dispatch::Worker;
+use crate::app::loader::ImageLoader;
+use crate::app::{AppEvent, BrowserEvent, SpotifyUri};
+
+mod imp {
+
+    use libadwaita::subclass::prelude::BinImpl;
+
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/dev/alextren/Spot/components/show_details.ui")]
+    pub struct ShowDetailsWidget {
+        #[template_child]
+        pub scrolled_window: TemplateChild<gtk::ScrolledWindow>,
+
+        #[template_child]
+        pub headerbar: TemplateChild<HeaderBarWidget>,
+
+        #[template_child]
+        pub header_revealer: TemplateChild<gtk::Revealer>,
+
+        #[template_child]
+        pub header_widget: TemplateChild<AlbumHeaderWidget>,
+
+        #[template_child]
+        pub header_mobile: TemplateChild<AlbumHeaderWidget>,
+
+        #[template_child]
+        pub show_description: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        pub show_episodes: TemplateChild<gtk::ListView>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ShowDetailsWidget {
+        const NAME: &'static str = "ShowDetailsWidget";
+        type Type = super::ShowDetailsWidget;
+        type ParentType = libadwaita::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ShowDetailsWidget {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.header_mobile.set_centered();
+            self.header_widget.hide_info();
+            self.header_mobile.hide_info();
+            self.headerbar.add_classes(&["details__headerbar"]);
+        }
+    }
+
+    impl WidgetImpl for ShowDetailsWidget {}
+    impl BinImpl for ShowDetailsWidget {}
+}
+
+glib::wrapper! {
+    pub struct ShowDetailsWidget(ObjectSubclass<imp::ShowDetailsWidget>) @extends gtk::Widget, libadwaita::Bin;
+}
+
+impl ShowDetailsWidget {
+    fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn set_header_visible(&self, visible: bool) -> bool {
+        let widget = self.imp();
+        let is_up_to_date = widget.header_revealer.reveals_child() == visible;
+        if !is_up_to_date {
+            widget.header_revealer.set_reveal_child(visible);
+            widget.headerbar.set_title_visible(true);
+            if visible {
+                widget.headerbar.add_classes(&["flat"]);
+            } else {
+                widget.headerbar.remove_classes(&["flat"]);
+            }
+        }
+        is_up_to_date
+    }
+
+    fn connect_header_visibility(&self) {
+        self.set_header_visible(true);
+
+        let scroll_controller =
+            gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll_controller.connect_scroll(
+            clone!(@weak self as _self => @default-return gtk::Inhibit(false), move |_, _, dy| {
+                let visible = dy < 0f64;
+                gtk::Inhibit(!_self.set_header_visible(visible))
+            }),
+        );
+
+        let swipe_controller = gtk::GestureSwipe::new();
+        swipe_controller.set_touch_only(true);
+        swipe_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+        swipe_controller.connect_swipe(clone!(@weak self as _self => move |_, _, dy| {
+            let visible = dy >= 0f64;
+            _self.set_header_visible(visible);
+        }));
+
+        self.imp().scrolled_window.add_controller(scroll_controller);
+        self.add_controller(swipe_controller);
+    }
+
+    fn connect_bottom_edge<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp()
+            .scrolled_window
+            .connect_edge_reached(move |_, pos| {
+                if let gtk::PositionType::Bottom = pos {
+                    f()
+                }
+            });
+    }
+
+    fn headerbar_widget(&self) -> &HeaderBarWidget {
+        self.imp().headerbar.as_ref()
+    }
+
+    fn show_episodes_widget(&self) -> &gtk::ListView {
+        self.imp().show_episodes.as_ref()
+    }
+
+    fn set_loaded(&self) {
+        let context = self.style_context();
+        context.add_class("container--loaded");
+    }
+
+    fn connect_saved<F>(&self, f: F)
+    where
+        F: Fn() + Clone + 'static,
+    {
+        self.imp().header_widget.connect_liked(f.clone());
+        self.imp().header_mobile.connect_liked(f);
+    }
+
+    fn set_saved(&self, is_saved: bool) {
+        self.imp().header_widget.set_liked(is_saved);
+        self.imp().header_mobile.set_liked(is_saved);
+    }
+
+    fn set_title_and_publisher(&self, title: &str, publisher: &str) {
+        self.imp()
+            .header_widget
+            .set_album_and_artist_and_year(title, publisher, None);
+        self.imp()
+            .header_mobile
+            .set_album_and_artist_and_year(title, publisher, None);
+        self.imp()
+            .headerbar
+            .set_title_and_subtitle(title, publisher);
+    }
+
+    fn set_description(&self, description: Option<&str>) {
+        let label = &self.imp().show_description;
+        match description {
+            Some(description) if !description.is_empty() => {
+                label.set_label(description);
+                label.set_visible(true);
+            }
+            _ => label.set_visible(false),
+        }
+    }
+
+    fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        self.imp().header_widget.set_share_uri(uri);
+        self.imp().header_mobile.set_share_uri(uri);
+    }
+
+    fn set_artwork(&self, art: &gdk_pixbuf::Pixbuf) {
+        self.imp().header_widget.set_artwork(art);
+        self.imp().header_mobile.set_artwork(art);
+    }
+}
+
+pub struct ShowDetails {
+    model: Rc<ShowDetailsModel>,
+    worker: Worker,
+    widget: ShowDetailsWidget,
+    children: Vec<Box<dyn EventListener>>,
+}
+
+impl ShowDetails {
+    pub fn new(model: Rc<ShowDetailsModel>, worker: Worker, leaflet: &libadwaita::Leaflet) -> Self {
+        if model.get_show_info().is_none() {
+            model.load_show_info();
+        }
+
+        let widget = ShowDetailsWidget::new();
+
+        let playlist = Box::new(Playlist::new(
+            widget.show_episodes_widget().clone(),
+            model.clone(),
+            worker.clone(),
+        ));
+
+        let headerbar_widget = widget.headerbar_widget();
+        headerbar_widget.bind_to_leaflet(leaflet);
+        let headerbar = Box::new(HeaderBarComponent::new(
+            headerbar_widget.clone(),
+            model.to_headerbar_model(),
+        ));
+
+        widget.connect_saved(clone!(@weak model => move || model.toggle_save_show()));
+
+        widget.connect_header_visibility();
+        widget.set_share_uri(&SpotifyUri::Show(&model.id));
+
+        widget.connect_bottom_edge(clone!(@weak model => move || {
+            model.load_more();
+        }));
+
+        Self {
+            model,
+            worker,
+            widget,
+            children: vec![playlist, headerbar],
+        }
+    }
+
+    fn update_saved(&self) {
+        if let Some(show) = self.model.get_show_info() {
+            self.widget.set_saved(show.is_saved);
+        }
+    }
+
+    fn update_details(&self) {
+        if let Some(show) = self.model.get_show_info() {
+            self.widget.set_saved(show.is_saved);
+            self.widget
+                .set_title_and_publisher(&show.title[..], &show.publisher[..]);
+            self.widget.set_description(show.description.as_deref());
+
+            if let Some(art) = show.art.clone() {
+                let widget = self.widget.downgrade();
+
+                self.worker.send_local_task(async move {
+                    let pixbuf = ImageLoader::new()
+                        .load_remote(&art[..], "jpg", 320, 320)
+                        .await;
+                    if let (Some(widget), Some(ref pixbuf)) = (widget.upgrade(), pixbuf) {
+                        widget.set_artwork(pixbuf);
+                        widget.set_loaded();
+                    }
+                });
+            } else {
+                self.widget.set_loaded();
+            }
+        }
+    }
+}
+
+impl Component for ShowDetails {
+    fn get_root_widget(&self) -> &gtk::Widget {
+        self.widget.upcast_ref()
+    }
+
+    fn get_children(&mut self) -> Option<&mut Vec<Box<dyn EventListener>>> {
+        Some(&mut self.children)
+    }
+}
+
+impl EventListener for ShowDetails {
+    fn on_event(&mut self, event: &AppEvent) {
+        match event {
+            AppEvent::BrowserEvent(BrowserEvent::ShowDetailsLoaded(id)) if id == &self.model.id => {
+                self.update_details();
+            }
+            AppEvent::BrowserEvent(BrowserEvent::ShowSaved(id))
+            | AppEvent::BrowserEvent(BrowserEvent::ShowUnsaved(id))
+                if id == &self.model.id =>
+            {
+                self.update_saved();
+            }
+            _ => {}
+        }
+        self.broadcast_event(event);
+    }
+}
@@ -0,0 +1,261 @@
+use gio::prelude::*;
+use gio::SimpleActionGroup;
+use std::cell::Ref;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::api::SpotifyApiError;
+use crate::app::components::labels;
+use crate::app::components::HeaderBarModel;
+use crate::app::components::PlaylistModel;
+use crate::app::components::SimpleHeaderBarModel;
+use crate::app::components::SimpleHeaderBarModelWrapper;
+use crate::app::dispatch::ActionDispatcher;
+use crate::app::models::*;
+use crate::app::state::SelectionContext;
+use crate::app::state::{BrowserAction, PlaybackAction, SelectionAction, SelectionState};
+use crate::app::{AppAction, AppEvent, AppModel, AppState, BatchQuery, SongsSource};
+
+pub struct ShowDetailsModel {
+    pub id: String,
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl ShowDetailsModel {
+    pub fn new(id: String, app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            id,
+            app_model,
+            dispatcher,
+        }
+    }
+
+    fn state(&self) -> Ref<'_, AppState> {
+        self.app_model.get_state()
+    }
+
+    pub fn get_show_info(&self) -> Option<impl Deref<Target = ShowDescription> + '_> {
+        self.app_model
+            .map_state_opt(|s| s.browser.show_state(&self.id)?.show.as_ref())
+    }
+
+    pub fn load_show_info(&self) {
+        let id = self.id.clone();
+        let api = self.app_model.get_spotify();
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                let show = api.get_show(&id).await;
+                match show {
+                    Ok(show) => Ok(BrowserAction::SetShowDetails(Box::new(show)).into()),
+                    Err(SpotifyApiError::BadStatus(400, _))
+                    | Err(SpotifyApiError::BadStatus(404, _)) => {
+                        Ok(BrowserAction::NavigationPop.into())
+                    }
+                    Err(e) => Err(e),
+                }
+            });
+    }
+
+    pub fn toggle_save_show(&self) {
+        if let Some(show) = self.get_show_info() {
+            let id = show.id.clone();
+            let is_saved = show.is_saved;
+
+            let api = self.app_model.get_spotify();
+
+            self.dispatcher
+                .call_spotify_and_dispatch(move || async move {
+                    if !is_saved {
+                        api.save_show(&id)
+                            .await
+                            .map(|show| BrowserAction::SaveShow(Box::new(show)).into())
+                    } else {
+                        api.remove_saved_show(&id)
+                            .await
+                            .map(|_| BrowserAction::UnsaveShow(id).into())
+                    }
+                });
+        }
+    }
+
+    pub fn load_more(&self) -> Option<()> {
+        let last_batch = self.song_list_model().last_batch()?;
+        let query = BatchQuery {
+            source: SongsSource::Show(self.id.clone()),
+            batch: last_batch,
+        };
+
+        let id = self.id.clone();
+        let next_query = query.next()?;
+        let api = self.app_model.get_spotify();
+
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                let batch = next_query.batch;
+                api.get_show_episodes(&id, batch.offset, batch.batch_size)
+                    .await
+                    .map(|episodes| {
+                        BrowserAction::AppendShowEpisodes(id, Box::new(episodes)).into()
+                    })
+            });
+
+        Some(())
+    }
+
+    pub fn to_headerbar_model(self: &Rc<Self>) -> Rc<impl HeaderBarModel> {
+        Rc::new(SimpleHeaderBarModelWrapper::new(
+            self.clone(),
+            self.app_model.clone(),
+            self.dispatcher.box_clone(),
+        ))
+    }
+}
+
+impl PlaylistModel for ShowDetailsModel {
+    fn song_list_model(&self) -> SongListModel {
+        self.app_model
+            .get_state()
+            .browser
+            .show_state(&self.id)
+            .expect("illegal attempt to read show_state")
+            .episodes
+            .clone()
+    }
+
+    fn show_song_covers(&self) -> bool {
+        false
+    }
+
+    fn select_song(&self, id: &str) {
+        let songs = self.song_list_model();
+        if let Some(song) = songs.get(id) {
+            self.dispatcher
+                .dispatch(SelectionAction::Select(vec![song.description().clone()]).into());
+        }
+    }
+
+    fn deselect_song(&self, id: &str) {
+        self.dispatcher
+            .dispatch(SelectionAction::Deselect(vec![id.to_string()]).into());
+    }
+
+    fn enable_selection(&self) -> bool {
+        self.dispatcher
+            .dispatch(AppAction::EnableSelection(SelectionContext::Default));
+        true
+    }
+
+    fn selection(&self) -> Option<Box<dyn Deref<Target = SelectionState> + '_>> {
+        Some(Box::new(self.app_model.map_state(|s| &s.selection)))
+    }
+
+    fn current_song_id(&self) -> Option<String> {
+        self.state().playback.current_song_id()
+    }
+
+    fn play_song_at(&self, pos: usize, id: &str) {
+        let source = SongsSource::Show(self.id.clone());
+        let batch = self.song_list_model().song_batch_for(pos);
+        let resume_positions = self
+            .state()
+            .browser
+            .show_state(&self.id)
+            .map(|state| {
+                state
+                    .resume_positions
+                    .iter()
+                    .map(|(id, position)| (id.clone(), *position))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if let Some(batch) = batch {
+            self.dispatcher
+                .dispatch(PlaybackAction::SeedResumePositions(resume_positions).into());
+            self.dispatcher
+                .dispatch(PlaybackAction::LoadPagedSongs(source, batch).into());
+            self.dispatcher
+                .dispatch(PlaybackAction::Load(id.to_string()).into());
+        }
+    }
+
+    fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let group = SimpleActionGroup::new();
+        group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
+
+        Some(group.upcast())
+    }
+
+    fn menu_for(&self, id: &str) -> Option<gio::MenuModel> {
+        self.song_list_model().get(id)?;
+
+        let menu = gio::Menu::new();
+        menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
+        Some(menu.upcast())
+    }
+}
+
+impl SimpleHeaderBarModel for ShowDetailsModel {
+    fn title(&self) -> Option<String> {
+        None
+    }
+
+    fn title_updated(&self, _: &AppEvent) -> bool {
+        false
+    }
+
+    fn selection_context(&self) -> Option<&SelectionContext> {
+        Some(&SelectionContext::Default)
+    }
+
+    fn select_all(&self) {
+        let songs: Vec<SongDescription> = self.song_list_model().collect();
+        self.dispatcher
+            .dispatch(SelectionAction::Select(songs).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::state::ScreenName;
+    use crate::app::testing::{episode, show, user, TestApp};
+
+    #[test]
+    fn test_play_episode_seeds_resume_positions() {
+        let started = EpisodeDescription {
+            resume_position: Some(30_000),
+            ..episode("episode0", "show0")
+        };
+        let api = FakeSpotifyClient::new()
+            .with_user(user("me"))
+            .with_show(show("show0", vec![started, episode("episode1", "show0")]));
+        let app = TestApp::logged_in(api, "me");
+        app.dispatch(
+            BrowserAction::NavigationPush(ScreenName::ShowDetails("show0".to_string())).into(),
+        );
+        app.run_until_idle();
+
+        let model = ShowDetailsModel::new("show0".to_string(), app.model.clone(), app.dispatcher());
+        model.load_show_info();
+        app.run_until_idle();
+        assert_eq!(model.song_list_model().partial_len(), 2);
+
+        model.play_song_at(0, "episode0");
+        app.run_until_idle();
+        let state = app.state();
+        assert_eq!(
+            state.playback.current_song_id().as_deref(),
+            Some("episode0")
+        );
+        assert_eq!(state.playback.resume_position("episode0"), Some(30_000));
+        assert_eq!(state.playback.resume_position("episode1"), None);
+    }
+}
@@ -0,0 +1,5 @@
+mod top_items;
+pub use top_items::*;
+
+mod top_items_model;
+pub use top_items_model::*;
@@ -0,0 +1,199 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::CompositeTemplate;
+use std::rc::Rc;
+
+use super::TopItemsModel;
+use crate::app::components::utils::wrap_flowbox_item;
+use crate::app::components::{ArtistWidget, Component, EventListener, Playlist};
+use crate::app::models::{ArtistModel, TimeRange};
+use crate::app::state::BrowserEvent;
+use crate::app::{AppEvent, ListStore, Worker};
+use libadwaita::subclass::prelude::BinImpl;
+
+mod imp {
+
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/dev/alextren/Spot/components/top_items.ui")]
+    pub struct TopItemsWidget {
+        #[template_child]
+        pub scrolled_window: TemplateChild<gtk::ScrolledWindow>,
+
+        #[template_child]
+        pub short_term: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        pub medium_term: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        pub long_term: TemplateChild<gtk::ToggleButton>,
+
+        #[template_child]
+        pub top_artists: TemplateChild<gtk::FlowBox>,
+
+        #[template_child]
+        pub top_tracks: TemplateChild<gtk::ListView>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TopItemsWidget {
+        const NAME: &'static str = "TopItemsWidget";
+        type Type = super::TopItemsWidget;
+        type ParentType = libadwaita::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for TopItemsWidget {}
+    impl WidgetImpl for TopItemsWidget {}
+    impl BinImpl for TopItemsWidget {}
+}
+
+glib::wrapper! {
+    pub struct TopItemsWidget(ObjectSubclass<imp::TopItemsWidget>) @extends gtk::Widget, libadwaita::Bin;
+}
+
+impl TopItemsWidget {
+    fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn time_range_buttons(&self) -> [(&gtk::ToggleButton, TimeRange); 3] {
+        let widget = self.imp();
+        [
+            (&*widget.short_term, TimeRange::Short),
+            (&*widget.medium_term, TimeRange::Medium),
+            (&*widget.long_term, TimeRange::Long),
+        ]
+    }
+
+    fn set_time_range(&self, time_range: TimeRange) {
+        for (button, range) in self.time_range_buttons() {
+            if range == time_range {
+                button.set_active(true);
+            }
+        }
+    }
+
+    fn connect_time_range_changed<F>(&self, f: F)
+    where
+        F: Fn(TimeRange) + Clone + 'static,
+    {
+        for (button, range) in self.time_range_buttons() {
+            let f = f.clone();
+            button.connect_toggled(move |button| {
+                // The button being untoggled is notified as well
+                if button.is_active() {
+                    f(range);
+                }
+            });
+        }
+    }
+
+    fn connect_bottom_edge<F>(&self, f: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.imp()
+            .scrolled_window
+            .connect_edge_reached(move |_, pos| {
+                if let gtk::PositionType::Bottom = pos {
+                    f()
+                }
+            });
+    }
+
+    fn bind_artists<F>(&self, worker: Worker, store: &ListStore<ArtistModel>, on_artist_pressed: F)
+    where
+        F: Fn(String) + Clone + 'static,
+    {
+        self.imp()
+            .top_artists
+            .bind_model(Some(store.unsafe_store()), move |item| {
+                wrap_flowbox_item(item, |artist_model| {
+                    let f = on_artist_pressed.clone();
+                    let artist = ArtistWidget::for_model(artist_model, worker.clone());
+                    artist.connect_artist_pressed(clone!(@weak artist_model => move |_| {
+                        f(artist_model.id());
+                    }));
+                    artist
+                })
+            });
+    }
+
+    fn song_list_widget(&self) -> &gtk::ListView {
+        self.imp().top_tracks.as_ref()
+    }
+}
+
+pub struct TopItems {
+    widget: TopItemsWidget,
+    model: Rc<TopItemsModel>,
+    children: Vec<Box<dyn EventListener>>,
+}
+
+impl TopItems {
+    pub fn new(model: Rc<TopItemsModel>, worker: Worker) -> Self {
+        model.load_initial();
+
+        let widget = TopItemsWidget::new();
+
+        if let Some(time_range) = model.time_range() {
+            widget.set_time_range(time_range);
+        }
+
+        widget.connect_time_range_changed(clone!(@weak model => move |time_range| {
+            model.set_time_range(time_range);
+        }));
+
+        widget.connect_bottom_edge(clone!(@weak model => move || {
+            model.load_more();
+        }));
+
+        if let Some(store) = model.get_artists() {
+            widget.bind_artists(
+                worker.clone(),
+                &store,
+                clone!(@weak model => move |id| {
+                    model.open_artist(id);
+                }),
+            );
+        }
+
+        let playlist = Playlist::new(widget.song_list_widget().clone(), model.clone(), worker);
+
+        Self {
+            widget,
+            model,
+            children: vec![Box::new(playlist)],
+        }
+    }
+}
+
+impl Component for TopItems {
+    fn get_root_widget(&self) -> &gtk::Widget {
+        self.widget.upcast_ref()
+    }
+
+    fn get_children(&mut self) -> Option<&mut Vec<Box<dyn EventListener>>> {
+        Some(&mut self.children)
+    }
+}
+
+impl EventListener for TopItems {
+    fn on_event(&mut self, event: &AppEvent) {
+        if let AppEvent::BrowserEvent(BrowserEvent::TopItemsTimeRangeChanged(time_range)) = event {
+            self.widget.set_time_range(*time_range);
+            self.model.load_initial();
+        }
+        self.broadcast_event(event);
+    }
+}
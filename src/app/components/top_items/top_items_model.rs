@@ -0,0 +1,209 @@
+use gio::prelude::*;
+use gio::SimpleActionGroup;
+use std::cell::Ref;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::app::components::{labels, PlaylistModel, SavedTracksToggle};
+use crate::app::models::*;
+use crate::app::state::{
+    PlaybackAction, SelectionAction, SelectionContext, SelectionState, TopItemsState,
+};
+use crate::app::{ActionDispatcher, AppAction, AppModel, BrowserAction, ListStore};
+
+const SONGS_PAGE_SIZE: usize = 50;
+const ARTISTS_PAGE_SIZE: usize = 20;
+
+pub struct TopItemsModel {
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl TopItemsModel {
+    pub fn new(app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            app_model,
+            dispatcher,
+        }
+    }
+
+    fn state(&self) -> Option<Ref<'_, TopItemsState>> {
+        self.app_model
+            .map_state_opt(|s| s.browser.top_items_state())
+    }
+
+    pub fn time_range(&self) -> Option<TimeRange> {
+        Some(self.state()?.time_range)
+    }
+
+    pub fn set_time_range(&self, time_range: TimeRange) {
+        self.dispatcher
+            .dispatch(BrowserAction::SetTopItemsTimeRange(time_range).into());
+    }
+
+    pub fn get_artists(&self) -> Option<impl Deref<Target = ListStore<ArtistModel>> + '_> {
+        Some(Ref::map(self.state()?, |s| &s.artists))
+    }
+
+    pub fn load_initial(&self) -> Option<()> {
+        let time_range = self.time_range()?;
+        self.load(
+            TopItemsCategory::Songs,
+            time_range,
+            0,
+            SONGS_PAGE_SIZE,
+            |items| BrowserAction::SetTopItems(time_range, items).into(),
+        );
+        self.load(
+            TopItemsCategory::Artists,
+            time_range,
+            0,
+            ARTISTS_PAGE_SIZE,
+            |items| BrowserAction::SetTopItems(time_range, items).into(),
+        );
+        Some(())
+    }
+
+    pub fn load_more(&self) -> Option<()> {
+        let state = self.state()?;
+        let time_range = state.time_range;
+        let next_songs = state.songs.last_batch().and_then(|b| b.next());
+        let next_artists = state.next_artists_page.next_offset;
+        drop(state);
+
+        if let Some(batch) = next_songs {
+            self.load(
+                TopItemsCategory::Songs,
+                time_range,
+                batch.offset,
+                batch.batch_size,
+                |items| BrowserAction::AppendTopItems(time_range, items).into(),
+            );
+        }
+        if let Some(offset) = next_artists {
+            self.load(
+                TopItemsCategory::Artists,
+                time_range,
+                offset,
+                ARTISTS_PAGE_SIZE,
+                |items| BrowserAction::AppendTopItems(time_range, items).into(),
+            );
+        }
+        Some(())
+    }
+
+    fn load<F>(
+        &self,
+        category: TopItemsCategory,
+        time_range: TimeRange,
+        offset: usize,
+        limit: usize,
+        make_action: F,
+    ) where
+        F: FnOnce(Box<TopItems>) -> AppAction + Send + Clone + 'static,
+    {
+        let api = self.app_model.get_spotify();
+        self.dispatcher
+            .call_spotify_and_dispatch(move || async move {
+                api.get_top_items(category, time_range, offset, limit)
+                    .await
+                    .map(|items| make_action(Box::new(items)))
+            });
+    }
+
+    pub fn open_artist(&self, id: String) {
+        self.dispatcher.dispatch(AppAction::ViewArtist(id));
+    }
+}
+
+impl PlaylistModel for TopItemsModel {
+    fn song_list_model(&self) -> SongListModel {
+        self.state()
+            .expect("illegal attempt to read top_items_state")
+            .songs
+            .clone()
+    }
+
+    fn current_song_id(&self) -> Option<String> {
+        self.app_model.get_state().playback.current_song_id()
+    }
+
+    fn play_song_at(&self, _pos: usize, id: &str) {
+        let tracks: Vec<SongDescription> = self.song_list_model().collect();
+        self.dispatcher
+            .dispatch(PlaybackAction::LoadSongs(tracks).into());
+        self.dispatcher
+            .dispatch(PlaybackAction::Load(id.to_string()).into());
+    }
+
+    fn autoscroll_to_playing(&self) -> bool {
+        false
+    }
+
+    fn saved_tracks(&self) -> Option<SavedTracksToggle> {
+        SavedTracksToggle::for_model(&self.app_model, self.dispatcher.as_ref())
+    }
+
+    fn actions_for(&self, id: &str) -> Option<gio::ActionGroup> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let group = SimpleActionGroup::new();
+
+        for view_artist in song.make_artist_actions(self.dispatcher.box_clone(), None) {
+            group.add_action(&view_artist);
+        }
+        group.add_action(&song.make_album_action(self.dispatcher.box_clone(), None));
+        group.add_action(&song.make_link_action(None));
+        group.add_action(&song.make_radio_action(
+            self.app_model.get_spotify(),
+            self.dispatcher.box_clone(),
+            None,
+        ));
+        group.add_action(&song.make_queue_action(self.dispatcher.box_clone(), None));
+
+        Some(group.upcast())
+    }
+
+    fn menu_for(&self, id: &str) -> Option<gio::MenuModel> {
+        let song = self.song_list_model().get(id)?;
+        let song = song.description();
+
+        let menu = gio::Menu::new();
+        menu.append(Some(&*labels::VIEW_ALBUM), Some("song.view_album"));
+        for artist in song.artists.iter() {
+            menu.append(
+                Some(&labels::more_from_label(&artist.name)),
+                Some(&format!("song.view_artist_{}", artist.id)),
+            );
+        }
+
+        menu.append(Some(&*labels::COPY_LINK), Some("song.copy_link"));
+        menu.append(Some(&*labels::START_RADIO), Some("song.radio"));
+        menu.append(Some(&*labels::ADD_TO_QUEUE), Some("song.queue"));
+        Some(menu.upcast())
+    }
+
+    fn select_song(&self, id: &str) {
+        let song = self.song_list_model().get(id);
+        if let Some(song) = song {
+            self.dispatcher
+                .dispatch(SelectionAction::Select(vec![song.into_description()]).into());
+        }
+    }
+
+    fn deselect_song(&self, id: &str) {
+        self.dispatcher
+            .dispatch(SelectionAction::Deselect(vec![id.to_string()]).into());
+    }
+
+    fn enable_selection(&self) -> bool {
+        self.dispatcher
+            .dispatch(AppAction::EnableSelection(SelectionContext::Default));
+        true
+    }
+
+    fn selection(&self) -> Option<Box<dyn Deref<Target = SelectionState> + '_>> {
+        Some(Box::new(self.app_model.map_state(|s| &s.selection)))
+    }
+}
@@ -3,10 +3,11 @@ use gtk::subclass::prelude::*;
 use gtk::CompositeTemplate;
 use std::rc::Rc;
 
+use crate::app::components::share::bind_share_button;
 use crate::app::components::utils::wrap_flowbox_item;
 use crate::app::components::{display_add_css_provider, AlbumWidget, Component, EventListener};
 use crate::app::{models::*, ListStore};
-use crate::app::{AppEvent, BrowserEvent, Worker};
+use crate::app::{AppEvent, BrowserEvent, SpotifyUri, Worker};
 
 use super::UserDetailsModel;
 
@@ -23,6 +24,9 @@ mod imp {
         #[template_child]
         pub user_name: TemplateChild<gtk::Label>,
 
+        #[template_child]
+        pub share_button: TemplateChild<gtk::MenuButton>,
+
         #[template_child]
         pub user_playlists: TemplateChild<gtk::FlowBox>,
     }
@@ -63,6 +67,10 @@ impl UserDetailsWidget {
         self.imp().user_name.set_text(name);
     }
 
+    fn set_share_uri(&self, uri: &SpotifyUri<'_>) {
+        bind_share_button(&self.imp().share_button, uri);
+    }
+
     fn connect_bottom_edge<F>(&self, f: F)
     where
         F: Fn() + 'static,
@@ -107,6 +115,7 @@ impl UserDetails {
         let widget = UserDetailsWidget::new();
         let model = Rc::new(model);
 
+        widget.set_share_uri(&SpotifyUri::User(&model.id));
         widget.connect_bottom_edge(clone!(@weak model => move || {
             model.load_more();
         }));
@@ -4,7 +4,7 @@ use gtk::prelude::*;
 use std::rc::Rc;
 
 use super::UserMenuModel;
-use crate::app::components::{EventListener, Settings};
+use crate::app::components::{EventListener, PlaylistEditWindow, Settings};
 use crate::app::{state::LoginEvent, AppEvent};
 
 pub struct UserMenu {
@@ -54,6 +54,20 @@ impl UserMenu {
             about_action
         });
 
+        action_group.add_action(&{
+            let new_playlist_window = PlaylistEditWindow::new_for_creation();
+            new_playlist_window.connect_save(clone!(@weak model => move |name, _, _, _| {
+                model.create_playlist(name);
+            }));
+
+            let new_playlist_action = SimpleAction::new("new_playlist", None);
+            new_playlist_action.connect_activate(clone!(@weak user_button => move |_, _| {
+                new_playlist_window.set_details("", None, false, false);
+                new_playlist_window.present_for(&user_button);
+            }));
+            new_playlist_action
+        });
+
         user_button.insert_action_group("menu", Some(&action_group));
 
         Self { user_button, model }
@@ -71,6 +85,8 @@ impl UserMenu {
         if let Some(username) = self.model.username() {
             let user_menu = gio::Menu::new();
             // translators: This is a menu entry.
+            user_menu.append(Some(&gettext("New Playlist…")), Some("menu.new_playlist"));
+            // translators: This is a menu entry.
             user_menu.append(Some(&gettext("Log out")), Some("menu.logout"));
             menu.insert_section(0, Some(&username), &user_menu);
         }
@@ -1,8 +1,8 @@
 use crate::api::clear_user_cache;
 use crate::app::credentials::Credentials;
 use crate::app::models::{PlaylistDescription, PlaylistSummary};
-use crate::app::state::{LoginAction, PlaybackAction};
-use crate::app::{ActionDispatcher, AppModel};
+use crate::app::state::{BrowserAction, LoginAction, PlaybackAction};
+use crate::app::{ActionDispatcher, AppAction, AppModel};
 use std::ops::Deref;
 use std::rc::Rc;
 
@@ -53,4 +53,25 @@ impl UserMenuModel {
                 });
         }
     }
+
+    pub fn create_playlist(&self, name: String) {
+        let api = self.app_model.get_spotify();
+        if let Some(current_user) = self.username() {
+            let current_user = current_user.clone();
+            self.dispatcher
+                .call_spotify_and_dispatch_many(move || async move {
+                    let playlist = api.create_playlist(&current_user, &name).await?;
+                    let summary = PlaylistSummary {
+                        id: playlist.id.clone(),
+                        title: playlist.title.clone(),
+                    };
+                    let id = playlist.id.clone();
+                    Ok(vec![
+                        BrowserAction::PrependPlaylistsContent(vec![playlist]).into(),
+                        LoginAction::PrependUserPlaylist(vec![summary]).into(),
+                        AppAction::ViewPlaylist(id),
+                    ])
+                });
+        }
+    }
 }
@@ -152,6 +152,57 @@ pub fn wrap_flowbox_item<
     child.upcast::<gtk::Widget>()
 }
 
+// Links are either dropped as text, or as a list of URIs when dragged out of a browser
+pub fn links_drop_target<F>(on_drop: F) -> gtk::DropTarget
+where
+    F: Fn(&str) -> bool + 'static,
+{
+    let drop_target = gtk::DropTarget::new(glib::Type::INVALID, gdk::DragAction::COPY);
+    drop_target.set_types(&[gdk::FileList::static_type(), String::static_type()]);
+    drop_target.connect_drop(move |_, value, _, _| {
+        if let Ok(files) = value.get::<gdk::FileList>() {
+            let uris: Vec<String> = files.files().iter().map(|f| f.uri().to_string()).collect();
+            on_drop(&uris.join("\n"))
+        } else if let Ok(text) = value.get::<String>() {
+            on_drop(&text)
+        } else {
+            false
+        }
+    });
+    drop_target
+}
+
+// Ctrl+V, which text fields get to handle first since they are focused.
+// Hidden widgets leave it to the next controller.
+pub fn paste_links_controller<F>(scope: gtk::ShortcutScope, on_paste: F) -> gtk::ShortcutController
+where
+    F: Fn(&str) + 'static,
+{
+    let on_paste = Rc::new(on_paste);
+    let action = gtk::CallbackAction::new(move |widget, _| {
+        if !widget.is_mapped() {
+            return false;
+        }
+        let on_paste = Rc::clone(&on_paste);
+        widget
+            .clipboard()
+            .read_text_async(gio::Cancellable::NONE, move |text| {
+                if let Ok(Some(text)) = text {
+                    on_paste(&text);
+                }
+            });
+        true
+    });
+    let shortcut = gtk::Shortcut::new(
+        gtk::ShortcutTrigger::parse_string("<Control>v"),
+        Some(action),
+    );
+    let controller = gtk::ShortcutController::new();
+    controller.set_scope(scope);
+    controller.add_shortcut(&shortcut);
+    controller
+}
+
 pub fn format_duration(duration: f64) -> String {
     let seconds = (duration / 1000.0) as i32;
     let hours = seconds.div_euclid(3600);
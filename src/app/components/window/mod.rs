@@ -2,10 +2,14 @@ use gtk::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::app::components::utils::{links_drop_target, paste_links_controller};
 use crate::app::components::EventListener;
-use crate::app::{AppEvent, AppModel};
+use crate::app::AppEvent;
 use crate::settings::WindowGeometry;
 
+mod window_model;
+pub use window_model::*;
+
 thread_local! {
     static WINDOW_GEOMETRY: RefCell<WindowGeometry> = RefCell::new(WindowGeometry {
         width: 0, height: 0, is_maximized: false
@@ -20,13 +24,12 @@ pub struct MainWindow {
 impl MainWindow {
     pub fn new(
         initial_window_geometry: WindowGeometry,
-        app_model: Rc<AppModel>,
+        model: Rc<MainWindowModel>,
         window: libadwaita::ApplicationWindow,
     ) -> Self {
         window.connect_close_request(
-            clone!(@weak app_model => @default-return gtk::Inhibit(false), move |window| {
-                let state = app_model.get_state();
-                if state.playback.is_playing() {
+            clone!(@weak model => @default-return gtk::Inhibit(false), move |window| {
+                if model.is_playing() {
                     window.hide();
                     gtk::Inhibit(true)
                 } else {
@@ -39,6 +42,16 @@ impl MainWindow {
         window.connect_default_width_notify(Self::save_window_geometry);
         window.connect_maximized_notify(Self::save_window_geometry);
 
+        window.add_controller(links_drop_target(
+            clone!(@weak model => @default-return false, move |text| model.open_links(text)),
+        ));
+        window.add_controller(paste_links_controller(
+            gtk::ShortcutScope::Local,
+            clone!(@weak model => move |text| {
+                model.open_links(text);
+            }),
+        ));
+
         window.connect_unrealize(|_| {
             debug!("saving geometry");
             WINDOW_GEOMETRY.with(|g| g.borrow().save());
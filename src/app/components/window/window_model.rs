@@ -0,0 +1,107 @@
+use std::rc::Rc;
+
+use crate::api::SpotifyApiError;
+use crate::app::state::PlaybackAction;
+use crate::app::{ActionDispatcher, AppModel, SpotifyUri};
+
+pub struct MainWindowModel {
+    app_model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl MainWindowModel {
+    pub fn new(app_model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self {
+            app_model,
+            dispatcher,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.app_model.get_state().playback.is_playing()
+    }
+
+    // A single link is opened, several are queued since there is no showing them all at once
+    pub fn open_links(&self, text: &str) -> bool {
+        let mut uris: Vec<String> = SpotifyUri::parse_all(text)
+            .iter()
+            .map(|uri| uri.to_string())
+            .collect();
+        let loader = self.app_model.get_batch_loader();
+        match uris.len() {
+            0 => return false,
+            1 => {
+                let uri = uris.remove(0);
+                self.dispatcher
+                    .call_spotify_and_dispatch_many(move || async move {
+                        let uri = SpotifyUri::parse(&uri).ok_or(SpotifyApiError::NoContent)?;
+                        loader.open_uri(&uri).await
+                    });
+            }
+            _ => {
+                self.dispatcher
+                    .call_spotify_and_dispatch(move || async move {
+                        let uris: Vec<SpotifyUri> = uris
+                            .iter()
+                            .filter_map(|uri| SpotifyUri::parse(uri))
+                            .collect();
+                        let songs = loader.load_all_uris(&uris).await?;
+                        Ok(PlaybackAction::Queue(songs).into())
+                    });
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::models::SongDescription;
+    use crate::app::state::ScreenName;
+    use crate::app::testing::{album, episode, show, song, TestApp};
+
+    #[test]
+    fn test_open_links() {
+        let api = FakeSpotifyClient::new()
+            .with_album(album("album0", vec![song("a"), song("b")]))
+            .with_album(album("album1", vec![song("c")]));
+        let app = TestApp::new(api);
+        let model = MainWindowModel::new(app.model.clone(), app.dispatcher());
+
+        assert!(!model.open_links("https://example.com/album/album0"));
+
+        assert!(model.open_links("https://open.spotify.com/album/album0?si=abc"));
+        app.run_until_idle();
+        assert_eq!(
+            app.state().browser.current_screen(),
+            &ScreenName::AlbumDetails("album0".to_string())
+        );
+
+        assert!(model.open_links("spotify:album:album0\nspotify:track:c"));
+        app.run_until_idle();
+        let songs: Vec<SongDescription> = app.state().playback.songs().collect();
+        let ids: Vec<String> = songs.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_open_episode_link() {
+        let mut episode0 = episode("episode0", "show0");
+        episode0.resume_position = Some(30_000);
+        let api = FakeSpotifyClient::new().with_show(show("show0", vec![episode0]));
+        let app = TestApp::new(api);
+        let model = MainWindowModel::new(app.model.clone(), app.dispatcher());
+
+        assert!(model.open_links("https://open.spotify.com/episode/episode0?si=abc"));
+        app.run_until_idle();
+        let state = app.state();
+        assert_eq!(
+            state.playback.current_song_id().as_deref(),
+            Some("episode0")
+        );
+        assert_eq!(state.playback.resume_position("episode0"), Some(30_000));
+    }
+}
@@ -38,6 +38,14 @@ where
         self.store.splice(0, self.store.n_items(), &upcast_vec[..]);
     }
 
+    pub fn truncate(&mut self, len: usize) {
+        let n_items = self.store.n_items();
+        if (len as u32) < n_items {
+            self.store
+                .splice(len as u32, n_items - len as u32, &[] as &[glib::Object]);
+        }
+    }
+
     pub fn insert(&mut self, position: u32, element: GType) {
         self.store.insert(position, &element);
     }
@@ -23,12 +23,18 @@ pub use state::{AppAction, AppEvent, AppModel, AppState, BrowserAction, BrowserE
 mod batch_loader;
 pub use batch_loader::*;
 
+mod uri;
+pub use uri::*;
+
 pub mod credentials;
 pub mod loader;
 
 pub mod rng;
 pub use rng::LazyRandomIndex;
 
+#[cfg(test)]
+pub mod testing;
+
 pub struct App {
     settings: SpotSettings,
     builder: gtk::Builder,
@@ -45,12 +51,16 @@ impl App {
         sender: UnboundedSender<AppAction>,
         worker: Worker,
     ) -> Self {
-        let state = AppState::new();
+        let mut state = AppState::new();
+        state.playback.set_volume(settings.volume);
+        state
+            .playback
+            .restore_resume_positions(settings.resume_positions.clone());
         let spotify_client = Arc::new(CachedSpotifyClient::new());
         let model = Rc::new(AppModel::new(state, spotify_client));
 
         let components: Vec<Box<dyn EventListener>> = vec![
-            App::make_player_notifier(&settings, sender.clone()),
+            App::make_player_notifier(&settings, Rc::clone(&model), sender.clone()),
             App::make_dbus(Rc::clone(&model), sender.clone()),
         ];
 
@@ -72,7 +82,12 @@ impl App {
         let dispatcher = Box::new(ActionDispatcherImpl::new(sender.clone(), worker.clone()));
 
         let mut components: Vec<Box<dyn EventListener>> = vec![
-            App::make_window(&self.settings, builder, Rc::clone(model)),
+            App::make_window(
+                &self.settings,
+                builder,
+                Rc::clone(model),
+                dispatcher.box_clone(),
+            ),
             App::make_selection_toolbar(builder, Rc::clone(model), dispatcher.box_clone()),
             App::make_playback(
                 builder,
@@ -97,11 +112,17 @@ impl App {
 
     fn make_player_notifier(
         settings: &SpotSettings,
+        app_model: Rc<AppModel>,
         sender: UnboundedSender<AppAction>,
     ) -> Box<impl EventListener> {
         Box::new(PlayerNotifier::new(
+            app_model,
             sender.clone(),
-            crate::player::start_player_service(settings.player_settings.clone(), sender),
+            crate::player::start_player_service(
+                settings.player_settings.clone(),
+                settings.volume,
+                sender,
+            ),
         ))
     }
 
@@ -116,9 +137,11 @@ impl App {
         settings: &SpotSettings,
         builder: &gtk::Builder,
         app_model: Rc<AppModel>,
+        dispatcher: Box<dyn ActionDispatcher>,
     ) -> Box<impl EventListener> {
         let window: libadwaita::ApplicationWindow = builder.object("window").unwrap();
-        Box::new(MainWindow::new(settings.window.clone(), app_model, window))
+        let model = Rc::new(MainWindowModel::new(app_model, dispatcher));
+        Box::new(MainWindow::new(settings.window.clone(), model, window))
     }
 
     fn make_navigation(
@@ -209,6 +232,10 @@ impl App {
         Box::new(Notification::new(toast_overlay))
     }
 
+    pub fn model(&self) -> Rc<AppModel> {
+        Rc::clone(&self.model)
+    }
+
     fn handle(&mut self, message: AppAction) {
         let starting = matches!(&message, &AppAction::Start);
 
@@ -50,10 +50,81 @@ pub struct AlbumRef {
     pub name: String,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchCategory {
+    Albums,
+    Artists,
+    Songs,
+    Playlists,
+}
+
+impl SearchCategory {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Songs, Self::Albums, Self::Artists, Self::Playlists]
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchResults {
     pub albums: Vec<AlbumDescription>,
     pub artists: Vec<ArtistSummary>,
+    pub songs: Vec<SongDescription>,
+    pub playlists: Vec<PlaylistDescription>,
+}
+
+impl SearchResults {
+    pub fn len_for(&self, category: SearchCategory) -> usize {
+        match category {
+            SearchCategory::Albums => self.albums.len(),
+            SearchCategory::Artists => self.artists.len(),
+            SearchCategory::Songs => self.songs.len(),
+            SearchCategory::Playlists => self.playlists.len(),
+        }
+    }
+}
+
+// How far back Spotify looks when computing the top items of a user
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeRange {
+    // About the last 4 weeks
+    Short,
+    // About the last 6 months
+    Medium,
+    // Several years
+    Long,
+}
+
+impl TimeRange {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Short, Self::Medium, Self::Long]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopItemsCategory {
+    Songs,
+    Artists,
+}
+
+#[derive(Clone, Debug)]
+pub enum TopItems {
+    Songs(SongBatch),
+    Artists(Vec<ArtistSummary>),
+}
+
+#[derive(Clone, Debug)]
+pub struct FollowedArtists {
+    pub artists: Vec<ArtistSummary>,
+    // Cursor to the artists following the last one of this page, if any
+    pub after: Option<String>,
+}
+
+// Most recent plays first, the same song can show up several times
+#[derive(Clone, Debug)]
+pub struct RecentlyPlayed {
+    pub songs: Vec<SongDescription>,
+    // Cursor to the plays preceding the oldest one of this page, if any
+    pub before: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -101,6 +172,11 @@ pub struct AlbumReleaseDetails {
 pub struct PlaylistDescription {
     pub id: String,
     pub title: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub collaborative: bool,
+    // Identifies the version of the playlist the songs were loaded from
+    pub snapshot_id: Option<String>,
     pub art: Option<String>,
     pub songs: SongBatch,
     pub owner: UserRef,
@@ -112,14 +188,42 @@ pub struct PlaylistSummary {
     pub title: String,
 }
 
+// Only the fields that are set get changed
+#[derive(Clone, Debug, Default)]
+pub struct PlaylistDetailsUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub collaborative: Option<bool>,
+}
+
+impl PlaylistDescription {
+    pub fn apply_update(&mut self, update: &PlaylistDetailsUpdate) {
+        if let Some(title) = update.title.as_ref() {
+            self.title = title.clone();
+        }
+        if let Some(description) = update.description.as_ref() {
+            self.description = Some(description.clone()).filter(|d| !d.is_empty());
+        }
+        if let Some(public) = update.public {
+            self.public = Some(public);
+        }
+        if let Some(collaborative) = update.collaborative {
+            self.collaborative = collaborative;
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SongDescription {
     pub id: String,
     pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
     pub uri: String,
     pub title: String,
     pub artists: Vec<ArtistRef>,
     pub album: AlbumRef,
+    pub album_artists: Vec<ArtistRef>,
     pub duration: u32,
     pub art: Option<String>,
 }
@@ -132,18 +236,32 @@ impl SongDescription {
             .collect::<Vec<String>>()
             .join(", ")
     }
+
+    // Episodes are queued like tracks, but their links differ
+    pub fn link(&self) -> String {
+        let kind = self.uri.split(':').nth(1).unwrap_or("track");
+        format!("https://open.spotify.com/{}/{}", kind, &self.id)
+    }
+
+    // Episodes have a show in place of their album
+    pub fn is_episode(&self) -> bool {
+        self.uri.starts_with("spotify:episode:")
+    }
 }
 
 #[derive(Copy, Clone, Default)]
 pub struct SongState {
     pub is_playing: bool,
     pub is_selected: bool,
+    pub is_saved: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct SongBatch {
     pub songs: Vec<SongDescription>,
     pub batch: Batch,
+    // Positions of the entries left out of songs, such as local files in playlists
+    pub skipped: Vec<usize>,
 }
 
 impl SongBatch {
@@ -151,11 +269,16 @@ impl SongBatch {
         Self {
             songs: vec![],
             batch: Batch::first_of_size(1),
+            skipped: vec![],
         }
     }
 
     pub fn resize(self, batch_size: usize) -> Vec<Self> {
-        let SongBatch { mut songs, batch } = self;
+        let SongBatch {
+            mut songs,
+            batch,
+            skipped,
+        } = self;
         if batch_size > batch.batch_size {
             let new_batch = Batch {
                 batch_size,
@@ -164,6 +287,7 @@ impl SongBatch {
             vec![Self {
                 songs,
                 batch: new_batch,
+                skipped,
             }]
         } else {
             let n = songs.len();
@@ -181,6 +305,7 @@ impl SongBatch {
                     Self {
                         songs: new_songs,
                         batch: new_batch,
+                        skipped: vec![],
                     }
                 })
                 .collect()
@@ -188,12 +313,81 @@ impl SongBatch {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ShowDescription {
+    pub id: String,
+    pub title: String,
+    pub publisher: String,
+    pub description: Option<String>,
+    pub art: Option<String>,
+    pub episodes: EpisodeBatch,
+    pub is_saved: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct EpisodeDescription {
+    pub id: String,
+    pub uri: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub release_date: Option<String>,
+    pub duration: u32,
+    pub art: Option<String>,
+    pub show: AlbumRef,
+    // Where the user stopped listening, unset if the episode was never started or fully played
+    pub resume_position: Option<u32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EpisodeBatch {
+    pub episodes: Vec<EpisodeDescription>,
+    pub batch: Batch,
+}
+
+impl EpisodeBatch {
+    pub fn empty() -> Self {
+        Self {
+            episodes: vec![],
+            batch: Batch::first_of_size(1),
+        }
+    }
+}
+
+// Episodes go through the same queue as tracks, the show stands in for the album
+impl From<&EpisodeDescription> for SongDescription {
+    fn from(episode: &EpisodeDescription) -> Self {
+        Self {
+            id: episode.id.clone(),
+            track_number: None,
+            disc_number: None,
+            uri: episode.uri.clone(),
+            title: episode.title.clone(),
+            artists: vec![],
+            album: episode.show.clone(),
+            album_artists: vec![],
+            duration: episode.duration,
+            art: episode.art.clone(),
+        }
+    }
+}
+
+impl From<EpisodeBatch> for SongBatch {
+    fn from(episode_batch: EpisodeBatch) -> Self {
+        Self {
+            songs: episode_batch.episodes.iter().map(|e| e.into()).collect(),
+            batch: episode_batch.batch,
+            skipped: vec![],
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ArtistDescription {
     pub id: String,
     pub name: String,
     pub albums: Vec<AlbumDescription>,
     pub top_tracks: Vec<SongDescription>,
+    pub is_followed: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -225,9 +419,11 @@ mod tests {
                 id: "".to_string(),
                 name: "".to_string(),
             },
+            album_artists: vec![],
             duration: 1000,
             art: None,
             track_number: None,
+            disc_number: None,
         }
     }
 
@@ -236,6 +432,7 @@ mod tests {
         let batch = SongBatch {
             songs: vec![song("1"), song("2"), song("3"), song("4")],
             batch: Batch::first_of_size(4),
+            skipped: vec![],
         };
 
         let batches = batch.resize(2);
@@ -47,6 +47,24 @@ impl From<PlaylistDescription> for AlbumModel {
     }
 }
 
+impl From<&ShowDescription> for AlbumModel {
+    fn from(show: &ShowDescription) -> Self {
+        AlbumModel::new(
+            &show.publisher,
+            &show.title,
+            None,
+            show.art.as_ref(),
+            &show.id,
+        )
+    }
+}
+
+impl From<&ArtistSummary> for ArtistModel {
+    fn from(artist: &ArtistSummary) -> Self {
+        ArtistModel::new(&artist.name, &artist.photo, &artist.id)
+    }
+}
+
 impl From<SongDescription> for SongModel {
     fn from(song: SongDescription) -> Self {
         SongModel::new(song)
@@ -164,6 +164,18 @@ impl SongListModel {
         SongListModelPending::new(swap, self)
     }
 
+    pub fn move_range(
+        &mut self,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+    ) -> SongListModelPending {
+        let moved = self
+            .inner_mut()
+            .move_range(range_start, range_length, insert_before);
+        SongListModelPending::new(moved, self)
+    }
+
     pub fn clear(&mut self) -> SongListModelPending {
         let removed = self.inner_mut().clear();
         SongListModelPending::new(Some(removed), self)
@@ -26,6 +26,10 @@ impl SongModel {
         self.set_property("selected", is_selected);
     }
 
+    pub fn set_saved(&self, is_saved: bool) {
+        self.set_property("saved", is_saved);
+    }
+
     pub fn get_playing(&self) -> bool {
         self.property("playing")
     }
@@ -34,6 +38,10 @@ impl SongModel {
         self.property("selected")
     }
 
+    pub fn get_saved(&self) -> bool {
+        self.property("saved")
+    }
+
     pub fn get_id(&self) -> String {
         self.property("id")
     }
@@ -86,6 +94,14 @@ impl SongModel {
         );
     }
 
+    pub fn bind_saved(&self, o: &impl ObjectType, property: &str) {
+        self.imp().push_binding(
+            self.bind_property("saved", o, property)
+                .flags(glib::BindingFlags::DEFAULT | glib::BindingFlags::SYNC_CREATE)
+                .build(),
+        );
+    }
+
     pub fn unbind_all(&self) {
         self.imp().unbind_all(self);
     }
@@ -148,7 +164,7 @@ mod imp {
     }
 
     lazy_static! {
-        static ref PROPERTIES: [glib::ParamSpec; 8] = [
+        static ref PROPERTIES: [glib::ParamSpec; 9] = [
             glib::ParamSpecString::builder("id").read_only().build(),
             glib::ParamSpecUInt::builder("index").read_only().build(),
             glib::ParamSpecString::builder("title").read_only().build(),
@@ -163,6 +179,7 @@ mod imp {
             glib::ParamSpecBoolean::builder("selected")
                 .readwrite()
                 .build(),
+            glib::ParamSpecBoolean::builder("saved").readwrite().build(),
         ];
     }
 
@@ -177,20 +194,27 @@ mod imp {
                     let is_playing = value
                         .get()
                         .expect("type conformity checked by `Object::set_property`");
-                    let SongState { is_selected, .. } = self.state.get();
                     self.state.set(SongState {
                         is_playing,
-                        is_selected,
+                        ..self.state.get()
                     });
                 }
                 "selected" => {
                     let is_selected = value
                         .get()
                         .expect("type conformity checked by `Object::set_property`");
-                    let SongState { is_playing, .. } = self.state.get();
                     self.state.set(SongState {
-                        is_playing,
                         is_selected,
+                        ..self.state.get()
+                    });
+                }
+                "saved" => {
+                    let is_saved = value
+                        .get()
+                        .expect("type conformity checked by `Object::set_property`");
+                    self.state.set(SongState {
+                        is_saved,
+                        ..self.state.get()
                     });
                 }
                 _ => unimplemented!(),
@@ -244,6 +268,7 @@ mod imp {
                     .to_value(),
                 "playing" => self.state.get().is_playing.to_value(),
                 "selected" => self.state.get().is_selected.to_value(),
+                "saved" => self.state.get().is_saved.to_value(),
                 _ => unimplemented!(),
             }
         }
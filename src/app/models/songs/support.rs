@@ -277,7 +277,7 @@ impl SongList {
         }
     }
 
-    fn add_one(&mut self, SongBatch { songs, batch }: SongBatch) -> Option<ListRangeUpdate> {
+    fn add_one(&mut self, SongBatch { songs, batch, .. }: SongBatch) -> Option<ListRangeUpdate> {
         assert_eq!(batch.batch_size, self.batch_size);
 
         let index = batch.offset / batch.batch_size;
@@ -332,6 +332,44 @@ impl SongList {
         Some(ListRangeUpdate::updated(a).merge(ListRangeUpdate::updated(b)))
     }
 
+    // Same semantics as the Web API: the songs in [range_start, range_start + range_length)
+    // are moved so that they end up right before the song that was at insert_before
+    pub fn move_range(
+        &mut self,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+    ) -> Option<ListRangeUpdate> {
+        let range_end = range_start + range_length;
+        if range_length == 0 || (range_start..=range_end).contains(&insert_before) {
+            return None;
+        }
+
+        // Only the songs between the original and the target position are affected
+        let (start, end) = if insert_before < range_start {
+            (insert_before, range_end)
+        } else {
+            (range_start, insert_before)
+        };
+
+        let mut ids = (start..end)
+            .map(|i| self.index_mut(i).cloned())
+            .collect::<Option<Vec<String>>>()?;
+
+        if insert_before < range_start {
+            ids.rotate_right(range_length);
+        } else {
+            ids.rotate_left(range_length);
+        }
+
+        for (i, id) in (start..end).zip(ids.into_iter()) {
+            *self.index_mut(i)? = id;
+        }
+
+        let len = end - start;
+        Some(ListRangeUpdate(start as i32, len as i32, len as i32))
+    }
+
     pub fn index(&self, i: usize) -> Option<&SongModel> {
         let batch_size = self.batch_size;
         let batch_id = i / batch_size;
@@ -384,6 +422,7 @@ impl SongList {
                 total,
                 offset: batch_id * batch_size,
             },
+            skipped: vec![],
         })
     }
 
@@ -429,9 +468,11 @@ mod tests {
                 id: "".to_string(),
                 name: "".to_string(),
             },
+            album_artists: vec![],
             duration: 1000,
             art: None,
             track_number: None,
+            disc_number: None,
         }
     }
 
@@ -447,6 +488,7 @@ mod tests {
                 song(&format!("song{}", offset)),
                 song(&format!("song{}", offset + 1)),
             ],
+            skipped: vec![],
         }
     }
 
@@ -646,4 +688,55 @@ mod tests {
         assert_eq!(list_iter.next().unwrap().description().id, "song0");
         assert!(list_iter.next().is_none());
     }
+
+    fn ids(list: &SongList) -> Vec<String> {
+        list.iter().map(|s| s.description().id).collect()
+    }
+
+    #[test]
+    fn test_move_range_down() {
+        let mut list = SongList::new_sized(2);
+        list.add(batch(0));
+        list.add(batch(1));
+        list.add(batch(2));
+
+        let change = list.move_range(0, 2, 5);
+
+        assert_eq!(change, Some(ListRangeUpdate(0, 5, 5)));
+        assert_eq!(
+            ids(&list),
+            vec!["song2", "song3", "song4", "song0", "song1", "song5"]
+        );
+    }
+
+    #[test]
+    fn test_move_range_up() {
+        let mut list = SongList::new_sized(2);
+        list.add(batch(0));
+        list.add(batch(1));
+        list.add(batch(2));
+
+        let change = list.move_range(4, 1, 1);
+
+        assert_eq!(change, Some(ListRangeUpdate(1, 4, 4)));
+        assert_eq!(
+            ids(&list),
+            vec!["song0", "song4", "song1", "song2", "song3", "song5"]
+        );
+    }
+
+    #[test]
+    fn test_move_range_no_op() {
+        let mut list = SongList::new_sized(2);
+        list.add(batch(0));
+        list.add(batch(1));
+
+        assert_eq!(list.move_range(1, 2, 1), None);
+        assert_eq!(list.move_range(1, 2, 3), None);
+        assert_eq!(list.move_range(1, 0, 0), None);
+        // not loaded
+        assert_eq!(list.move_range(0, 1, 6), None);
+
+        assert_eq!(ids(&list), vec!["song0", "song1", "song2", "song3"]);
+    }
 }
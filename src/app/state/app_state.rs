@@ -8,6 +8,7 @@ use crate::app::state::{
     settings_state::{SettingsAction, SettingsEvent, SettingsState},
     ScreenName, UpdatableState,
 };
+use crate::app::SpotifyUri;
 
 #[derive(Clone, Debug)]
 pub enum AppAction {
@@ -32,27 +33,16 @@ pub enum AppAction {
 }
 
 impl AppAction {
+    // Tracks and episodes are not shown but played, see BatchLoader::open_uri
     #[allow(non_snake_case)]
-    pub fn OpenURI(uri: String) -> Option<Self> {
-        debug!("parsing {}", &uri);
-        let mut parts = uri.split(':');
-        if parts.next()? != "spotify" {
-            return None;
-        }
-
-        // Might start with /// because of https://gitlab.gnome.org/GNOME/glib/-/issues/1886/
-        let action = parts
-            .next()?
-            .strip_prefix("///")
-            .filter(|p| !p.is_empty())?;
-        let data = parts.next()?;
-
-        match action {
-            "album" => Some(Self::ViewAlbum(data.to_string())),
-            "artist" => Some(Self::ViewArtist(data.to_string())),
-            "playlist" => Some(Self::ViewPlaylist(data.to_string())),
-            "user" => Some(Self::ViewUser(data.to_string())),
-            _ => None,
+    pub fn View(uri: &SpotifyUri<'_>) -> Option<Self> {
+        match uri {
+            SpotifyUri::Album(id) => Some(Self::ViewAlbum(id.to_string())),
+            SpotifyUri::Artist(id) => Some(Self::ViewArtist(id.to_string())),
+            SpotifyUri::Playlist(id) => Some(Self::ViewPlaylist(id.to_string())),
+            SpotifyUri::User(id) => Some(Self::ViewUser(id.to_string())),
+            SpotifyUri::Show(id) => Some(Self::ViewShow(id.to_string())),
+            SpotifyUri::Track(_) | SpotifyUri::Episode(_) => None,
         }
     }
 
@@ -76,6 +66,11 @@ impl AppAction {
         BrowserAction::NavigationPush(ScreenName::User(id)).into()
     }
 
+    #[allow(non_snake_case)]
+    pub fn ViewShow(id: String) -> Self {
+        BrowserAction::NavigationPush(ScreenName::ShowDetails(id)).into()
+    }
+
     #[allow(non_snake_case)]
     pub fn ViewSearch() -> Self {
         BrowserAction::NavigationPush(ScreenName::Search).into()
@@ -225,3 +220,41 @@ where
         .map(|e| e.into())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn pushed_screen(uri: &str) -> Option<String> {
+        match AppAction::View(&SpotifyUri::parse(uri)?)? {
+            AppAction::BrowserAction(BrowserAction::NavigationPush(name)) => {
+                Some(name.identifier().into_owned())
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_open_uri() {
+        assert_eq!(
+            pushed_screen("spotify:album:id").as_deref(),
+            Some("album_id")
+        );
+        assert_eq!(
+            pushed_screen("spotify:///artist:id").as_deref(),
+            Some("artist_id")
+        );
+        assert_eq!(
+            pushed_screen("https://open.spotify.com/playlist/id?si=abc").as_deref(),
+            Some("playlist_id")
+        );
+        assert_eq!(
+            pushed_screen("spotify:user:someone:playlist:id").as_deref(),
+            Some("playlist_id")
+        );
+        assert_eq!(pushed_screen("spotify:track:id"), None);
+        assert_eq!(pushed_screen("spotify:album:"), None);
+        assert_eq!(pushed_screen("https://example.com/album/id"), None);
+    }
+}
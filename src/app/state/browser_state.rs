@@ -1,6 +1,6 @@
 use super::{
-    AppEvent, ArtistState, DetailsState, HomeState, PlaylistDetailsState, ScreenName, SearchState,
-    UpdatableState, UserState,
+    AppEvent, ArtistState, DetailsState, HomeState, PlaylistDetailsState, RecentlyPlayedState,
+    ScreenName, SearchState, ShowState, TopItemsState, UpdatableState, UserState,
 };
 use crate::app::models::*;
 use crate::app::state::AppAction;
@@ -14,13 +14,22 @@ pub enum BrowserAction {
     AppendLibraryContent(Vec<AlbumDescription>),
     SetPlaylistsContent(Vec<PlaylistDescription>),
     AppendPlaylistsContent(Vec<PlaylistDescription>),
+    PrependPlaylistsContent(Vec<PlaylistDescription>),
+    UpdatePlaylistDetails(String, PlaylistDetailsUpdate),
+    RemovePlaylist(String),
     RemoveTracksFromPlaylist(String, Vec<String>),
+    // playlist id, range start, range length, insert before
+    MovePlaylistTracks(String, usize, usize, usize),
+    SetPlaylistSnapshot(String, String),
     SetAlbumDetails(Box<AlbumFullDescription>),
     AppendAlbumTracks(String, Box<SongBatch>),
     SetPlaylistDetails(Box<PlaylistDescription>),
     AppendPlaylistTracks(String, Box<SongBatch>),
     Search(String),
     SetSearchResults(Box<SearchResults>),
+    ShowSearchCategory(Option<SearchCategory>),
+    // query, category, next page of results
+    AppendSearchResults(String, SearchCategory, Box<SearchResults>),
     SetArtistDetails(Box<ArtistDescription>),
     AppendArtistReleases(String, Vec<AlbumDescription>),
     NavigationPush(ScreenName),
@@ -34,6 +43,23 @@ pub enum BrowserAction {
     AppendSavedTracks(Box<SongBatch>),
     SaveTracks(Vec<SongDescription>),
     RemoveSavedTracks(Vec<String>),
+    // Track ids along with whether they are in the library
+    SetTracksSaved(Vec<(String, bool)>),
+    SetRecentlyPlayed(Box<RecentlyPlayed>),
+    AppendRecentlyPlayed(Box<RecentlyPlayed>),
+    SetTopItemsTimeRange(TimeRange),
+    SetTopItems(TimeRange, Box<TopItems>),
+    AppendTopItems(TimeRange, Box<TopItems>),
+    SetFollowedArtists(Box<FollowedArtists>),
+    AppendFollowedArtists(Box<FollowedArtists>),
+    FollowArtist(Box<ArtistSummary>),
+    UnfollowArtist(String),
+    SetShowDetails(Box<ShowDescription>),
+    AppendShowEpisodes(String, Box<EpisodeBatch>),
+    SetSavedShows(Vec<ShowDescription>),
+    AppendSavedShows(Vec<ShowDescription>),
+    SaveShow(Box<ShowDescription>),
+    UnsaveShow(String),
 }
 
 impl From<BrowserAction> for AppAction {
@@ -50,10 +76,13 @@ pub enum BrowserEvent {
     AlbumDetailsLoaded(String),
     AlbumTracksAppended(String),
     PlaylistDetailsLoaded(String),
+    PlaylistDetailsUpdated(String),
     PlaylistTracksAppended(String),
     PlaylistTracksRemoved(String),
+    PlaylistTracksMoved(String),
     SearchUpdated,
     SearchResultsUpdated,
+    SearchCategoryChanged(Option<SearchCategory>),
     ArtistDetailsUpdated(String),
     NavigationPushed(ScreenName),
     NavigationPopped,
@@ -62,6 +91,17 @@ pub enum BrowserEvent {
     AlbumUnsaved(String),
     UserDetailsUpdated(String),
     SavedTracksUpdated,
+    RecentlyPlayedUpdated,
+    TopItemsTimeRangeChanged(TimeRange),
+    TopItemsUpdated,
+    FollowedArtistsUpdated,
+    ArtistFollowed(String),
+    ArtistUnfollowed(String),
+    ShowDetailsLoaded(String),
+    ShowEpisodesAppended(String),
+    ShowSaved(String),
+    ShowUnsaved(String),
+    SavedShowsUpdated,
 }
 
 impl From<BrowserEvent> for AppEvent {
@@ -77,6 +117,9 @@ pub enum BrowserScreen {
     Artist(Box<ArtistState>),
     PlaylistDetails(Box<PlaylistDetailsState>),
     User(Box<UserState>),
+    RecentlyPlayed(Box<RecentlyPlayedState>),
+    TopItems(Box<TopItemsState>),
+    ShowDetails(Box<ShowState>),
 }
 
 impl BrowserScreen {
@@ -94,6 +137,11 @@ impl BrowserScreen {
                 BrowserScreen::PlaylistDetails(Box::new(PlaylistDetailsState::new(id.to_string())))
             }
             ScreenName::User(id) => BrowserScreen::User(Box::new(UserState::new(id.to_string()))),
+            ScreenName::RecentlyPlayed => BrowserScreen::RecentlyPlayed(Default::default()),
+            ScreenName::TopItems => BrowserScreen::TopItems(Default::default()),
+            ScreenName::ShowDetails(id) => {
+                BrowserScreen::ShowDetails(Box::new(ShowState::new(id.to_string())))
+            }
         }
     }
 
@@ -105,6 +153,9 @@ impl BrowserScreen {
             Self::Artist(state) => &mut **state,
             Self::PlaylistDetails(state) => &mut **state,
             Self::User(state) => &mut **state,
+            Self::RecentlyPlayed(state) => &mut **state,
+            Self::TopItems(state) => &mut **state,
+            Self::ShowDetails(state) => &mut **state,
         }
     }
 }
@@ -120,6 +171,9 @@ impl NamedScreen for BrowserScreen {
             Self::Artist(state) => &state.name,
             Self::PlaylistDetails(state) => &state.name,
             Self::User(state) => &state.name,
+            Self::RecentlyPlayed(state) => &state.name,
+            Self::TopItems(state) => &state.name,
+            Self::ShowDetails(state) => &state.name,
         }
     }
 }
@@ -279,6 +333,18 @@ impl BrowserState {
         extract_state!(self, BrowserScreen::User(state) if state.id == id => state)
     }
 
+    pub fn recently_played_state(&self) -> Option<&RecentlyPlayedState> {
+        extract_state!(self, BrowserScreen::RecentlyPlayed(s) => s)
+    }
+
+    pub fn top_items_state(&self) -> Option<&TopItemsState> {
+        extract_state!(self, BrowserScreen::TopItems(s) => s)
+    }
+
+    pub fn show_state(&self, id: &str) -> Option<&ShowState> {
+        extract_state!(self, BrowserScreen::ShowDetails(state) if state.id == id => state)
+    }
+
     fn push_if_needed(&mut self, name: ScreenName) -> Vec<BrowserEvent> {
         let navigation = &mut self.navigation;
         let screen_visibility = navigation.screen_visibility(&name);
@@ -23,6 +23,9 @@ pub enum LoginAction {
     TryLogin(TryLoginAction),
     SetLoginSuccess(SetLoginSuccessAction),
     SetUserPlaylists(Vec<PlaylistSummary>),
+    PrependUserPlaylist(Vec<PlaylistSummary>),
+    UpdateUserPlaylist(PlaylistSummary),
+    RemoveUserPlaylist(String),
     SetLoginFailure,
     RefreshToken,
     SetRefreshedToken {
@@ -123,6 +126,20 @@ impl UpdatableState for LoginState {
                 self.playlists = playlists;
                 vec![LoginEvent::UserPlaylistsLoaded.into()]
             }
+            LoginAction::PrependUserPlaylist(summaries) => {
+                self.playlists.splice(0..0, summaries);
+                vec![LoginEvent::UserPlaylistsLoaded.into()]
+            }
+            LoginAction::UpdateUserPlaylist(PlaylistSummary { id, title }) => {
+                if let Some(p) = self.playlists.iter_mut().find(|p| p.id == id) {
+                    p.title = title;
+                }
+                vec![LoginEvent::UserPlaylistsLoaded.into()]
+            }
+            LoginAction::RemoveUserPlaylist(id) => {
+                self.playlists.retain(|p| p.id != id);
+                vec![LoginEvent::UserPlaylistsLoaded.into()]
+            }
         }
     }
 }
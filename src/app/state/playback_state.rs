@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 use crate::app::models::{SongBatch, SongDescription, SongListModel, SongListModelPending};
 use crate::app::state::{AppAction, AppEvent, UpdatableState};
@@ -13,6 +14,10 @@ pub struct PlaybackState {
     repeat: RepeatMode,
     is_playing: bool,
     is_shuffled: bool,
+    // From 0 to 1
+    volume: f64,
+    // Where to resume episodes from, by episode id, 0 for those played to the end
+    resume_positions: HashMap<String, u32>,
 }
 
 impl PlaybackState {
@@ -20,6 +25,10 @@ impl PlaybackState {
         &self.songs
     }
 
+    pub fn source(&self) -> Option<&SongsSource> {
+        self.source.as_ref()
+    }
+
     pub fn is_playing(&self) -> bool {
         self.is_playing && self.position.is_some()
     }
@@ -32,6 +41,14 @@ impl PlaybackState {
         self.repeat
     }
 
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f64) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
     pub fn next_query(&self) -> Option<BatchQuery> {
         let next_index = self.next_index()?;
         let next_index = if self.is_shuffled {
@@ -65,6 +82,42 @@ impl PlaybackState {
         self.index(self.position?)
     }
 
+    // The current song is the last one to play, and playback will stop after it
+    pub fn is_playing_last(&self) -> bool {
+        self.position.is_some() && self.next_index().is_none()
+    }
+
+    // Songs played so far in the current queue, the current one first
+    pub fn played_songs(&self) -> impl Iterator<Item = SongDescription> + '_ {
+        let played = self.position.map(|p| p + 1).unwrap_or(0);
+        (0..played).rev().filter_map(move |i| self.index(i))
+    }
+
+    pub fn resume_position(&self, id: &str) -> Option<u32> {
+        self.resume_positions.get(id).copied().filter(|&p| p > 0)
+    }
+
+    pub fn resume_positions(&self) -> &HashMap<String, u32> {
+        &self.resume_positions
+    }
+
+    // Brings back the positions saved by a previous launch
+    pub fn restore_resume_positions(&mut self, positions: HashMap<String, u32>) {
+        self.resume_positions = positions;
+    }
+
+    // Episodes played to the end are kept, so that Spotify's older position does not come back
+    fn set_resume_position(&mut self, id: String, position: u32) -> bool {
+        self.resume_positions.insert(id, position) != Some(position)
+    }
+
+    // Positions that were saved locally are more recent than the ones we got from Spotify
+    fn seed_resume_positions(&mut self, positions: Vec<(String, u32)>) {
+        for (id, position) in positions {
+            self.resume_positions.entry(id).or_insert(position);
+        }
+    }
+
     fn next_id(&self) -> Option<String> {
         self.next_index()
             .and_then(|i| Some(self.songs().index(i)?.description().id.clone()))
@@ -133,6 +186,25 @@ impl PlaybackState {
         Some(index)
     }
 
+    pub fn move_range(
+        &mut self,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+    ) -> bool {
+        // The play order does not follow the list order when shuffled
+        if self.is_shuffled {
+            return false;
+        }
+        let current_id = self.current_song_id();
+        let moved = self
+            .songs
+            .move_range(range_start, range_length, insert_before)
+            .commit();
+        self.position = current_id.and_then(|id| self.songs.find_index(&id));
+        moved
+    }
+
     fn play(&mut self, id: &str) -> bool {
         if self.current_song_id().map(|cur| cur == id).unwrap_or(false) {
             return false;
@@ -219,6 +291,8 @@ impl Default for PlaybackState {
             repeat: RepeatMode::None,
             is_playing: false,
             is_shuffled: false,
+            volume: 1.0,
+            resume_positions: HashMap::new(),
         }
     }
 }
@@ -232,6 +306,7 @@ pub enum PlaybackAction {
     SetRepeatMode(RepeatMode),
     ToggleRepeat,
     ToggleShuffle,
+    SetShuffled(bool),
     Seek(u32),
     SyncSeek(u32),
     Load(String),
@@ -243,6 +318,11 @@ pub enum PlaybackAction {
     Preload,
     Queue(Vec<SongDescription>),
     Dequeue(String),
+    // range start, range length, insert before
+    MoveRange(usize, usize, usize),
+    // episode id, position in ms (0 once fully played)
+    SetResumePosition(String, u32),
+    SeedResumePositions(Vec<(String, u32)>),
 }
 
 impl From<PlaybackAction> for AppAction {
@@ -264,6 +344,7 @@ pub enum PlaybackEvent {
     ShuffleChanged,
     PlaylistChanged,
     PlaybackStopped,
+    ResumePositionsChanged,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -330,6 +411,10 @@ impl UpdatableState for PlaybackState {
                 self.toggle_shuffle();
                 vec![PlaybackEvent::ShuffleChanged]
             }
+            PlaybackAction::SetShuffled(shuffled) if shuffled != self.is_shuffled => {
+                self.toggle_shuffle();
+                vec![PlaybackEvent::ShuffleChanged]
+            }
             PlaybackAction::Next => {
                 if let Some(id) = self.play_next() {
                     make_events(vec![
@@ -399,9 +484,30 @@ impl UpdatableState for PlaybackState {
                 self.dequeue(&[id]);
                 vec![PlaybackEvent::PlaylistChanged]
             }
+            PlaybackAction::MoveRange(range_start, range_length, insert_before) => {
+                if self.move_range(range_start, range_length, insert_before) {
+                    vec![PlaybackEvent::PlaylistChanged]
+                } else {
+                    vec![]
+                }
+            }
             PlaybackAction::Seek(pos) => vec![PlaybackEvent::TrackSeeked(pos)],
             PlaybackAction::SyncSeek(pos) => vec![PlaybackEvent::SeekSynced(pos)],
-            PlaybackAction::SetVolume(volume) => vec![PlaybackEvent::VolumeSet(volume)],
+            PlaybackAction::SetVolume(volume) => {
+                self.set_volume(volume);
+                vec![PlaybackEvent::VolumeSet(self.volume)]
+            }
+            PlaybackAction::SetResumePosition(id, position) => {
+                if self.set_resume_position(id, position) {
+                    vec![PlaybackEvent::ResumePositionsChanged]
+                } else {
+                    vec![]
+                }
+            }
+            PlaybackAction::SeedResumePositions(positions) => {
+                self.seed_resume_positions(positions);
+                vec![]
+            }
             _ => vec![],
         }
     }
@@ -423,9 +529,11 @@ mod tests {
                 id: "".to_string(),
                 name: "".to_string(),
             },
+            album_artists: vec![],
             duration: 1000,
             art: None,
             track_number: None,
+            disc_number: None,
         }
     }
 
@@ -530,6 +638,82 @@ mod tests {
         assert_eq!(state.current_song_id(), Some("1".to_string()));
     }
 
+    #[test]
+    fn test_played_songs() {
+        let mut state = PlaybackState::default();
+        state.queue(vec![song("1"), song("2"), song("3")]);
+        assert!(!state.is_playing_last());
+        assert_eq!(state.played_songs().count(), 0);
+
+        state.play("2");
+        assert!(!state.is_playing_last());
+        let ids: Vec<String> = state.played_songs().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["2".to_string(), "1".to_string()]);
+
+        state.play_next();
+        assert!(state.is_playing_last());
+
+        state.queue(vec![song("4")]);
+        assert!(!state.is_playing_last());
+
+        state.repeat = RepeatMode::Playlist;
+        state.play_next();
+        assert!(!state.is_playing_last());
+    }
+
+    #[test]
+    fn test_resume_positions() {
+        let mut state = PlaybackState::default();
+        state.seed_resume_positions(vec![("1".to_string(), 100), ("2".to_string(), 200)]);
+        assert_eq!(state.resume_position("1"), Some(100));
+
+        state.set_resume_position("1".to_string(), 150);
+        state.seed_resume_positions(vec![("1".to_string(), 100)]);
+        assert_eq!(state.resume_position("1"), Some(150));
+
+        state.set_resume_position("2".to_string(), 0);
+        assert_eq!(state.resume_position("2"), None);
+
+        // played to the end here, what Spotify remembers is older
+        state.seed_resume_positions(vec![("2".to_string(), 200)]);
+        assert_eq!(state.resume_position("2"), None);
+    }
+
+    #[test]
+    fn test_resume_positions_changed() {
+        let mut state = PlaybackState::default();
+        let events = state.update_with(Cow::Owned(PlaybackAction::SetResumePosition(
+            "1".to_string(),
+            100,
+        )));
+        assert!(matches!(
+            events.as_slice(),
+            [PlaybackEvent::ResumePositionsChanged]
+        ));
+
+        let events = state.update_with(Cow::Owned(PlaybackAction::SetResumePosition(
+            "1".to_string(),
+            100,
+        )));
+        assert!(events.is_empty());
+
+        let mut restored = PlaybackState::default();
+        restored.restore_resume_positions(state.resume_positions().clone());
+        assert_eq!(restored.resume_position("1"), Some(100));
+    }
+
+    #[test]
+    fn test_set_volume() {
+        let mut state = PlaybackState::default();
+        assert_eq!(state.volume(), 1.0);
+
+        let events = state.update_with(Cow::Owned(PlaybackAction::SetVolume(1.5)));
+        assert!(matches!(events[..], [PlaybackEvent::VolumeSet(volume)] if volume == 1.0));
+
+        state.update_with(Cow::Owned(PlaybackAction::SetVolume(0.4)));
+        assert_eq!(state.volume(), 0.4);
+    }
+
     #[test]
     fn test_shuffle() {
         let mut state = PlaybackState::default();
@@ -562,6 +746,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_shuffled() {
+        let mut state = PlaybackState::default();
+        state.queue(vec![song("1"), song("2")]);
+
+        let events = state.update_with(Cow::Owned(PlaybackAction::SetShuffled(true)));
+        assert_eq!(events.len(), 1);
+        assert!(state.is_shuffled());
+
+        let events = state.update_with(Cow::Owned(PlaybackAction::SetShuffled(true)));
+        assert!(events.is_empty());
+        assert!(state.is_shuffled());
+    }
+
     #[test]
     fn test_shuffle_queue() {
         let mut state = PlaybackState::default();
@@ -618,6 +816,43 @@ mod tests {
         assert_eq!(ids, vec!["1".to_string(), "2".to_string(), "3".to_string()]);
     }
 
+    #[test]
+    fn test_move_range() {
+        let mut state = PlaybackState::default();
+        state.queue(vec![song("1"), song("2"), song("3"), song("4")]);
+
+        state.play("2");
+        assert!(state.is_playing());
+
+        assert!(state.move_range(0, 2, 4));
+        assert_eq!(state.current_song_id(), Some("2".to_string()));
+        let ids = state.song_ids();
+        assert_eq!(
+            ids,
+            vec![
+                "3".to_string(),
+                "4".to_string(),
+                "1".to_string(),
+                "2".to_string()
+            ]
+        );
+
+        assert!(state.move_range(3, 1, 0));
+        assert_eq!(state.current_song_id(), Some("2".to_string()));
+        let ids = state.song_ids();
+        assert_eq!(
+            ids,
+            vec![
+                "2".to_string(),
+                "3".to_string(),
+                "4".to_string(),
+                "1".to_string()
+            ]
+        );
+
+        assert!(!state.move_range(1, 1, 2));
+    }
+
     #[test]
     fn test_dequeue_last() {
         let mut state = PlaybackState::default();
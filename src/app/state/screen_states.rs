@@ -1,5 +1,7 @@
+use glib::prelude::*;
 use std::borrow::Cow;
 use std::cmp::PartialEq;
+use std::collections::{HashMap, HashSet};
 
 use super::{pagination::Pagination, BrowserAction, BrowserEvent, UpdatableState};
 use crate::app::models::*;
@@ -13,6 +15,9 @@ pub enum ScreenName {
     Artist(String),
     PlaylistDetails(String),
     User(String),
+    RecentlyPlayed,
+    TopItems,
+    ShowDetails(String),
 }
 
 impl ScreenName {
@@ -24,6 +29,9 @@ impl ScreenName {
             Self::Artist(s) => Cow::Owned(format!("artist_{}", s)),
             Self::PlaylistDetails(s) => Cow::Owned(format!("playlist_{}", s)),
             Self::User(s) => Cow::Owned(format!("user_{}", s)),
+            Self::RecentlyPlayed => Cow::Borrowed("recently_played"),
+            Self::TopItems => Cow::Borrowed("top_items"),
+            Self::ShowDetails(s) => Cow::Owned(format!("show_{}", s)),
         }
     }
 }
@@ -92,11 +100,80 @@ impl UpdatableState for DetailsState {
     }
 }
 
+pub struct ShowState {
+    pub id: String,
+    pub name: ScreenName,
+    pub show: Option<ShowDescription>,
+    pub episodes: SongListModel,
+    // Where the user stopped listening according to Spotify, by episode id
+    pub resume_positions: HashMap<String, u32>,
+}
+
+impl ShowState {
+    pub fn new(id: String) -> Self {
+        Self {
+            id: id.clone(),
+            name: ScreenName::ShowDetails(id),
+            show: None,
+            episodes: SongListModel::new(50),
+            resume_positions: HashMap::new(),
+        }
+    }
+
+    fn add_episodes(&mut self, episode_batch: EpisodeBatch) {
+        self.resume_positions.extend(
+            episode_batch
+                .episodes
+                .iter()
+                .filter_map(|e| Some((e.id.clone(), e.resume_position?))),
+        );
+        self.episodes.add(episode_batch.into()).commit();
+    }
+}
+
+impl UpdatableState for ShowState {
+    type Action = BrowserAction;
+    type Event = BrowserEvent;
+
+    fn update_with(&mut self, action: Cow<Self::Action>) -> Vec<Self::Event> {
+        match action.as_ref() {
+            BrowserAction::SetShowDetails(show) if show.id == self.id => {
+                self.add_episodes(show.episodes.clone());
+                self.show = Some(*show.clone());
+                vec![BrowserEvent::ShowDetailsLoaded(self.id.clone())]
+            }
+            BrowserAction::AppendShowEpisodes(id, episode_batch) if id == &self.id => {
+                self.add_episodes(*episode_batch.clone());
+                vec![BrowserEvent::ShowEpisodesAppended(id.clone())]
+            }
+            BrowserAction::SaveShow(show) if show.id == self.id => {
+                if let Some(show) = self.show.as_mut() {
+                    show.is_saved = true;
+                    vec![BrowserEvent::ShowSaved(self.id.clone())]
+                } else {
+                    vec![]
+                }
+            }
+            BrowserAction::UnsaveShow(id) if id == &self.id => {
+                if let Some(show) = self.show.as_mut() {
+                    show.is_saved = false;
+                    vec![BrowserEvent::ShowUnsaved(id.clone())]
+                } else {
+                    vec![]
+                }
+            }
+            _ => vec![],
+        }
+    }
+}
+
 pub struct PlaylistDetailsState {
     pub id: String,
     pub name: ScreenName,
     pub playlist: Option<PlaylistDescription>,
     pub songs: SongListModel,
+    // Positions in the actual playlist of the entries missing from songs (local files, unavailable tracks), in order
+    skipped: Vec<usize>,
 }
 
 impl PlaylistDetailsState {
@@ -106,6 +183,63 @@ impl PlaylistDetailsState {
             name: ScreenName::PlaylistDetails(id),
             playlist: None,
             songs: SongListModel::new(100),
+            skipped: vec![],
+        }
+    }
+
+    // Where the song listed at that position is in the playlist Spotify knows of
+    pub fn remote_position(&self, position: usize) -> usize {
+        self.skipped.iter().fold(position, |remote, skipped| {
+            if *skipped <= remote {
+                remote + 1
+            } else {
+                remote
+            }
+        })
+    }
+
+    // Same as remote_position, for the arguments of a reorder
+    pub fn remote_reorder(
+        &self,
+        range_start: usize,
+        range_length: usize,
+        insert_before: usize,
+    ) -> (usize, usize, usize) {
+        let start = self.remote_position(range_start);
+        let end = self.remote_position(range_start + range_length.max(1) - 1) + 1;
+        (start, end - start, self.remote_position(insert_before))
+    }
+
+    fn add_skipped(&mut self, skipped: &[usize]) {
+        self.skipped.extend_from_slice(skipped);
+        self.skipped.sort_unstable();
+        self.skipped.dedup();
+    }
+
+    fn move_skipped(&mut self, range_start: usize, range_length: usize, insert_before: usize) {
+        let (start, length, before) = self.remote_reorder(range_start, range_length, insert_before);
+        let end = start + length;
+        let new_start = if before > start {
+            before - length
+        } else {
+            before
+        };
+        for position in self.skipped.iter_mut() {
+            if (start..end).contains(position) {
+                *position = *position - start + new_start;
+            } else if before > end && (end..before).contains(position) {
+                *position -= length;
+            } else if before < start && (before..start).contains(position) {
+                *position += length;
+            }
+        }
+        self.skipped.sort_unstable();
+    }
+
+    fn remove_skipped(&mut self, removed_positions: &[usize]) {
+        for position in self.skipped.iter_mut() {
+            let removed_before = removed_positions.iter().filter(|p| **p < *position).count();
+            *position -= removed_before;
         }
     }
 }
@@ -118,18 +252,61 @@ impl UpdatableState for PlaylistDetailsState {
         match action.as_ref() {
             BrowserAction::SetPlaylistDetails(playlist) if playlist.id == self.id => {
                 let PlaylistDescription { id, songs, .. } = *playlist.clone();
+                self.add_skipped(&songs.skipped);
                 self.songs.add(songs).commit();
                 self.playlist = Some(*playlist.clone());
                 vec![BrowserEvent::PlaylistDetailsLoaded(id)]
             }
             BrowserAction::AppendPlaylistTracks(id, song_batch) if id == &self.id => {
+                self.add_skipped(&song_batch.skipped);
                 self.songs.add(*song_batch.clone()).commit();
                 vec![BrowserEvent::PlaylistTracksAppended(id.clone())]
             }
             BrowserAction::RemoveTracksFromPlaylist(id, uris) if id == &self.id => {
+                let removed_positions: Vec<usize> = self
+                    .songs
+                    .collect()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, song)| uris.contains(&song.uri) || uris.contains(&song.id))
+                    .map(|(i, _)| self.remote_position(i))
+                    .collect();
+                self.remove_skipped(&removed_positions);
                 self.songs.remove(&uris[..]).commit();
+                // Positions in the known snapshot do not match the list anymore
+                if let Some(playlist) = self.playlist.as_mut() {
+                    playlist.snapshot_id = None;
+                }
                 vec![BrowserEvent::PlaylistTracksRemoved(self.id.clone())]
             }
+            BrowserAction::MovePlaylistTracks(id, range_start, range_length, insert_before)
+                if id == &self.id =>
+            {
+                if self
+                    .songs
+                    .move_range(*range_start, *range_length, *insert_before)
+                    .commit()
+                {
+                    self.move_skipped(*range_start, *range_length, *insert_before);
+                    vec![BrowserEvent::PlaylistTracksMoved(id.clone())]
+                } else {
+                    vec![]
+                }
+            }
+            BrowserAction::SetPlaylistSnapshot(id, snapshot_id) if id == &self.id => {
+                if let Some(playlist) = self.playlist.as_mut() {
+                    playlist.snapshot_id = Some(snapshot_id.clone());
+                }
+                vec![]
+            }
+            BrowserAction::UpdatePlaylistDetails(id, update) if id == &self.id => {
+                if let Some(playlist) = self.playlist.as_mut() {
+                    playlist.apply_update(update);
+                    vec![BrowserEvent::PlaylistDetailsUpdated(id.clone())]
+                } else {
+                    vec![]
+                }
+            }
             _ => vec![],
         }
     }
@@ -142,6 +319,7 @@ pub struct ArtistState {
     pub next_page: Pagination<String>,
     pub albums: ListStore<AlbumModel>,
     pub top_tracks: SongListModel,
+    pub is_followed: bool,
 }
 
 impl ArtistState {
@@ -153,6 +331,7 @@ impl ArtistState {
             next_page: Pagination::new(id, 20),
             albums: ListStore::new(),
             top_tracks: SongListModel::new(10),
+            is_followed: false,
         }
     }
 }
@@ -169,8 +348,10 @@ impl UpdatableState for ArtistState {
                     name,
                     albums,
                     mut top_tracks,
+                    is_followed,
                 } = *details.clone();
                 self.artist = Some(name);
+                self.is_followed = is_followed;
                 self.albums
                     .replace_all(albums.into_iter().map(|a| a.into()));
                 self.next_page.reset_count(self.albums.len());
@@ -185,6 +366,14 @@ impl UpdatableState for ArtistState {
                 self.albums.extend(albums.iter().map(|a| a.into()));
                 vec![BrowserEvent::ArtistDetailsUpdated(self.id.clone())]
             }
+            BrowserAction::FollowArtist(artist) if artist.id == self.id => {
+                self.is_followed = true;
+                vec![BrowserEvent::ArtistFollowed(self.id.clone())]
+            }
+            BrowserAction::UnfollowArtist(id) if id == &self.id => {
+                self.is_followed = false;
+                vec![BrowserEvent::ArtistUnfollowed(self.id.clone())]
+            }
             _ => vec![],
         }
     }
@@ -197,6 +386,13 @@ pub struct HomeState {
     pub next_playlists_page: Pagination<()>,
     pub playlists: ListStore<AlbumModel>,
     pub saved_tracks: SongListModel,
+    // Whether tracks are in the library, for those we know about so far
+    saved_track_ids: HashMap<String, bool>,
+    pub followed_artists: ListStore<ArtistModel>,
+    // Cursor to the next followed artists, unset once they are all loaded
+    pub next_followed_artists: Option<String>,
+    pub next_shows_page: Pagination<()>,
+    pub shows: ListStore<AlbumModel>,
 }
 
 impl Default for HomeState {
@@ -208,6 +404,24 @@ impl Default for HomeState {
             next_playlists_page: Pagination::new((), 30),
             playlists: ListStore::new(),
             saved_tracks: SongListModel::new(50),
+            saved_track_ids: HashMap::new(),
+            followed_artists: ListStore::new(),
+            next_followed_artists: None,
+            next_shows_page: Pagination::new((), 30),
+            shows: ListStore::new(),
+        }
+    }
+}
+
+impl HomeState {
+    // None until the track shows up in the library or has been checked
+    pub fn is_track_saved(&self, id: &str) -> Option<bool> {
+        self.saved_track_ids.get(id).copied()
+    }
+
+    fn mark_tracks_saved<'a>(&mut self, ids: impl Iterator<Item = &'a String>, saved: bool) {
+        for id in ids {
+            self.saved_track_ids.insert(id.clone(), saved);
         }
     }
 }
@@ -254,7 +468,11 @@ impl UpdatableState for HomeState {
                 }
             }
             BrowserAction::SetPlaylistsContent(content) => {
-                if !self.playlists.eq(content, |a, b| a.uri() == b.id) {
+                // Titles are compared too, otherwise renamed playlists would not show up
+                if !self
+                    .playlists
+                    .eq(content, |a, b| a.uri() == b.id && a.album() == b.title)
+                {
                     self.playlists.replace_all(content.iter().map(|a| a.into()));
                     self.next_playlists_page.reset_count(self.playlists.len());
                     vec![BrowserEvent::SavedPlaylistsUpdated]
@@ -267,7 +485,44 @@ impl UpdatableState for HomeState {
                 self.playlists.extend(content.iter().map(|p| p.into()));
                 vec![BrowserEvent::SavedPlaylistsUpdated]
             }
+            BrowserAction::PrependPlaylistsContent(content) => {
+                let mut updated = false;
+                for playlist in content.iter().rev() {
+                    let already_present = self.playlists.iter().any(|p| p.uri() == playlist.id);
+                    if !already_present {
+                        self.playlists.insert(0, playlist.into());
+                        self.next_playlists_page.increment();
+                        updated = true;
+                    }
+                }
+                if updated {
+                    vec![BrowserEvent::SavedPlaylistsUpdated]
+                } else {
+                    vec![]
+                }
+            }
+            BrowserAction::UpdatePlaylistDetails(id, update) => {
+                let playlist = self.playlists.iter().find(|p| p.uri() == *id);
+                match (playlist, update.title.as_ref()) {
+                    (Some(playlist), Some(title)) => {
+                        playlist.set_property("album", title);
+                        vec![BrowserEvent::SavedPlaylistsUpdated]
+                    }
+                    _ => vec![],
+                }
+            }
+            BrowserAction::RemovePlaylist(id) => {
+                let position = self.playlists.iter().position(|p| p.uri() == *id);
+                if let Some(position) = position {
+                    self.playlists.remove(position as u32);
+                    self.next_playlists_page.decrement();
+                    vec![BrowserEvent::SavedPlaylistsUpdated]
+                } else {
+                    vec![]
+                }
+            }
             BrowserAction::AppendSavedTracks(song_batch) => {
+                self.mark_tracks_saved(song_batch.songs.iter().map(|s| &s.id), true);
                 if self.saved_tracks.add(*song_batch.clone()).commit() {
                     vec![BrowserEvent::SavedTracksUpdated]
                 } else {
@@ -275,6 +530,7 @@ impl UpdatableState for HomeState {
                 }
             }
             BrowserAction::SetSavedTracks(song_batch) => {
+                self.mark_tracks_saved(song_batch.songs.iter().map(|s| &s.id), true);
                 let song_batch = *song_batch.clone();
                 if self
                     .saved_tracks
@@ -288,13 +544,85 @@ impl UpdatableState for HomeState {
                 }
             }
             BrowserAction::SaveTracks(tracks) => {
+                self.mark_tracks_saved(tracks.iter().map(|s| &s.id), true);
                 self.saved_tracks.prepend(tracks.clone()).commit();
                 vec![BrowserEvent::SavedTracksUpdated]
             }
             BrowserAction::RemoveSavedTracks(tracks) => {
+                self.mark_tracks_saved(tracks.iter(), false);
                 self.saved_tracks.remove(&tracks[..]).commit();
                 vec![BrowserEvent::SavedTracksUpdated]
             }
+            BrowserAction::SetTracksSaved(tracks) => {
+                for (id, saved) in tracks.iter() {
+                    self.saved_track_ids.insert(id.clone(), *saved);
+                }
+                vec![BrowserEvent::SavedTracksUpdated]
+            }
+            BrowserAction::SetFollowedArtists(followed) => {
+                self.followed_artists
+                    .replace_all(followed.artists.iter().map(|a| a.into()));
+                self.next_followed_artists = followed.after.clone();
+                vec![BrowserEvent::FollowedArtistsUpdated]
+            }
+            BrowserAction::AppendFollowedArtists(followed) => {
+                self.followed_artists
+                    .extend(followed.artists.iter().map(|a| a.into()));
+                self.next_followed_artists = followed.after.clone();
+                vec![BrowserEvent::FollowedArtistsUpdated]
+            }
+            BrowserAction::FollowArtist(artist) => {
+                let already_present = self.followed_artists.iter().any(|a| a.id() == artist.id);
+                if already_present {
+                    vec![]
+                } else {
+                    self.followed_artists.insert(0, (&**artist).into());
+                    vec![BrowserEvent::FollowedArtistsUpdated]
+                }
+            }
+            BrowserAction::UnfollowArtist(id) => {
+                let position = self.followed_artists.iter().position(|a| a.id() == *id);
+                if let Some(position) = position {
+                    self.followed_artists.remove(position as u32);
+                    vec![BrowserEvent::FollowedArtistsUpdated]
+                } else {
+                    vec![]
+                }
+            }
+            BrowserAction::SetSavedShows(content) => {
+                if !self.shows.eq(content, |a, b| a.uri() == b.id) {
+                    self.shows.replace_all(content.iter().map(|s| s.into()));
+                    self.next_shows_page.reset_count(self.shows.len());
+                    vec![BrowserEvent::SavedShowsUpdated]
+                } else {
+                    vec![]
+                }
+            }
+            BrowserAction::AppendSavedShows(content) => {
+                self.next_shows_page.set_loaded_count(content.len());
+                self.shows.extend(content.iter().map(|s| s.into()));
+                vec![BrowserEvent::SavedShowsUpdated]
+            }
+            BrowserAction::SaveShow(show) => {
+                let already_present = self.shows.iter().any(|s| s.uri() == show.id);
+                if already_present {
+                    vec![]
+                } else {
+                    self.shows.insert(0, (&**show).into());
+                    self.next_shows_page.increment();
+                    vec![BrowserEvent::SavedShowsUpdated]
+                }
+            }
+            BrowserAction::UnsaveShow(id) => {
+                let position = self.shows.iter().position(|s| s.uri() == *id);
+                if let Some(position) = position {
+                    self.shows.remove(position as u32);
+                    self.next_shows_page.decrement();
+                    vec![BrowserEvent::SavedShowsUpdated]
+                } else {
+                    vec![]
+                }
+            }
             _ => vec![],
         }
     }
@@ -303,8 +631,61 @@ impl UpdatableState for HomeState {
 pub struct SearchState {
     pub name: ScreenName,
     pub query: String,
-    pub album_results: Vec<AlbumDescription>,
-    pub artist_results: Vec<ArtistSummary>,
+    pub overview_size: usize,
+    pub album_results: ListStore<AlbumModel>,
+    pub artist_results: ListStore<ArtistModel>,
+    pub playlist_results: ListStore<AlbumModel>,
+    pub song_results: SongListModel,
+    // Only set when browsing a single category ("see all")
+    pub category_page: Option<Pagination<SearchCategory>>,
+}
+
+impl SearchState {
+    pub fn category(&self) -> Option<SearchCategory> {
+        self.category_page.as_ref().map(|page| page.data)
+    }
+
+    fn clear_category(&mut self, category: SearchCategory) {
+        match category {
+            SearchCategory::Albums => self.album_results.truncate(0),
+            SearchCategory::Artists => self.artist_results.truncate(0),
+            SearchCategory::Playlists => self.playlist_results.truncate(0),
+            SearchCategory::Songs => {
+                self.song_results.clear().commit();
+            }
+        }
+    }
+
+    fn truncate_category(&mut self, category: SearchCategory) {
+        let size = self.overview_size;
+        match category {
+            SearchCategory::Albums => self.album_results.truncate(size),
+            SearchCategory::Artists => self.artist_results.truncate(size),
+            SearchCategory::Playlists => self.playlist_results.truncate(size),
+            SearchCategory::Songs => {
+                let songs: Vec<SongDescription> =
+                    self.song_results.collect().into_iter().take(size).collect();
+                self.song_results.clear().and(|s| s.append(songs)).commit();
+            }
+        }
+    }
+
+    fn extend_category(&mut self, category: SearchCategory, results: &SearchResults) {
+        match category {
+            SearchCategory::Albums => self
+                .album_results
+                .extend(results.albums.iter().map(|a| a.into())),
+            SearchCategory::Artists => self
+                .artist_results
+                .extend(results.artists.iter().map(|a| a.into())),
+            SearchCategory::Playlists => self
+                .playlist_results
+                .extend(results.playlists.iter().map(|p| p.into())),
+            SearchCategory::Songs => {
+                self.song_results.append(results.songs.clone()).commit();
+            }
+        }
+    }
 }
 
 impl Default for SearchState {
@@ -312,8 +693,12 @@ impl Default for SearchState {
         Self {
             name: ScreenName::Search,
             query: "".to_owned(),
-            album_results: vec![],
-            artist_results: vec![],
+            overview_size: 5,
+            album_results: ListStore::new(),
+            artist_results: ListStore::new(),
+            playlist_results: ListStore::new(),
+            song_results: SongListModel::new(20),
+            category_page: None,
         }
     }
 }
@@ -329,8 +714,39 @@ impl UpdatableState for SearchState {
                 vec![BrowserEvent::SearchUpdated]
             }
             BrowserAction::SetSearchResults(results) => {
-                self.album_results = results.albums.clone();
-                self.artist_results = results.artists.clone();
+                self.album_results
+                    .replace_all(results.albums.iter().map(|a| a.into()));
+                self.artist_results
+                    .replace_all(results.artists.iter().map(|a| a.into()));
+                self.playlist_results
+                    .replace_all(results.playlists.iter().map(|p| p.into()));
+                let songs = results.songs.clone();
+                self.song_results.clear().and(|s| s.append(songs)).commit();
+
+                let mut events = vec![BrowserEvent::SearchResultsUpdated];
+                if self.category_page.take().is_some() {
+                    events.push(BrowserEvent::SearchCategoryChanged(None));
+                }
+                events
+            }
+            BrowserAction::ShowSearchCategory(category) if category != &self.category() => {
+                if let Some(previous) = self.category_page.take() {
+                    self.truncate_category(previous.data);
+                }
+                if let Some(category) = *category {
+                    self.clear_category(category);
+                    self.category_page = Some(Pagination::new(category, 20));
+                }
+                vec![BrowserEvent::SearchCategoryChanged(*category)]
+            }
+            // Results for an earlier query or category are dropped
+            BrowserAction::AppendSearchResults(query, category, results)
+                if query == &self.query && Some(*category) == self.category() =>
+            {
+                if let Some(page) = self.category_page.as_mut() {
+                    page.set_loaded_count(results.len_for(*category));
+                }
+                self.extend_category(*category, results);
                 vec![BrowserEvent::SearchResultsUpdated]
             }
             _ => vec![],
@@ -387,10 +803,130 @@ impl UpdatableState for UserState {
     }
 }
 
+pub struct RecentlyPlayedState {
+    pub name: ScreenName,
+    pub songs: SongListModel,
+    // Cursor to older plays, unset once the whole history is loaded
+    pub before: Option<String>,
+}
+
+impl Default for RecentlyPlayedState {
+    fn default() -> Self {
+        Self {
+            name: ScreenName::RecentlyPlayed,
+            songs: SongListModel::new(50),
+            before: None,
+        }
+    }
+}
+
+impl RecentlyPlayedState {
+    // Songs played several times are only listed once, at their most recent play
+    fn unique_plays<F>(songs: &[SongDescription], is_known: F) -> Vec<SongDescription>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut seen = HashSet::new();
+        songs
+            .iter()
+            .filter(|song| !is_known(&song.id) && seen.insert(song.id.clone()))
+            .cloned()
+            .collect()
+    }
+}
+
+impl UpdatableState for RecentlyPlayedState {
+    type Action = BrowserAction;
+    type Event = BrowserEvent;
+
+    fn update_with(&mut self, action: Cow<Self::Action>) -> Vec<Self::Event> {
+        match action.as_ref() {
+            BrowserAction::SetRecentlyPlayed(recently_played) => {
+                let songs = Self::unique_plays(&recently_played.songs, |_| false);
+                self.songs.clear().and(|s| s.append(songs)).commit();
+                self.before = recently_played.before.clone();
+                vec![BrowserEvent::RecentlyPlayedUpdated]
+            }
+            BrowserAction::AppendRecentlyPlayed(recently_played) => {
+                let songs =
+                    Self::unique_plays(&recently_played.songs, |id| self.songs.get(id).is_some());
+                self.songs.append(songs).commit();
+                self.before = recently_played.before.clone();
+                vec![BrowserEvent::RecentlyPlayedUpdated]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+pub struct TopItemsState {
+    pub name: ScreenName,
+    pub time_range: TimeRange,
+    pub songs: SongListModel,
+    pub artists: ListStore<ArtistModel>,
+    pub next_artists_page: Pagination<()>,
+}
+
+impl Default for TopItemsState {
+    fn default() -> Self {
+        Self {
+            name: ScreenName::TopItems,
+            time_range: TimeRange::Short,
+            songs: SongListModel::new(50),
+            artists: ListStore::new(),
+            next_artists_page: Pagination::new((), 20),
+        }
+    }
+}
+
+impl UpdatableState for TopItemsState {
+    type Action = BrowserAction;
+    type Event = BrowserEvent;
+
+    fn update_with(&mut self, action: Cow<Self::Action>) -> Vec<Self::Event> {
+        match action.as_ref() {
+            BrowserAction::SetTopItemsTimeRange(time_range) if time_range != &self.time_range => {
+                self.time_range = *time_range;
+                self.songs.clear().commit();
+                self.artists.truncate(0);
+                self.next_artists_page = Pagination::new((), 20);
+                vec![BrowserEvent::TopItemsTimeRangeChanged(*time_range)]
+            }
+            BrowserAction::SetTopItems(time_range, items) if time_range == &self.time_range => {
+                match items.as_ref() {
+                    TopItems::Songs(batch) => {
+                        let batch = batch.clone();
+                        self.songs.clear().and(|s| s.add(batch)).commit();
+                    }
+                    TopItems::Artists(artists) => {
+                        self.artists.replace_all(artists.iter().map(|a| a.into()));
+                        self.next_artists_page.reset_count(self.artists.len());
+                    }
+                }
+                vec![BrowserEvent::TopItemsUpdated]
+            }
+            BrowserAction::AppendTopItems(time_range, items) if time_range == &self.time_range => {
+                match items.as_ref() {
+                    TopItems::Songs(batch) => {
+                        self.songs.add(batch.clone()).commit();
+                    }
+                    TopItems::Artists(artists) => {
+                        self.next_artists_page.set_loaded_count(artists.len());
+                        self.artists.extend(artists.iter().map(|a| a.into()));
+                    }
+                }
+                vec![BrowserEvent::TopItemsUpdated]
+            }
+            _ => vec![],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::app::testing::{episode, playlist, show, song};
 
     #[test]
     fn test_next_page_no_next() {
@@ -401,6 +937,7 @@ mod tests {
                 name: "Foo".to_owned(),
                 albums: vec![],
                 top_tracks: vec![],
+                is_followed: false,
             },
         ))));
 
@@ -427,6 +964,7 @@ mod tests {
                 name: "Foo".to_owned(),
                 albums: (0..20).map(|_| fake_album.clone()).collect(),
                 top_tracks: vec![],
+                is_followed: false,
             },
         ))));
 
@@ -441,4 +979,334 @@ mod tests {
         let next = &artist_state.next_page;
         assert_eq!(None, next.next_offset);
     }
+
+    #[test]
+    fn test_search_category_pages() {
+        let results = |count: usize| SearchResults {
+            albums: vec![],
+            artists: (0..count)
+                .map(|i| ArtistSummary {
+                    id: i.to_string(),
+                    name: "Foo".to_owned(),
+                    photo: None,
+                })
+                .collect(),
+            songs: vec![],
+            playlists: vec![],
+        };
+
+        let mut search_state = SearchState::default();
+        search_state.update_with(Cow::Owned(BrowserAction::SetSearchResults(Box::new(
+            results(5),
+        ))));
+        assert_eq!(search_state.artist_results.len(), 5);
+
+        let events = search_state.update_with(Cow::Owned(BrowserAction::ShowSearchCategory(Some(
+            SearchCategory::Artists,
+        ))));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::SearchCategoryChanged(Some(
+                SearchCategory::Artists
+            ))]
+        );
+        assert_eq!(search_state.artist_results.len(), 0);
+
+        search_state.update_with(Cow::Owned(BrowserAction::AppendSearchResults(
+            "".to_owned(),
+            SearchCategory::Artists,
+            Box::new(results(20)),
+        )));
+        assert_eq!(
+            Some(20),
+            search_state.category_page.as_ref().unwrap().next_offset
+        );
+
+        // the page of a previous query comes too late
+        let events = search_state.update_with(Cow::Owned(BrowserAction::AppendSearchResults(
+            "previous".to_owned(),
+            SearchCategory::Artists,
+            Box::new(results(20)),
+        )));
+        assert!(events.is_empty());
+        assert_eq!(search_state.artist_results.len(), 20);
+
+        search_state.update_with(Cow::Owned(BrowserAction::AppendSearchResults(
+            "".to_owned(),
+            SearchCategory::Artists,
+            Box::new(results(3)),
+        )));
+        assert_eq!(search_state.artist_results.len(), 23);
+        assert_eq!(
+            None,
+            search_state.category_page.as_ref().unwrap().next_offset
+        );
+
+        search_state.update_with(Cow::Owned(BrowserAction::ShowSearchCategory(None)));
+        assert_eq!(search_state.category(), None);
+        assert_eq!(search_state.artist_results.len(), 5);
+    }
+
+    #[test]
+    fn test_home_playlists_management() {
+        let mut home_state = HomeState::default();
+        home_state.update_with(Cow::Owned(BrowserAction::SetPlaylistsContent(vec![
+            playlist("1", "me"),
+            playlist("2", "me"),
+        ])));
+
+        let events =
+            home_state.update_with(Cow::Owned(BrowserAction::PrependPlaylistsContent(vec![
+                playlist("3", "me"),
+            ])));
+        assert_eq!(events, vec![BrowserEvent::SavedPlaylistsUpdated]);
+        assert_eq!(home_state.playlists.get(0).uri(), "3");
+        assert_eq!(home_state.next_playlists_page.next_offset, None);
+
+        home_state.update_with(Cow::Owned(BrowserAction::UpdatePlaylistDetails(
+            "2".to_owned(),
+            PlaylistDetailsUpdate {
+                title: Some("Renamed".to_owned()),
+                ..Default::default()
+            },
+        )));
+        assert_eq!(home_state.playlists.get(2).album(), "Renamed");
+
+        // Only a rename makes the content differ
+        let events = home_state.update_with(Cow::Owned(BrowserAction::SetPlaylistsContent(vec![
+            playlist("3", "me"),
+            playlist("1", "me"),
+            playlist("2", "me"),
+        ])));
+        assert_eq!(events, vec![BrowserEvent::SavedPlaylistsUpdated]);
+        assert_eq!(home_state.playlists.get(2).album(), "Playlist 2");
+
+        let events =
+            home_state.update_with(Cow::Owned(BrowserAction::RemovePlaylist("1".to_owned())));
+        assert_eq!(events, vec![BrowserEvent::SavedPlaylistsUpdated]);
+        assert_eq!(home_state.playlists.len(), 2);
+
+        let events =
+            home_state.update_with(Cow::Owned(BrowserAction::RemovePlaylist("1".to_owned())));
+        assert_eq!(events, vec![]);
+    }
+
+    #[test]
+    fn test_home_saved_track_ids() {
+        let mut home_state = HomeState::default();
+        assert_eq!(home_state.is_track_saved("1"), None);
+
+        let events = home_state.update_with(Cow::Owned(BrowserAction::SetTracksSaved(vec![
+            ("1".to_owned(), false),
+            ("2".to_owned(), true),
+        ])));
+        assert_eq!(events, vec![BrowserEvent::SavedTracksUpdated]);
+        assert_eq!(home_state.is_track_saved("1"), Some(false));
+        assert_eq!(home_state.is_track_saved("2"), Some(true));
+
+        home_state.update_with(Cow::Owned(BrowserAction::SaveTracks(vec![song("1")])));
+        assert_eq!(home_state.is_track_saved("1"), Some(true));
+        assert_eq!(song_ids(&home_state.saved_tracks), vec!["1"]);
+
+        home_state.update_with(Cow::Owned(BrowserAction::RemoveSavedTracks(vec![
+            "1".to_owned()
+        ])));
+        assert_eq!(home_state.is_track_saved("1"), Some(false));
+        assert!(home_state.saved_tracks.collect().is_empty());
+    }
+
+    #[test]
+    fn test_playlist_details_update() {
+        let mut state = PlaylistDetailsState::new("1".to_owned());
+        state.update_with(Cow::Owned(BrowserAction::SetPlaylistDetails(Box::new(
+            playlist("1", "me"),
+        ))));
+
+        let events = state.update_with(Cow::Owned(BrowserAction::UpdatePlaylistDetails(
+            "1".to_owned(),
+            PlaylistDetailsUpdate {
+                description: Some("Some tunes".to_owned()),
+                collaborative: Some(true),
+                public: Some(false),
+                ..Default::default()
+            },
+        )));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::PlaylistDetailsUpdated("1".to_owned())]
+        );
+
+        let playlist = state.playlist.as_ref().unwrap();
+        assert_eq!(playlist.title, "Playlist 1");
+        assert_eq!(playlist.description.as_deref(), Some("Some tunes"));
+        assert_eq!(playlist.public, Some(false));
+        assert!(playlist.collaborative);
+    }
+
+    #[test]
+    fn test_playlist_tracks_move() {
+        let mut state = PlaylistDetailsState::new("1".to_owned());
+        state.update_with(Cow::Owned(BrowserAction::SetPlaylistDetails(Box::new(
+            PlaylistDescription {
+                songs: SongBatch {
+                    songs: vec![song("a"), song("b"), song("c")],
+                    batch: Batch {
+                        offset: 0,
+                        batch_size: 100,
+                        total: 3,
+                    },
+                    skipped: vec![],
+                },
+                ..playlist("1", "me")
+            },
+        ))));
+
+        let events = state.update_with(Cow::Owned(BrowserAction::MovePlaylistTracks(
+            "1".to_owned(),
+            2,
+            1,
+            0,
+        )));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::PlaylistTracksMoved("1".to_owned())]
+        );
+        let ids: Vec<String> = state.songs.collect().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+
+        state.update_with(Cow::Owned(BrowserAction::SetPlaylistSnapshot(
+            "1".to_owned(),
+            "new_snapshot".to_owned(),
+        )));
+        let playlist = state.playlist.as_ref().unwrap();
+        assert_eq!(playlist.snapshot_id.as_deref(), Some("new_snapshot"));
+
+        state.update_with(Cow::Owned(BrowserAction::RemoveTracksFromPlaylist(
+            "1".to_owned(),
+            vec!["a".to_owned()],
+        )));
+        assert_eq!(state.playlist.as_ref().unwrap().snapshot_id, None);
+    }
+
+    fn song_ids(songs: &SongListModel) -> Vec<String> {
+        songs.collect().into_iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn test_recently_played_repeated_songs() {
+        let mut state = RecentlyPlayedState::default();
+        let events = state.update_with(Cow::Owned(BrowserAction::SetRecentlyPlayed(Box::new(
+            RecentlyPlayed {
+                songs: vec![song("a"), song("b"), song("a")],
+                before: Some("2".to_owned()),
+            },
+        ))));
+        assert_eq!(events, vec![BrowserEvent::RecentlyPlayedUpdated]);
+        assert_eq!(song_ids(&state.songs), vec!["a", "b"]);
+
+        state.update_with(Cow::Owned(BrowserAction::AppendRecentlyPlayed(Box::new(
+            RecentlyPlayed {
+                songs: vec![song("b"), song("c")],
+                before: None,
+            },
+        ))));
+        assert_eq!(song_ids(&state.songs), vec!["a", "b", "c"]);
+        assert_eq!(state.before, None);
+    }
+
+    #[test]
+    fn test_top_items_time_range() {
+        let mut state = TopItemsState::default();
+        let artist = ArtistSummary {
+            id: "artist".to_owned(),
+            name: "Artist".to_owned(),
+            photo: None,
+        };
+        state.update_with(Cow::Owned(BrowserAction::SetTopItems(
+            TimeRange::Short,
+            Box::new(TopItems::Artists(vec![artist.clone()])),
+        )));
+        state.update_with(Cow::Owned(BrowserAction::SetTopItems(
+            TimeRange::Short,
+            Box::new(TopItems::Songs(SongBatch {
+                songs: vec![song("a"), song("b")],
+                batch: Batch {
+                    offset: 0,
+                    batch_size: 50,
+                    total: 2,
+                },
+                skipped: vec![],
+            })),
+        )));
+        assert_eq!(state.artists.len(), 1);
+        assert_eq!(song_ids(&state.songs), vec!["a", "b"]);
+
+        let events = state.update_with(Cow::Owned(BrowserAction::SetTopItemsTimeRange(
+            TimeRange::Long,
+        )));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::TopItemsTimeRangeChanged(TimeRange::Long)]
+        );
+        assert_eq!(state.artists.len(), 0);
+        assert_eq!(state.songs.partial_len(), 0);
+
+        // Results for another range arriving late are ignored
+        let events = state.update_with(Cow::Owned(BrowserAction::SetTopItems(
+            TimeRange::Short,
+            Box::new(TopItems::Artists(vec![artist])),
+        )));
+        assert_eq!(events, vec![]);
+        assert_eq!(state.artists.len(), 0);
+    }
+
+    #[test]
+    fn test_follow_artist() {
+        let artist = ArtistSummary {
+            id: "artist".to_owned(),
+            name: "Artist".to_owned(),
+            photo: None,
+        };
+        let mut artist_state = ArtistState::new("artist".to_owned());
+        let mut home_state = HomeState::default();
+
+        let follow = BrowserAction::FollowArtist(Box::new(artist));
+        let events = artist_state.update_with(Cow::Borrowed(&follow));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::ArtistFollowed("artist".to_owned())]
+        );
+        assert!(artist_state.is_followed);
+        home_state.update_with(Cow::Borrowed(&follow));
+        home_state.update_with(Cow::Borrowed(&follow));
+        assert_eq!(home_state.followed_artists.len(), 1);
+
+        let unfollow = BrowserAction::UnfollowArtist("artist".to_owned());
+        artist_state.update_with(Cow::Borrowed(&unfollow));
+        assert!(!artist_state.is_followed);
+        home_state.update_with(Cow::Borrowed(&unfollow));
+        assert_eq!(home_state.followed_artists.len(), 0);
+    }
+
+    #[test]
+    fn test_show_episodes_resume_positions() {
+        let mut first = episode("episode0", "show");
+        first.resume_position = Some(42);
+        let show = show("show", vec![first, episode("episode1", "show")]);
+
+        let mut state = ShowState::new("show".to_owned());
+        let events = state.update_with(Cow::Owned(BrowserAction::SetShowDetails(Box::new(show))));
+        assert_eq!(
+            events,
+            vec![BrowserEvent::ShowDetailsLoaded("show".to_owned())]
+        );
+        assert_eq!(state.episodes.partial_len(), 2);
+        assert_eq!(state.resume_positions.get("episode0"), Some(&42));
+        assert_eq!(state.resume_positions.get("episode1"), None);
+
+        let save = BrowserAction::SaveShow(Box::new(state.show.clone().unwrap()));
+        let events = state.update_with(Cow::Owned(save));
+        assert_eq!(events, vec![BrowserEvent::ShowSaved("show".to_owned())]);
+        assert!(state.show.as_ref().unwrap().is_saved);
+    }
 }
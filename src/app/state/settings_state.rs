@@ -1,11 +1,14 @@
 use crate::{
     app::state::{AppAction, AppEvent, UpdatableState},
+    player::AudioBackend,
     settings::SpotSettings,
 };
 
 #[derive(Clone, Debug)]
 pub enum SettingsAction {
     ChangeSettings,
+    // None for the default sink
+    SetAudioSink(Option<String>),
 }
 
 impl From<SettingsAction> for AppAction {
@@ -17,6 +20,8 @@ impl From<SettingsAction> for AppAction {
 #[derive(Clone, Debug)]
 pub enum SettingsEvent {
     PlayerSettingsChanged,
+    // Unlike other settings, does not interrupt playback
+    AudioSinkChanged(Option<String>),
 }
 
 impl From<SettingsEvent> for AppEvent {
@@ -37,17 +42,62 @@ impl UpdatableState for SettingsState {
     fn update_with(&mut self, action: std::borrow::Cow<Self::Action>) -> Vec<Self::Event> {
         match action.into_owned() {
             SettingsAction::ChangeSettings => {
-                let old_settings = &self.settings;
+                let old_settings = &self.settings.player_settings;
                 let new_settings = SpotSettings::new_from_gsettings().unwrap_or_default();
-                let player_settings_changed =
-                    new_settings.player_settings != old_settings.player_settings;
-                self.settings = new_settings;
-                if player_settings_changed {
-                    vec![SettingsEvent::PlayerSettingsChanged.into()]
-                } else {
+                let events = if new_settings.player_settings == *old_settings {
                     vec![]
+                } else if let Some(sink) = old_settings.switched_sink(&new_settings.player_settings)
+                {
+                    vec![SettingsEvent::AudioSinkChanged(sink).into()]
+                } else {
+                    vec![SettingsEvent::PlayerSettingsChanged.into()]
+                };
+                self.settings = new_settings;
+                events
+            }
+            SettingsAction::SetAudioSink(sink) => {
+                match &mut self.settings.player_settings.backend {
+                    AudioBackend::PulseAudio(current) if *current != sink => {
+                        *current = sink.clone();
+                        vec![SettingsEvent::AudioSinkChanged(sink).into()]
+                    }
+                    _ => vec![],
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_set_audio_sink() {
+        let mut state = SettingsState::default();
+
+        let events = state.update_with(Cow::Owned(SettingsAction::SetAudioSink(Some(
+            "headphones".to_string(),
+        ))));
+        assert!(matches!(
+            &events[..],
+            [AppEvent::SettingsEvent(SettingsEvent::AudioSinkChanged(Some(sink)))] if sink == "headphones"
+        ));
+        assert_eq!(
+            state.settings.player_settings.backend,
+            AudioBackend::PulseAudio(Some("headphones".to_string()))
+        );
+
+        // Already playing there
+        let events = state.update_with(Cow::Owned(SettingsAction::SetAudioSink(Some(
+            "headphones".to_string(),
+        ))));
+        assert!(events.is_empty());
+
+        // No sink to pick with other backends
+        state.settings.player_settings.backend = AudioBackend::Alsa("default".to_string());
+        let events = state.update_with(Cow::Owned(SettingsAction::SetAudioSink(None)));
+        assert!(events.is_empty());
+    }
+}
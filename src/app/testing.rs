@@ -0,0 +1,232 @@
+use futures::executor::block_on;
+use futures::future::{BoxFuture, LocalBoxFuture};
+use std::cell::{Ref, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::api::FakeSpotifyClient;
+use crate::app::models::*;
+use crate::app::state::{LoginAction, SetLoginSuccessAction};
+use crate::app::{ActionDispatcher, AppAction, AppEvent, AppModel, AppState};
+
+// Drives the app's model without a main loop: dispatched actions and futures are queued,
+// and only processed when the test calls run_until_idle.
+
+#[derive(Default)]
+struct DispatchQueue {
+    actions: VecDeque<AppAction>,
+    tasks: VecDeque<LocalBoxFuture<'static, Vec<AppAction>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct TestDispatcher {
+    queue: Rc<RefCell<DispatchQueue>>,
+}
+
+impl ActionDispatcher for TestDispatcher {
+    fn dispatch(&self, action: AppAction) {
+        self.queue.borrow_mut().actions.push_back(action);
+    }
+
+    fn dispatch_local_async(&self, action: LocalBoxFuture<'static, Option<AppAction>>) {
+        self.queue
+            .borrow_mut()
+            .tasks
+            .push_back(Box::pin(async move { action.await.into_iter().collect() }));
+    }
+
+    fn dispatch_async(&self, action: BoxFuture<'static, Option<AppAction>>) {
+        self.queue
+            .borrow_mut()
+            .tasks
+            .push_back(Box::pin(async move { action.await.into_iter().collect() }));
+    }
+
+    fn dispatch_many_async(&self, actions: BoxFuture<'static, Vec<AppAction>>) {
+        self.queue.borrow_mut().tasks.push_back(actions);
+    }
+
+    fn box_clone(&self) -> Box<dyn ActionDispatcher> {
+        Box::new(self.clone())
+    }
+}
+
+pub struct TestApp {
+    pub model: Rc<AppModel>,
+    pub api: Arc<FakeSpotifyClient>,
+    dispatcher: TestDispatcher,
+}
+
+impl TestApp {
+    pub fn new(api: FakeSpotifyClient) -> Self {
+        Self::with_state(api, AppState::new())
+    }
+
+    pub fn with_state(api: FakeSpotifyClient, state: AppState) -> Self {
+        let api = Arc::new(api);
+        let model = Rc::new(AppModel::new(state, api.clone()));
+        Self {
+            model,
+            api,
+            dispatcher: Default::default(),
+        }
+    }
+
+    // Same as a successful login, without going through librespot
+    pub fn logged_in(api: FakeSpotifyClient, username: &str) -> Self {
+        let app = Self::new(api);
+        app.dispatch(
+            LoginAction::SetLoginSuccess(SetLoginSuccessAction::Token {
+                username: username.to_string(),
+                token: "token".to_string(),
+            })
+            .into(),
+        );
+        app.run_until_idle();
+        app
+    }
+
+    pub fn dispatcher(&self) -> Box<dyn ActionDispatcher> {
+        self.dispatcher.box_clone()
+    }
+
+    pub fn dispatch(&self, action: AppAction) {
+        self.dispatcher.dispatch(action);
+    }
+
+    pub fn state(&self) -> Ref<'_, AppState> {
+        self.model.get_state()
+    }
+
+    // Processes actions in the order they were dispatched; futures only run once no plain
+    // action is left, like they would on the idle priority of the main loop.
+    pub fn run_until_idle(&self) -> Vec<AppEvent> {
+        let mut events = vec![];
+        loop {
+            let action = self.dispatcher.queue.borrow_mut().actions.pop_front();
+            if let Some(action) = action {
+                events.extend(self.model.update_state(action));
+                continue;
+            }
+
+            let task = self.dispatcher.queue.borrow_mut().tasks.pop_front();
+            match task {
+                Some(task) => {
+                    let actions = block_on(task);
+                    self.dispatcher.queue.borrow_mut().actions.extend(actions);
+                }
+                None => break,
+            }
+        }
+        events
+    }
+}
+
+pub fn user(id: &str) -> UserRef {
+    UserRef {
+        id: id.to_string(),
+        display_name: format!("User {}", id),
+    }
+}
+
+pub fn song(id: &str) -> SongDescription {
+    SongDescription {
+        id: id.to_string(),
+        track_number: None,
+        disc_number: None,
+        uri: format!("spotify:track:{}", id),
+        title: format!("Song {}", id),
+        artists: vec![ArtistRef {
+            id: "artist0".to_string(),
+            name: "Artist".to_string(),
+        }],
+        album: AlbumRef {
+            id: "album0".to_string(),
+            name: "Album".to_string(),
+        },
+        album_artists: vec![],
+        duration: 1000,
+        art: None,
+    }
+}
+
+pub fn album(id: &str, songs: Vec<SongDescription>) -> AlbumFullDescription {
+    let total_tracks = songs.len();
+    AlbumFullDescription {
+        description: AlbumDescription {
+            id: id.to_string(),
+            title: format!("Album {}", id),
+            artists: vec![],
+            release_date: None,
+            art: None,
+            songs: SongBatch {
+                batch: Batch {
+                    offset: 0,
+                    batch_size: total_tracks.max(1),
+                    total: total_tracks,
+                },
+                songs,
+                skipped: vec![],
+            },
+            is_liked: false,
+        },
+        release_details: AlbumReleaseDetails {
+            label: String::new(),
+            copyright_text: String::new(),
+            total_tracks,
+        },
+    }
+}
+
+// The songs are set on the fake client separately, see FakeSpotifyClient::with_playlist
+pub fn playlist(id: &str, owner_id: &str) -> PlaylistDescription {
+    PlaylistDescription {
+        id: id.to_string(),
+        title: format!("Playlist {}", id),
+        description: None,
+        public: Some(true),
+        collaborative: false,
+        snapshot_id: Some("snapshot0".to_string()),
+        art: None,
+        songs: SongBatch::empty(),
+        owner: user(owner_id),
+    }
+}
+
+pub fn episode(id: &str, show_id: &str) -> EpisodeDescription {
+    EpisodeDescription {
+        id: id.to_string(),
+        uri: format!("spotify:episode:{}", id),
+        title: format!("Episode {}", id),
+        description: None,
+        release_date: None,
+        duration: 1000,
+        art: None,
+        show: AlbumRef {
+            id: show_id.to_string(),
+            name: format!("Show {}", show_id),
+        },
+        resume_position: None,
+    }
+}
+
+pub fn show(id: &str, episodes: Vec<EpisodeDescription>) -> ShowDescription {
+    let total = episodes.len();
+    ShowDescription {
+        id: id.to_string(),
+        title: format!("Show {}", id),
+        publisher: "Publisher".to_string(),
+        description: None,
+        art: None,
+        episodes: EpisodeBatch {
+            batch: Batch {
+                offset: 0,
+                batch_size: total.max(1),
+                total,
+            },
+            episodes,
+        },
+        is_saved: false,
+    }
+}
@@ -0,0 +1,188 @@
+use std::fmt;
+
+use crate::app::SongsSource;
+
+// Links to the web player, possibly localized, as in open.spotify.com/intl-fr/track/<id>
+const WEB_PLAYER_PREFIXES: [&str; 2] = ["https://open.spotify.com/", "http://open.spotify.com/"];
+
+// Resources other programs can point us to, as Spotify URIs or links to the web player
+#[derive(Debug, PartialEq, Eq)]
+pub enum SpotifyUri<'a> {
+    Artist(&'a str),
+    Album(&'a str),
+    Playlist(&'a str),
+    Track(&'a str),
+    User(&'a str),
+    Show(&'a str),
+    Episode(&'a str),
+}
+
+impl<'a> SpotifyUri<'a> {
+    pub fn parse(uri: &'a str) -> Option<Self> {
+        // Tracking parameters such as ?si= are of no use to us
+        let uri = uri.split(&['?', '#'][..]).next()?;
+
+        let web_path = WEB_PLAYER_PREFIXES
+            .iter()
+            .find_map(|prefix| uri.strip_prefix(prefix));
+        let parts: Vec<&str> = if let Some(path) = web_path {
+            path.split('/')
+                .filter(|part| !part.is_empty())
+                .skip_while(|part| part.starts_with("intl-"))
+                .collect()
+        } else {
+            // Might start with /// because of https://gitlab.gnome.org/GNOME/glib/-/issues/1886/
+            let path = uri.strip_prefix("spotify:")?.trim_start_matches('/');
+            path.split(':').collect()
+        };
+
+        match parts[..] {
+            // Playlists used to be named after their owner
+            ["user", _, "playlist", id] => Self::from_kind("playlist", id),
+            [kind, id] => Self::from_kind(kind, id),
+            _ => None,
+        }
+    }
+
+    // Pasted or dropped text may hold several links, one per line or mixed with other words
+    pub fn parse_all(text: &'a str) -> Vec<Self> {
+        text.split_whitespace().filter_map(Self::parse).collect()
+    }
+
+    fn from_kind(kind: &str, id: &'a str) -> Option<Self> {
+        if id.is_empty() {
+            return None;
+        }
+        match kind {
+            "artist" => Some(Self::Artist(id)),
+            "album" => Some(Self::Album(id)),
+            "playlist" => Some(Self::Playlist(id)),
+            "track" => Some(Self::Track(id)),
+            "user" => Some(Self::User(id)),
+            "show" => Some(Self::Show(id)),
+            "episode" => Some(Self::Episode(id)),
+            _ => None,
+        }
+    }
+
+    fn kind_and_id(&self) -> (&'static str, &'a str) {
+        match *self {
+            Self::Artist(id) => ("artist", id),
+            Self::Album(id) => ("album", id),
+            Self::Playlist(id) => ("playlist", id),
+            Self::Track(id) => ("track", id),
+            Self::User(id) => ("user", id),
+            Self::Show(id) => ("show", id),
+            Self::Episode(id) => ("episode", id),
+        }
+    }
+
+    // The same resource on the web player, which is what people share
+    pub fn link(&self) -> String {
+        let (kind, id) = self.kind_and_id();
+        format!("https://open.spotify.com/{}/{}", kind, id)
+    }
+
+    // Albums, playlists and shows are loaded by pages, as the user goes through them
+    pub fn songs_source(&self) -> Option<SongsSource> {
+        match self {
+            Self::Album(id) => Some(SongsSource::Album(id.to_string())),
+            Self::Playlist(id) => Some(SongsSource::Playlist(id.to_string())),
+            Self::Show(id) => Some(SongsSource::Show(id.to_string())),
+            Self::Artist(_) | Self::Track(_) | Self::User(_) | Self::Episode(_) => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for SpotifyUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, id) = self.kind_and_id();
+        write!(f, "spotify:{}:{}", kind, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_uris() {
+        assert_eq!(
+            SpotifyUri::parse("spotify:album:abc"),
+            Some(SpotifyUri::Album("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("spotify:///artist:abc"),
+            Some(SpotifyUri::Artist("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("spotify:track:abc"),
+            Some(SpotifyUri::Track("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("spotify:user:someone:playlist:abc"),
+            Some(SpotifyUri::Playlist("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("spotify:user:someone"),
+            Some(SpotifyUri::User("someone"))
+        );
+        assert_eq!(SpotifyUri::parse("spotify:album:"), None);
+        assert_eq!(
+            SpotifyUri::parse("spotify:episode:abc"),
+            Some(SpotifyUri::Episode("abc"))
+        );
+        assert_eq!(SpotifyUri::parse("spotify:chapter:abc"), None);
+        assert_eq!(SpotifyUri::parse("spotify:user:someone:collection"), None);
+    }
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            SpotifyUri::parse("https://open.spotify.com/track/abc?si=xyz"),
+            Some(SpotifyUri::Track("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("https://open.spotify.com/intl-fr/album/abc"),
+            Some(SpotifyUri::Album("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("https://open.spotify.com/user/someone/playlist/abc?si=xyz"),
+            Some(SpotifyUri::Playlist("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("http://open.spotify.com/show/abc/"),
+            Some(SpotifyUri::Show("abc"))
+        );
+        assert_eq!(
+            SpotifyUri::parse("https://open.spotify.com/episode/abc?si=xyz"),
+            Some(SpotifyUri::Episode("abc"))
+        );
+        assert_eq!(SpotifyUri::parse("https://example.com/album/abc"), None);
+        assert_eq!(SpotifyUri::parse("https://open.spotify.com/"), None);
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let text = "https://open.spotify.com/track/abc?si=xyz\r\nspotify:album:def\n\nnot a link";
+        assert_eq!(
+            SpotifyUri::parse_all(text),
+            vec![SpotifyUri::Track("abc"), SpotifyUri::Album("def")]
+        );
+        assert!(SpotifyUri::parse_all("").is_empty());
+    }
+
+    #[test]
+    fn test_format_uris() {
+        assert_eq!(SpotifyUri::Track("abc").to_string(), "spotify:track:abc");
+        assert_eq!(
+            SpotifyUri::User("abc").link(),
+            "https://open.spotify.com/user/abc"
+        );
+        assert_eq!(
+            SpotifyUri::parse(&SpotifyUri::Show("abc").to_string()),
+            Some(SpotifyUri::Show("abc"))
+        );
+    }
+}
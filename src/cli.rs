@@ -0,0 +1,300 @@
+use gettextrs::*;
+use gio::prelude::*;
+use gio::ApplicationCommandLine;
+use glib::{OptionArg, OptionFlags, VariantDict};
+use serde_json::json;
+use std::rc::Rc;
+
+use crate::api::SpotifyApiError;
+use crate::app::models::SongDescription;
+use crate::app::state::PlaybackAction;
+use crate::app::{ActionDispatcher, AppAction, AppModel, SpotifyUri};
+
+// What can be asked of a running instance from the command line
+#[derive(Debug, PartialEq)]
+enum Command {
+    Play(String),
+    Queue(String),
+    Toggle,
+    Next,
+    Prev,
+    // position in seconds
+    Seek(u32),
+    Volume(f64),
+    NowPlaying { json: bool },
+}
+
+pub fn add_main_options(app: &gtk::Application) {
+    let options = [
+        (
+            "play",
+            OptionArg::String,
+            gettext("Play a track, album, playlist, artist, show or episode"),
+            Some("URI"),
+        ),
+        (
+            "queue",
+            OptionArg::String,
+            gettext("Add a track, album, playlist, artist, show or episode to the queue"),
+            Some("URI"),
+        ),
+        ("toggle", OptionArg::None, gettext("Play or pause"), None),
+        (
+            "next",
+            OptionArg::None,
+            gettext("Play the next track"),
+            None,
+        ),
+        (
+            "prev",
+            OptionArg::None,
+            gettext("Play the previous track"),
+            None,
+        ),
+        (
+            "seek",
+            OptionArg::Int,
+            gettext("Seek to a position in the current track"),
+            Some("SECONDS"),
+        ),
+        (
+            "volume",
+            OptionArg::Int,
+            gettext("Set the volume"),
+            Some("PERCENT"),
+        ),
+        (
+            "now-playing",
+            OptionArg::None,
+            gettext("Print the current track"),
+            None,
+        ),
+        (
+            "json",
+            OptionArg::None,
+            gettext("Print the current track as JSON"),
+            None,
+        ),
+    ];
+    for (name, arg, description, arg_description) in options.iter() {
+        app.add_main_option(
+            name,
+            glib::Char::from(0),
+            OptionFlags::NONE,
+            *arg,
+            description,
+            *arg_description,
+        );
+    }
+}
+
+fn lookup<T: glib::FromVariant>(options: &VariantDict, key: &str) -> Option<T> {
+    options.lookup(key).ok().flatten()
+}
+
+fn lookup_uri(options: &VariantDict, key: &str) -> Result<Option<String>, String> {
+    match lookup::<String>(options, key) {
+        Some(uri) if SpotifyUri::parse(&uri).is_none() => {
+            Err(format!("{}: {}", gettext("Unsupported URI"), uri))
+        }
+        uri => Ok(uri),
+    }
+}
+
+fn parse_commands(options: &VariantDict) -> Result<Vec<Command>, String> {
+    let mut commands = vec![];
+    if let Some(uri) = lookup_uri(options, "play")? {
+        commands.push(Command::Play(uri));
+    }
+    if let Some(uri) = lookup_uri(options, "queue")? {
+        commands.push(Command::Queue(uri));
+    }
+    if options.contains("toggle") {
+        commands.push(Command::Toggle);
+    }
+    if options.contains("next") {
+        commands.push(Command::Next);
+    }
+    if options.contains("prev") {
+        commands.push(Command::Prev);
+    }
+    if let Some(seconds) = lookup::<i32>(options, "seek") {
+        commands.push(Command::Seek(seconds.max(0) as u32));
+    }
+    if let Some(percent) = lookup::<i32>(options, "volume") {
+        commands.push(Command::Volume(f64::from(percent.clamp(0, 100)) / 100.0));
+    }
+    if options.contains("now-playing") {
+        let json = options.contains("json");
+        commands.push(Command::NowPlaying { json });
+    }
+    Ok(commands)
+}
+
+fn now_playing(song: Option<&SongDescription>, is_playing: bool, json: bool) -> String {
+    if !json {
+        return song
+            .map(|song| format!("{} — {}", song.title, song.artists_name()))
+            .unwrap_or_default();
+    }
+
+    let status = match (song, is_playing) {
+        (None, _) => "Stopped",
+        (Some(_), true) => "Playing",
+        (Some(_), false) => "Paused",
+    };
+    let track = song.map(|song| {
+        json!({
+            "id": song.id,
+            "uri": song.uri,
+            "url": song.link(),
+            "title": song.title,
+            "artists": song.artists.iter().map(|a| &a.name).collect::<Vec<_>>(),
+            "album": song.album.name,
+            "duration_ms": song.duration,
+        })
+    });
+    json!({ "status": status, "track": track }).to_string()
+}
+
+// Handles command lines forwarded to the primary instance, turning them into actions
+pub struct CommandLineHandler {
+    model: Rc<AppModel>,
+    dispatcher: Box<dyn ActionDispatcher>,
+}
+
+impl CommandLineHandler {
+    pub fn new(model: Rc<AppModel>, dispatcher: Box<dyn ActionDispatcher>) -> Self {
+        Self { model, dispatcher }
+    }
+
+    pub fn handle(&self, app: &gtk::Application, cmdline: &ApplicationCommandLine) -> i32 {
+        let commands = match parse_commands(&cmdline.options_dict()) {
+            Ok(commands) => commands,
+            Err(message) => {
+                cmdline.printerr(&format!("{}\n", message));
+                return 1;
+            }
+        };
+
+        // Remote controlling a running instance should not bring its window up
+        if commands.is_empty() || app.active_window().is_none() {
+            app.activate();
+        }
+
+        for arg in cmdline.arguments().iter().skip(1) {
+            self.open(&arg.to_string_lossy());
+        }
+
+        for command in commands {
+            self.run(command, cmdline);
+        }
+        0
+    }
+
+    // Opens a spotify: URI or an open.spotify.com link, tracks are played rather than viewed
+    pub fn open(&self, uri: &str) {
+        let uri = match SpotifyUri::parse(uri) {
+            Some(uri) => uri.to_string(),
+            None => {
+                self.dispatcher
+                    .dispatch(AppAction::ShowNotification(gettext("Failed to open link!")));
+                return;
+            }
+        };
+        let loader = self.model.get_batch_loader();
+        self.dispatcher
+            .call_spotify_and_dispatch_many(move || async move {
+                let uri = SpotifyUri::parse(&uri).ok_or(SpotifyApiError::NoContent)?;
+                loader.open_uri(&uri).await
+            });
+    }
+
+    fn run(&self, command: Command, cmdline: &ApplicationCommandLine) {
+        match command {
+            Command::Play(uri) => {
+                let loader = self.model.get_batch_loader();
+                self.dispatcher
+                    .call_spotify_and_dispatch_many(move || async move {
+                        let uri = SpotifyUri::parse(&uri).ok_or(SpotifyApiError::NoContent)?;
+                        let (_, actions) = loader.play_uri(&uri, None).await?;
+                        Ok(actions)
+                    });
+            }
+            Command::Queue(uri) => {
+                let loader = self.model.get_batch_loader();
+                self.dispatcher
+                    .call_spotify_and_dispatch(move || async move {
+                        let uri = SpotifyUri::parse(&uri).ok_or(SpotifyApiError::NoContent)?;
+                        let songs = loader.load_all_uri(&uri).await?;
+                        Ok(PlaybackAction::Queue(songs).into())
+                    });
+            }
+            Command::Toggle => self.dispatcher.dispatch(PlaybackAction::TogglePlay.into()),
+            Command::Next => self.dispatcher.dispatch(PlaybackAction::Next.into()),
+            Command::Prev => self.dispatcher.dispatch(PlaybackAction::Previous.into()),
+            Command::Seek(seconds) => self
+                .dispatcher
+                .dispatch(PlaybackAction::Seek(seconds.saturating_mul(1000)).into()),
+            Command::Volume(volume) => self
+                .dispatcher
+                .dispatch(PlaybackAction::SetVolume(volume).into()),
+            Command::NowPlaying { json } => {
+                let state = self.model.get_state();
+                let text = now_playing(
+                    state.playback.current_song().as_ref(),
+                    state.playback.is_playing(),
+                    json,
+                );
+                cmdline.print(&format!("{}\n", text));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glib::ToVariant;
+
+    use super::*;
+    use crate::app::testing::song;
+
+    #[test]
+    fn test_parse_commands() {
+        let options = VariantDict::new(None);
+        options.insert_value("queue", &"spotify:album:abc".to_variant());
+        options.insert_value("next", &true.to_variant());
+        options.insert_value("volume", &150.to_variant());
+        assert_eq!(
+            parse_commands(&options),
+            Ok(vec![
+                Command::Queue("spotify:album:abc".to_string()),
+                Command::Next,
+                Command::Volume(1.0)
+            ])
+        );
+
+        let options = VariantDict::new(None);
+        options.insert_value("play", &"spotify:episode:abc".to_variant());
+        assert_eq!(
+            parse_commands(&options),
+            Ok(vec![Command::Play("spotify:episode:abc".to_string())])
+        );
+
+        options.insert_value("play", &"spotify:chapter:abc".to_variant());
+        assert!(parse_commands(&options).is_err());
+    }
+
+    #[test]
+    fn test_now_playing() {
+        let song = song("1");
+        assert_eq!(now_playing(Some(&song), true, false), "Song 1 — Artist");
+        assert_eq!(now_playing(None, false, false), "");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&now_playing(Some(&song), false, true)).unwrap();
+        assert_eq!(json["status"], "Paused");
+        assert_eq!(json["track"]["uri"], "spotify:track:1");
+        assert_eq!(json["track"]["url"], "https://open.spotify.com/track/1");
+    }
+}
@@ -0,0 +1,177 @@
+#![allow(non_snake_case)]
+
+use std::sync::Arc;
+
+use futures::channel::mpsc::UnboundedSender;
+use zbus::dbus_interface;
+use zbus::fdo::{Error, Result};
+
+use super::search_provider::named_results;
+use crate::api::{SpotifyApiClient, SpotifyApiError};
+use crate::app::models::SearchCategory;
+use crate::app::{state::PlaybackAction, AppAction, BatchLoader, BrowserAction, SpotifyUri};
+
+pub const CONTROL_PATH: &str = "/dev/alextren/Spot/Control";
+
+fn failed(err: SpotifyApiError) -> Error {
+    Error::Failed(err.to_string())
+}
+
+fn unsupported(uri: &str) -> Error {
+    Error::InvalidArgs(format!("Unsupported uri {}", uri))
+}
+
+// What other programs can ask of Spot beyond what MPRIS allows, mostly for scripting
+pub struct SpotControl {
+    sender: UnboundedSender<AppAction>,
+    api: Arc<dyn SpotifyApiClient + Send + Sync>,
+    loader: BatchLoader,
+    current_track: Option<String>,
+}
+
+impl SpotControl {
+    pub fn new(
+        sender: UnboundedSender<AppAction>,
+        api: Arc<dyn SpotifyApiClient + Send + Sync>,
+    ) -> Self {
+        Self {
+            sender,
+            loader: BatchLoader::new(Arc::clone(&api)),
+            api,
+            current_track: None,
+        }
+    }
+
+    pub fn set_current_track(&mut self, id: Option<String>) {
+        self.current_track = id;
+    }
+
+    fn send(&self, action: AppAction) -> Result<()> {
+        self.sender
+            .unbounded_send(action)
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
+    }
+}
+
+#[dbus_interface(interface = "dev.alextren.Spot.Control")]
+impl SpotControl {
+    // Resolves to the number of tracks added to the queue
+    pub async fn queue(&self, Uri: &str) -> Result<u32> {
+        let uri = SpotifyUri::parse(Uri).ok_or_else(|| unsupported(Uri))?;
+        let songs = self.loader.load_all_uri(&uri).await.map_err(failed)?;
+        let count = songs.len() as u32;
+        self.send(PlaybackAction::Queue(songs).into())?;
+        Ok(count)
+    }
+
+    // Replaces the queue, and resolves to the URI of the track or episode that starts playing
+    pub async fn play(&self, Uri: &str, Shuffle: bool) -> Result<String> {
+        let uri = SpotifyUri::parse(Uri).ok_or_else(|| unsupported(Uri))?;
+        let (first, actions) = self
+            .loader
+            .play_uri(&uri, Some(Shuffle))
+            .await
+            .map_err(failed)?;
+        for action in actions {
+            self.send(action)?;
+        }
+        let first = match uri {
+            SpotifyUri::Show(_) | SpotifyUri::Episode(_) => SpotifyUri::Episode(&first),
+            _ => SpotifyUri::Track(&first),
+        };
+        Ok(first.to_string())
+    }
+
+    // Resolves to the URI of the track that was saved
+    pub async fn save_current_track(&self) -> Result<String> {
+        let id = self
+            .current_track
+            .clone()
+            .ok_or_else(|| Error::Failed("Nothing is playing".to_string()))?;
+        let song = self.api.get_track(&id).await.map_err(failed)?;
+        self.api
+            .save_tracks(vec![id.clone()])
+            .await
+            .map_err(failed)?;
+        self.send(BrowserAction::SaveTracks(vec![song]).into())?;
+        Ok(SpotifyUri::Track(&id).to_string())
+    }
+
+    // Resolves to the URI, name and description of each result, artists first
+    pub async fn search(&self, Query: &str, Limit: u32) -> Result<Vec<(String, String, String)>> {
+        let results = self
+            .api
+            .search(Query, SearchCategory::all(), 0, Limit as usize)
+            .await
+            .map_err(failed)?;
+        Ok(named_results(results)
+            .into_iter()
+            .map(|(uri, meta)| (uri, meta.name, meta.description))
+            .collect())
+    }
+
+    pub fn show_search(&self, Query: &str) -> Result<()> {
+        self.send(BrowserAction::Search(Query.to_string()).into())?;
+        self.send(AppAction::Raise)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc::unbounded;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::testing::*;
+
+    #[test]
+    fn test_queue_album() {
+        let api = FakeSpotifyClient::new().with_album(album("album0", vec![song("1"), song("2")]));
+        let (sender, mut receiver) = unbounded();
+        let control = SpotControl::new(sender, Arc::new(api));
+
+        let count = block_on(control.queue("spotify:album:album0")).unwrap();
+        assert_eq!(count, 2);
+        assert!(matches!(
+            receiver.try_next(),
+            Ok(Some(AppAction::PlaybackAction(PlaybackAction::Queue(songs)))) if songs.len() == 2
+        ));
+
+        assert!(block_on(control.queue("spotify:chapter:abc")).is_err());
+    }
+
+    #[test]
+    fn test_play_episode() {
+        let mut episode0 = episode("episode0", "show0");
+        episode0.resume_position = Some(30_000);
+        let api = FakeSpotifyClient::new().with_show(show("show0", vec![episode0]));
+        let (sender, mut receiver) = unbounded();
+        let control = SpotControl::new(sender, Arc::new(api));
+
+        let uri = block_on(control.play("spotify:episode:episode0", false)).unwrap();
+        assert_eq!(uri, "spotify:episode:episode0");
+        assert!(matches!(
+            receiver.try_next(),
+            Ok(Some(AppAction::PlaybackAction(PlaybackAction::SeedResumePositions(positions))))
+                if positions == vec![("episode0".to_string(), 30_000)]
+        ));
+    }
+
+    #[test]
+    fn test_save_current_track() {
+        let api = FakeSpotifyClient::new().with_album(album("album0", vec![song("1")]));
+        let (sender, mut receiver) = unbounded();
+        let mut control = SpotControl::new(sender, Arc::new(api));
+
+        assert!(block_on(control.save_current_track()).is_err());
+
+        control.set_current_track(Some("1".to_string()));
+        let uri = block_on(control.save_current_track()).unwrap();
+        assert_eq!(uri, "spotify:track:1");
+        assert!(matches!(
+            receiver.try_next(),
+            Ok(Some(AppAction::BrowserAction(BrowserAction::SaveTracks(songs)))) if songs[0].id == "1"
+        ));
+    }
+}
@@ -3,12 +3,12 @@ use std::rc::Rc;
 
 use crate::app::{
     components::EventListener,
-    models::SongDescription,
-    state::{PlaybackEvent, RepeatMode},
-    AppEvent, AppModel,
+    models::{PlaylistSummary, SongDescription},
+    state::{LoginEvent, PlaybackEvent, RepeatMode},
+    AppEvent, AppModel, SongsSource,
 };
 
-use super::types::{LoopStatus, PlaybackStatus, TrackMetadata};
+use super::types::{track_path, LoopStatus, PlaybackStatus, TrackMetadata};
 
 #[derive(Debug)]
 pub enum MprisStateUpdate {
@@ -19,6 +19,7 @@ pub enum MprisStateUpdate {
         has_next: bool,
     },
     SetPositionMs(u128),
+    Seeked(u128),
     SetLoopStatus {
         has_prev: bool,
         loop_status: LoopStatus,
@@ -26,6 +27,41 @@ pub enum MprisStateUpdate {
     },
     SetShuffled(bool),
     SetPlaying(PlaybackStatus),
+    // A snapshot of the whole queue, which the TrackList interface diffs against the last one
+    SetTrackList {
+        tracks: Vec<TrackMetadata>,
+        current: Option<String>,
+    },
+    SetPlaylists(Vec<PlaylistSummary>),
+    SetActivePlaylist(Option<String>),
+}
+
+fn track_meta(song: SongDescription) -> TrackMetadata {
+    let url = Some(song.link());
+    let SongDescription {
+        id,
+        track_number,
+        disc_number,
+        title,
+        artists,
+        album,
+        album_artists,
+        duration,
+        art,
+        ..
+    } = song;
+    TrackMetadata {
+        id: track_path(&id),
+        length: 1000 * duration as u64,
+        title,
+        album: album.name,
+        artist: artists.into_iter().map(|a| a.name).collect(),
+        album_artist: album_artists.into_iter().map(|a| a.name).collect(),
+        track_number,
+        disc_number,
+        art,
+        url,
+    }
 }
 
 pub struct AppPlaybackStateListener {
@@ -39,23 +75,21 @@ impl AppPlaybackStateListener {
     }
 
     fn make_track_meta(&self) -> Option<TrackMetadata> {
-        let SongDescription {
-            id,
-            title,
-            artists,
-            album,
-            duration,
-            art,
-            ..
-        } = self.app_model.get_state().playback.current_song()?;
-        Some(TrackMetadata {
-            id: format!("/dev/alextren/Spot/Track/{}", id),
-            length: 1000 * duration as u64,
-            title,
-            album: album.name,
-            artist: artists.into_iter().map(|a| a.name).collect(),
-            art,
-        })
+        let song = self.app_model.get_state().playback.current_song()?;
+        Some(track_meta(song))
+    }
+
+    fn make_track_list(&self) -> (Vec<TrackMetadata>, Option<String>) {
+        let state = self.app_model.get_state();
+        let tracks = state
+            .playback
+            .songs()
+            .collect()
+            .into_iter()
+            .map(track_meta)
+            .collect();
+        let current = state.playback.current_song_id().map(|id| track_path(&id));
+        (tracks, current)
     }
 
     fn has_prev_next(&self) -> (bool, bool) {
@@ -80,6 +114,32 @@ impl AppPlaybackStateListener {
         }
     }
 
+    fn active_playlist(&self) -> Option<String> {
+        let state = self.app_model.get_state();
+        match state.playback.source()? {
+            SongsSource::Playlist(id) => Some(id.clone()),
+            _ => None,
+        }
+    }
+
+    fn updates_for(&self, event: &AppEvent) -> Vec<MprisStateUpdate> {
+        match event {
+            AppEvent::PlaybackEvent(PlaybackEvent::PlaylistChanged) => {
+                let (tracks, current) = self.make_track_list();
+                vec![
+                    MprisStateUpdate::SetTrackList { tracks, current },
+                    MprisStateUpdate::SetActivePlaylist(self.active_playlist()),
+                ]
+            }
+            AppEvent::PlaybackEvent(event) => self.update_for(event).into_iter().collect(),
+            AppEvent::LoginEvent(LoginEvent::UserPlaylistsLoaded) => {
+                let playlists = self.app_model.get_state().logged_user.playlists.clone();
+                vec![MprisStateUpdate::SetPlaylists(playlists)]
+            }
+            _ => vec![],
+        }
+    }
+
     fn update_for(&self, event: &PlaybackEvent) -> Option<MprisStateUpdate> {
         match event {
             PlaybackEvent::PlaybackPaused => {
@@ -112,7 +172,12 @@ impl AppPlaybackStateListener {
             PlaybackEvent::ShuffleChanged => {
                 Some(MprisStateUpdate::SetShuffled(self.is_shuffled()))
             }
-            PlaybackEvent::TrackSeeked(pos) | PlaybackEvent::SeekSynced(pos) => {
+            // Only seeks requested by the user are signaled, syncs merely correct drift
+            PlaybackEvent::TrackSeeked(pos) => {
+                let pos = 1000 * (*pos as u128);
+                Some(MprisStateUpdate::Seeked(pos))
+            }
+            PlaybackEvent::SeekSynced(pos) => {
                 let pos = 1000 * (*pos as u128);
                 Some(MprisStateUpdate::SetPositionMs(pos))
             }
@@ -124,12 +189,10 @@ impl AppPlaybackStateListener {
 
 impl EventListener for AppPlaybackStateListener {
     fn on_event(&mut self, event: &AppEvent) {
-        if let AppEvent::PlaybackEvent(event) = event {
-            if let Some(update) = self.update_for(event) {
-                self.sender
-                    .unbounded_send(update)
-                    .expect("Could not send event to DBUS server");
-            }
+        for update in self.updates_for(event) {
+            self.sender
+                .unbounded_send(update)
+                .expect("Could not send event to DBUS server");
         }
     }
 }
@@ -4,20 +4,79 @@ use std::rc::Rc;
 use std::thread;
 use zbus::Connection;
 
+use crate::app::models::PlaylistSummary;
 use crate::app::{AppAction, AppModel};
 
 mod mpris;
 pub use mpris::*;
 
+mod control;
+use control::*;
+
+mod search_provider;
+use search_provider::*;
+
 mod types;
+use types::{track_id, TrackMetadata};
 
 mod listener;
 use listener::*;
 
+async fn update_track_list(
+    connection: &Connection,
+    tracks: Vec<TrackMetadata>,
+    current: Option<String>,
+) -> zbus::Result<()> {
+    let tracklist_ref = connection
+        .object_server()
+        .interface::<_, SpotMprisTrackList>("/org/mpris/MediaPlayer2")
+        .await?;
+    let mut tracklist = tracklist_ref.get_mut().await;
+    tracklist
+        .set_tracks(tracks, current, tracklist_ref.signal_context())
+        .await
+}
+
+async fn update_playlists(
+    connection: &Connection,
+    playlists: Option<Vec<PlaylistSummary>>,
+    active: Option<Option<String>>,
+) -> zbus::Result<()> {
+    let playlists_ref = connection
+        .object_server()
+        .interface::<_, SpotMprisPlaylists>("/org/mpris/MediaPlayer2")
+        .await?;
+    let mut mpris_playlists = playlists_ref.get_mut().await;
+    let ctxt = playlists_ref.signal_context();
+    if let Some(playlists) = playlists {
+        mpris_playlists.set_playlists(playlists, ctxt).await?;
+    }
+    if let Some(active) = active {
+        mpris_playlists.set_active_playlist(active, ctxt).await?;
+    }
+    Ok(())
+}
+
+async fn update_control(
+    connection: &Connection,
+    current_track: Option<String>,
+) -> zbus::Result<()> {
+    let control_ref = connection
+        .object_server()
+        .interface::<_, SpotControl>(CONTROL_PATH)
+        .await?;
+    control_ref.get_mut().await.set_current_track(current_track);
+    Ok(())
+}
+
 #[tokio::main]
 async fn dbus_server(
     mpris: SpotMpris,
     player: SpotMprisPlayer,
+    tracklist: SpotMprisTrackList,
+    playlists: SpotMprisPlaylists,
+    search_provider: SpotSearchProvider,
+    control: SpotControl,
     receiver: UnboundedReceiver<MprisStateUpdate>,
 ) -> zbus::Result<()> {
     let connection = Connection::session().await?;
@@ -29,10 +88,29 @@ async fn dbus_server(
         .object_server()
         .at("/org/mpris/MediaPlayer2", player)
         .await?;
+    connection
+        .object_server()
+        .at("/org/mpris/MediaPlayer2", tracklist)
+        .await?;
+    connection
+        .object_server()
+        .at("/org/mpris/MediaPlayer2", playlists)
+        .await?;
     connection
         .request_name("org.mpris.MediaPlayer2.Spot")
         .await?;
 
+    connection.object_server().at(CONTROL_PATH, control).await?;
+
+    connection
+        .object_server()
+        .at(SEARCH_PROVIDER_PATH, search_provider)
+        .await?;
+    // Not being able to provide search results should not prevent MPRIS from working
+    if let Err(err) = connection.request_name(SEARCH_PROVIDER_NAME).await {
+        warn!("Could not register search provider: {:?}", err);
+    }
+
     receiver
         .for_each(|update| async {
             if let Ok(player_ref) = connection
@@ -52,15 +130,24 @@ async fn dbus_server(
                         has_next,
                         current,
                     } => {
+                        let current_id = current
+                            .as_ref()
+                            .and_then(|track| track_id(&track.id))
+                            .map(String::from);
                         player.state_mut().set_has_prev(has_prev);
                         player.state_mut().set_has_next(has_next);
                         player.state_mut().set_current_track(current);
-                        player.notify_current_track_changed(ctxt).await
+                        let res = player.notify_current_track_changed(ctxt).await;
+                        res.and(update_control(&connection, current_id).await)
                     }
                     MprisStateUpdate::SetPositionMs(position) => {
                         player.state_mut().set_position(position);
                         Ok(())
                     }
+                    MprisStateUpdate::Seeked(position) => {
+                        player.state_mut().set_position(position);
+                        SpotMprisPlayer::seeked(ctxt, position as i64).await
+                    }
                     MprisStateUpdate::SetLoopStatus {
                         has_prev,
                         has_next,
@@ -79,6 +166,15 @@ async fn dbus_server(
                         player.state_mut().set_playing(status);
                         player.playback_status_changed(ctxt).await
                     }
+                    MprisStateUpdate::SetTrackList { tracks, current } => {
+                        update_track_list(&connection, tracks, current).await
+                    }
+                    MprisStateUpdate::SetPlaylists(playlists) => {
+                        update_playlists(&connection, Some(playlists), None).await
+                    }
+                    MprisStateUpdate::SetActivePlaylist(active) => {
+                        update_playlists(&connection, None, Some(active)).await
+                    }
                 };
                 res.expect("Signal emission failed");
             }
@@ -93,11 +189,26 @@ pub fn start_dbus_server(
     sender: UnboundedSender<AppAction>,
 ) -> AppPlaybackStateListener {
     let mpris = SpotMpris::new(sender.clone());
-    let player = SpotMprisPlayer::new(sender);
+    let volume = app_model.get_state().playback.volume();
+    let player = SpotMprisPlayer::new(sender.clone(), app_model.get_batch_loader(), volume);
+    let tracklist = SpotMprisTrackList::new(sender.clone(), app_model.get_spotify());
+    let playlists = SpotMprisPlaylists::new(sender.clone(), app_model.get_batch_loader());
+    let search_provider = SpotSearchProvider::new(sender.clone(), app_model.get_spotify());
+    let control = SpotControl::new(sender, app_model.get_spotify());
 
     let (sender, receiver) = unbounded();
 
-    thread::spawn(move || dbus_server(mpris, player, receiver));
+    thread::spawn(move || {
+        dbus_server(
+            mpris,
+            player,
+            tracklist,
+            playlists,
+            search_provider,
+            control,
+            receiver,
+        )
+    });
 
     AppPlaybackStateListener::new(app_model, sender)
 }
@@ -2,16 +2,21 @@
 #![allow(unused_variables)]
 
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
 
 use futures::channel::mpsc::UnboundedSender;
 use zbus::fdo::{Error, Result};
 use zbus::{dbus_interface, Interface, SignalContext};
-use zvariant::{ObjectPath, Value};
+use zvariant::{ObjectPath, OwnedObjectPath, Value};
 
 use super::types::*;
+use crate::api::SpotifyApiClient;
+use crate::app::models::{Batch, PlaylistSummary};
 use crate::app::state::RepeatMode;
-use crate::app::{state::PlaybackAction, AppAction};
+use crate::app::{
+    state::PlaybackAction, AppAction, BatchLoader, BatchQuery, SongsSource, SpotifyUri,
+};
 
 #[derive(Clone)]
 pub struct SpotMpris {
@@ -48,7 +53,7 @@ impl SpotMpris {
 
     #[dbus_interface(property)]
     fn has_track_list(&self) -> bool {
-        false
+        true
     }
 
     #[dbus_interface(property)]
@@ -63,7 +68,8 @@ impl SpotMpris {
 
     #[dbus_interface(property)]
     fn supported_uri_schemes(&self) -> Vec<String> {
-        vec![]
+        // https is only understood for open.spotify.com links
+        vec!["spotify".to_string(), "https".to_string()]
     }
 
     #[dbus_interface(property)]
@@ -75,13 +81,15 @@ impl SpotMpris {
 pub struct SpotMprisPlayer {
     state: MprisState,
     sender: UnboundedSender<AppAction>,
+    loader: BatchLoader,
 }
 
 impl SpotMprisPlayer {
-    pub fn new(sender: UnboundedSender<AppAction>) -> Self {
+    pub fn new(sender: UnboundedSender<AppAction>, loader: BatchLoader, volume: f64) -> Self {
         Self {
-            state: MprisState::new(),
+            state: MprisState::new(volume),
             sender,
+            loader,
         }
     }
 
@@ -134,8 +142,20 @@ impl SpotMprisPlayer {
             .map_err(|_| Error::Failed("Could not send action".to_string()))
     }
 
-    pub fn open_uri(&self, Uri: &str) -> Result<()> {
-        Err(Error::NotSupported("Not implemented".to_string()))
+    pub async fn open_uri(&self, Uri: &str) -> Result<()> {
+        let uri = SpotifyUri::parse(Uri)
+            .ok_or_else(|| Error::InvalidArgs(format!("Unsupported uri {}", Uri)))?;
+        let actions = self
+            .loader
+            .open_uri(&uri)
+            .await
+            .map_err(|_| Error::Failed("Could not open uri".to_string()))?;
+        for action in actions {
+            self.sender
+                .unbounded_send(action)
+                .map_err(|_| Error::Failed("Could not send action".to_string()))?;
+        }
+        Ok(())
     }
 
     pub fn pause(&self) -> Result<()> {
@@ -219,7 +239,9 @@ impl SpotMprisPlayer {
     }
 
     pub fn stop(&self) -> Result<()> {
-        Err(Error::NotSupported("Not implemented".to_string()))
+        self.sender
+            .unbounded_send(PlaybackAction::Stop.into())
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
     }
 
     #[dbus_interface(signal)]
@@ -271,7 +293,11 @@ impl SpotMprisPlayer {
                 title: "Not playing".to_string(),
                 artist: vec![],
                 album: String::new(),
+                album_artist: vec![],
+                track_number: None,
+                disc_number: None,
                 art: None,
+                url: None,
             })
     }
 
@@ -324,7 +350,7 @@ impl SpotMprisPlayer {
     #[dbus_interface(property)]
     pub fn set_shuffle(&self, value: bool) -> zbus::Result<()> {
         self.sender
-            .unbounded_send(PlaybackAction::ToggleShuffle.into())
+            .unbounded_send(PlaybackAction::SetShuffled(value).into())
             .map_err(|_| Error::Failed("Could not send action".to_string()))?;
         Ok(())
     }
@@ -340,8 +366,284 @@ impl SpotMprisPlayer {
         // also, we don't support volume higher than 100% at the moment.
         let volume = value.clamp(0.0, 1.0);
         self.sender
-            .unbounded_send(PlaybackAction::SetVolume(value).into())
+            .unbounded_send(PlaybackAction::SetVolume(volume).into())
+            .map_err(|_| Error::Failed("Could not send action".to_string()))?;
+        Ok(())
+    }
+}
+
+pub struct SpotMprisTrackList {
+    state: TrackListState,
+    sender: UnboundedSender<AppAction>,
+    api: Arc<dyn SpotifyApiClient + Send + Sync>,
+}
+
+impl SpotMprisTrackList {
+    pub fn new(
+        sender: UnboundedSender<AppAction>,
+        api: Arc<dyn SpotifyApiClient + Send + Sync>,
+    ) -> Self {
+        Self {
+            state: TrackListState::new(),
+            sender,
+            api,
+        }
+    }
+
+    fn track_paths(&self) -> Vec<OwnedObjectPath> {
+        self.state
+            .tracks()
+            .iter()
+            .filter_map(|t| OwnedObjectPath::try_from(t.id.clone()).ok())
+            .collect()
+    }
+
+    fn queued_id<'a>(&self, path: &'a ObjectPath<'_>) -> Result<&'a str> {
+        track_id(path.as_str())
+            .filter(|_| self.state.get(path.as_str()).is_some())
+            .ok_or_else(|| Error::InvalidArgs(format!("Unknown track {}", path)))
+    }
+
+    pub async fn set_tracks(
+        &mut self,
+        tracks: Vec<TrackMetadata>,
+        current: Option<String>,
+        ctxt: &SignalContext<'_>,
+    ) -> zbus::Result<()> {
+        match self.state.set_tracks(tracks, current) {
+            TrackListChange::Unchanged => Ok(()),
+            TrackListChange::Added(index) => {
+                let tracks = self.state.tracks();
+                let after = index
+                    .checked_sub(1)
+                    .map(|i| tracks[i].id.as_str())
+                    .unwrap_or(NO_TRACK);
+                Self::track_added(ctxt, tracks[index].clone(), ObjectPath::try_from(after)?).await
+            }
+            TrackListChange::Removed(id) => {
+                Self::track_removed(ctxt, ObjectPath::try_from(id.as_str())?).await
+            }
+            TrackListChange::Replaced => {
+                let current = self.state.current().unwrap_or(NO_TRACK);
+                Self::track_list_replaced(ctxt, self.track_paths(), ObjectPath::try_from(current)?)
+                    .await
+            }
+        }
+    }
+}
+
+#[dbus_interface(interface = "org.mpris.MediaPlayer2.TrackList")]
+impl SpotMprisTrackList {
+    pub fn get_tracks_metadata(&self, TrackIds: Vec<ObjectPath<'_>>) -> Vec<TrackMetadata> {
+        TrackIds
+            .iter()
+            .filter_map(|id| self.state.get(id.as_str()).cloned())
+            .collect()
+    }
+
+    // The queue only grows at its end, so AfterTrack is not honored
+    pub async fn add_track(
+        &self,
+        Uri: &str,
+        AfterTrack: ObjectPath<'_>,
+        SetAsCurrent: bool,
+    ) -> Result<()> {
+        let id = match SpotifyUri::parse(Uri) {
+            Some(SpotifyUri::Track(id)) => id.to_string(),
+            _ => return Err(Error::InvalidArgs(format!("Unsupported uri {}", Uri))),
+        };
+        let song = self
+            .api
+            .get_track(&id)
+            .await
+            .map_err(|_| Error::Failed("Could not fetch track".to_string()))?;
+
+        self.sender
+            .unbounded_send(PlaybackAction::Queue(vec![song]).into())
             .map_err(|_| Error::Failed("Could not send action".to_string()))?;
+        if SetAsCurrent {
+            self.sender
+                .unbounded_send(PlaybackAction::Load(id).into())
+                .map_err(|_| Error::Failed("Could not send action".to_string()))?;
+        }
+        Ok(())
+    }
+
+    pub fn go_to(&self, TrackId: ObjectPath<'_>) -> Result<()> {
+        let id = self.queued_id(&TrackId)?;
+        self.sender
+            .unbounded_send(PlaybackAction::Load(id.to_string()).into())
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
+    }
+
+    pub fn remove_track(&self, TrackId: ObjectPath<'_>) -> Result<()> {
+        let id = self.queued_id(&TrackId)?;
+        self.sender
+            .unbounded_send(PlaybackAction::Dequeue(id.to_string()).into())
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
+    }
+
+    #[dbus_interface(signal)]
+    pub async fn track_list_replaced(
+        ctxt: &SignalContext<'_>,
+        Tracks: Vec<OwnedObjectPath>,
+        CurrentTrack: ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    #[dbus_interface(signal)]
+    pub async fn track_added(
+        ctxt: &SignalContext<'_>,
+        Metadata: TrackMetadata,
+        AfterTrack: ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    #[dbus_interface(signal)]
+    pub async fn track_removed(
+        ctxt: &SignalContext<'_>,
+        TrackId: ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    #[dbus_interface(property)]
+    pub fn tracks(&self) -> Vec<OwnedObjectPath> {
+        self.track_paths()
+    }
+
+    #[dbus_interface(property)]
+    pub fn can_edit_tracks(&self) -> bool {
+        true
+    }
+}
+
+pub struct SpotMprisPlaylists {
+    playlists: Vec<PlaylistSummary>,
+    // The id of the playlist being played, if any
+    active: Option<String>,
+    sender: UnboundedSender<AppAction>,
+    loader: BatchLoader,
+}
+
+impl SpotMprisPlaylists {
+    pub fn new(sender: UnboundedSender<AppAction>, loader: BatchLoader) -> Self {
+        Self {
+            playlists: vec![],
+            active: None,
+            sender,
+            loader,
+        }
+    }
+
+    pub async fn set_playlists(
+        &mut self,
+        playlists: Vec<PlaylistSummary>,
+        ctxt: &SignalContext<'_>,
+    ) -> zbus::Result<()> {
+        self.playlists = playlists;
+        self.playlist_count_changed(ctxt).await?;
+        // Its name might have changed
+        self.active_playlist_changed(ctxt).await
+    }
+
+    pub async fn set_active_playlist(
+        &mut self,
+        active: Option<String>,
+        ctxt: &SignalContext<'_>,
+    ) -> zbus::Result<()> {
+        if self.active == active {
+            return Ok(());
+        }
+        self.active = active;
+        self.active_playlist_changed(ctxt).await
+    }
+
+    fn send(&self, action: AppAction) -> Result<()> {
+        self.sender
+            .unbounded_send(action)
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
+    }
+}
+
+#[dbus_interface(interface = "org.mpris.MediaPlayer2.Playlists")]
+impl SpotMprisPlaylists {
+    pub async fn activate_playlist(&self, PlaylistId: ObjectPath<'_>) -> Result<()> {
+        let id = playlist_id(PlaylistId.as_str())
+            .ok_or_else(|| Error::InvalidArgs(format!("Unknown playlist {}", PlaylistId)))?;
+
+        let source = SongsSource::Playlist(id.to_string());
+        let query = BatchQuery {
+            source: source.clone(),
+            batch: Batch::first_of_size(50),
+        };
+        let mut first_song = None;
+        let action = self
+            .loader
+            .query(query, |batch| {
+                first_song = batch.songs.first().map(|s| s.id.clone());
+                PlaybackAction::LoadPagedSongs(source, batch).into()
+            })
+            .await;
+
+        self.send(action)?;
+        if let Some(id) = first_song {
+            self.send(PlaybackAction::Load(id).into())?;
+        }
         Ok(())
     }
+
+    // Only the order the user gave their playlists is supported
+    pub fn get_playlists(
+        &self,
+        Index: u32,
+        MaxCount: u32,
+        Order: &str,
+        ReverseOrder: bool,
+    ) -> Vec<Playlist> {
+        let mut playlists: Vec<Playlist> = self
+            .playlists
+            .iter()
+            .filter_map(|p| make_playlist(&p.id, &p.title))
+            .collect();
+        if ReverseOrder {
+            playlists.reverse();
+        }
+        playlists
+            .into_iter()
+            .skip(Index as usize)
+            .take(MaxCount as usize)
+            .collect()
+    }
+
+    #[dbus_interface(property)]
+    pub fn playlist_count(&self) -> u32 {
+        self.playlists.len() as u32
+    }
+
+    #[dbus_interface(property)]
+    pub fn orderings(&self) -> Vec<String> {
+        vec!["UserDefined".to_string()]
+    }
+
+    #[dbus_interface(property)]
+    pub fn active_playlist(&self) -> (bool, Playlist) {
+        let active = self.active.as_ref().and_then(|id| {
+            // Playlists of other users are not listed, but can still be played
+            let name = self
+                .playlists
+                .iter()
+                .find(|p| &p.id == id)
+                .map(|p| &p.title[..])
+                .unwrap_or("");
+            make_playlist(id, name)
+        });
+        match active {
+            Some(playlist) => (true, playlist),
+            None => (
+                false,
+                (
+                    OwnedObjectPath::try_from("/").unwrap(),
+                    String::new(),
+                    String::new(),
+                ),
+            ),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+#![allow(non_snake_case)]
+#![allow(unused_variables)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::channel::mpsc::UnboundedSender;
+use zbus::dbus_interface;
+use zbus::fdo::{Error, Result};
+use zvariant::{OwnedValue, Value};
+
+use crate::api::SpotifyApiClient;
+use crate::app::models::{SearchCategory, SearchResults};
+use crate::app::{state::PlaybackAction, AppAction, BrowserAction, SpotifyUri};
+
+pub const SEARCH_PROVIDER_NAME: &str = "dev.alextren.Spot.SearchProvider";
+pub const SEARCH_PROVIDER_PATH: &str = "/dev/alextren/Spot/SearchProvider";
+
+// Results per category, the shell only shows a handful of them anyway
+const RESULTS_PER_CATEGORY: usize = 5;
+
+#[derive(Clone, Debug)]
+pub struct ResultMeta {
+    pub name: String,
+    pub description: String,
+}
+
+impl ResultMeta {
+    fn new(name: String, description: String) -> Self {
+        Self { name, description }
+    }
+
+    fn into_dict(self, id: &str) -> HashMap<String, OwnedValue> {
+        let mut dict = HashMap::new();
+        dict.insert("id".to_string(), Value::from(id).into());
+        dict.insert("name".to_string(), Value::from(self.name).into());
+        if !self.description.is_empty() {
+            dict.insert(
+                "description".to_string(),
+                Value::from(self.description).into(),
+            );
+        }
+        dict
+    }
+}
+
+// Results are identified by their Spotify URI, artists first
+pub fn named_results(results: SearchResults) -> Vec<(String, ResultMeta)> {
+    let artists = results.artists.into_iter().map(|artist| {
+        let meta = ResultMeta::new(artist.name, String::new());
+        (SpotifyUri::Artist(&artist.id).to_string(), meta)
+    });
+    let albums = results.albums.into_iter().map(|album| {
+        let meta = ResultMeta::new(album.title.clone(), album.artists_name());
+        (SpotifyUri::Album(&album.id).to_string(), meta)
+    });
+    let playlists = results.playlists.into_iter().map(|playlist| {
+        let meta = ResultMeta::new(playlist.title, playlist.owner.display_name);
+        (SpotifyUri::Playlist(&playlist.id).to_string(), meta)
+    });
+    let songs = results.songs.into_iter().map(|song| {
+        let description = format!("{} — {}", song.artists_name(), song.album.name);
+        let meta = ResultMeta::new(song.title, description);
+        (SpotifyUri::Track(&song.id).to_string(), meta)
+    });
+
+    artists
+        .chain(albums)
+        .chain(playlists)
+        .chain(songs)
+        .collect()
+}
+
+pub struct SpotSearchProvider {
+    sender: UnboundedSender<AppAction>,
+    api: Arc<dyn SpotifyApiClient + Send + Sync>,
+    // Metas of the last results, so that the shell does not have to wait on the network to display them
+    known: HashMap<String, ResultMeta>,
+}
+
+impl SpotSearchProvider {
+    pub fn new(
+        sender: UnboundedSender<AppAction>,
+        api: Arc<dyn SpotifyApiClient + Send + Sync>,
+    ) -> Self {
+        Self {
+            sender,
+            api,
+            known: HashMap::new(),
+        }
+    }
+
+    async fn search(&mut self, terms: Vec<String>) -> Vec<String> {
+        let query = terms.join(" ");
+        if query.trim().is_empty() {
+            return vec![];
+        }
+
+        let categories = vec![
+            SearchCategory::Artists,
+            SearchCategory::Albums,
+            SearchCategory::Playlists,
+            SearchCategory::Songs,
+        ];
+        let results = match self
+            .api
+            .search(&query, categories, 0, RESULTS_PER_CATEGORY)
+            .await
+        {
+            Ok(results) => results,
+            Err(err) => {
+                warn!("Search provider query failed: {:?}", err);
+                return vec![];
+            }
+        };
+
+        let results = named_results(results);
+        let ids = results.iter().map(|(id, _)| id.clone()).collect();
+        self.known = results.into_iter().collect();
+        ids
+    }
+
+    // For results the shell remembers from an earlier search, we go through the cache instead
+    async fn fetch_meta(&self, id: SpotifyUri<'_>) -> Option<ResultMeta> {
+        match id {
+            SpotifyUri::Artist(id) => {
+                let artist = self.api.get_artist(id).await.ok()?;
+                Some(ResultMeta::new(artist.name, String::new()))
+            }
+            SpotifyUri::Album(id) => {
+                let album = self.api.get_album(id).await.ok()?.description;
+                Some(ResultMeta::new(album.title.clone(), album.artists_name()))
+            }
+            SpotifyUri::Playlist(id) => {
+                let playlist = self.api.get_playlist(id).await.ok()?;
+                Some(ResultMeta::new(playlist.title, playlist.owner.display_name))
+            }
+            SpotifyUri::Track(id) => {
+                let song = self.api.get_track(id).await.ok()?;
+                let description = format!("{} — {}", song.artists_name(), song.album.name);
+                Some(ResultMeta::new(song.title, description))
+            }
+            // Never part of the results
+            SpotifyUri::User(_) | SpotifyUri::Show(_) | SpotifyUri::Episode(_) => None,
+        }
+    }
+
+    fn send(&self, action: AppAction) -> Result<()> {
+        self.sender
+            .unbounded_send(action)
+            .map_err(|_| Error::Failed("Could not send action".to_string()))
+    }
+}
+
+#[dbus_interface(interface = "org.gnome.Shell.SearchProvider2")]
+impl SpotSearchProvider {
+    pub async fn get_initial_result_set(&mut self, Terms: Vec<String>) -> Vec<String> {
+        self.search(Terms).await
+    }
+
+    // Searching again rather than filtering, as the API does not match terms the way we would
+    pub async fn get_subsearch_result_set(
+        &mut self,
+        PreviousResults: Vec<String>,
+        Terms: Vec<String>,
+    ) -> Vec<String> {
+        self.search(Terms).await
+    }
+
+    pub async fn get_result_metas(
+        &self,
+        Identifiers: Vec<String>,
+    ) -> Vec<HashMap<String, OwnedValue>> {
+        let mut metas = vec![];
+        for identifier in Identifiers.iter() {
+            let meta = match self.known.get(identifier) {
+                Some(meta) => Some(meta.clone()),
+                None => match SpotifyUri::parse(identifier) {
+                    Some(id) => self.fetch_meta(id).await,
+                    None => None,
+                },
+            };
+            if let Some(meta) = meta {
+                metas.push(meta.into_dict(identifier));
+            }
+        }
+        metas
+    }
+
+    pub async fn activate_result(
+        &self,
+        Identifier: &str,
+        Terms: Vec<String>,
+        Timestamp: u32,
+    ) -> Result<()> {
+        let uri = SpotifyUri::parse(Identifier)
+            .ok_or_else(|| Error::InvalidArgs(format!("Unknown result {}", Identifier)))?;
+
+        if let SpotifyUri::Track(id) = uri {
+            let song = self
+                .api
+                .get_track(id)
+                .await
+                .map_err(|_| Error::Failed("Could not fetch track".to_string()))?;
+            self.send(PlaybackAction::Queue(vec![song]).into())?;
+            self.send(PlaybackAction::Load(id.to_string()).into())?;
+        } else if let Some(action) = AppAction::View(&uri) {
+            self.send(action)?;
+        }
+        self.send(AppAction::Raise)
+    }
+
+    pub fn launch_search(&self, Terms: Vec<String>, Timestamp: u32) -> Result<()> {
+        self.send(BrowserAction::Search(Terms.join(" ")).into())?;
+        self.send(AppAction::Raise)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc::unbounded;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::api::FakeSpotifyClient;
+    use crate::app::state::ScreenName;
+    use crate::app::testing::*;
+
+    #[test]
+    fn test_search_and_activate() {
+        let api = FakeSpotifyClient::new().with_album(album("rock", vec![song("song0")]));
+        let (sender, mut receiver) = unbounded();
+        let mut provider = SpotSearchProvider::new(sender, Arc::new(api));
+
+        let results = block_on(provider.get_initial_result_set(vec!["rock".to_string()]));
+        assert_eq!(results, vec!["spotify:album:rock".to_string()]);
+
+        let metas = block_on(provider.get_result_metas(results));
+        assert_eq!(metas.len(), 1);
+        assert_eq!(
+            metas[0].get("name").cloned(),
+            Some(Value::from("Album rock").into())
+        );
+
+        block_on(provider.activate_result("spotify:album:rock", vec![], 0)).unwrap();
+        assert!(matches!(
+            receiver.try_next(),
+            Ok(Some(AppAction::BrowserAction(BrowserAction::NavigationPush(
+                ScreenName::AlbumDetails(id)
+            )))) if id == "rock"
+        ));
+        assert!(matches!(receiver.try_next(), Ok(Some(AppAction::Raise))));
+    }
+}
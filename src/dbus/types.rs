@@ -1,7 +1,40 @@
+use serde::{Serialize, Serializer};
 use std::convert::{Into, TryFrom};
 use std::time::Instant;
 use zvariant::Type;
-use zvariant::{Dict, Signature, Str, Value};
+use zvariant::{Dict, OwnedObjectPath, Signature, Str, Value};
+
+const TRACK_PATH_PREFIX: &str = "/dev/alextren/Spot/Track/";
+const PLAYLIST_PATH_PREFIX: &str = "/dev/alextren/Spot/Playlist/";
+
+// As per spec, the path standing for "no track" in the TrackList interface
+pub const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+pub fn track_path(id: &str) -> String {
+    format!("{}{}", TRACK_PATH_PREFIX, id)
+}
+
+pub fn track_id(path: &str) -> Option<&str> {
+    path.strip_prefix(TRACK_PATH_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
+pub fn playlist_path(id: &str) -> String {
+    format!("{}{}", PLAYLIST_PATH_PREFIX, id)
+}
+
+pub fn playlist_id(path: &str) -> Option<&str> {
+    path.strip_prefix(PLAYLIST_PATH_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
+// A playlist as the Playlists interface describes it: an object path, a name and an icon
+pub type Playlist = (OwnedObjectPath, String, String);
+
+pub fn make_playlist(id: &str, name: &str) -> Option<Playlist> {
+    let path = OwnedObjectPath::try_from(playlist_path(id)).ok()?;
+    Some((path, name.to_string(), String::new()))
+}
 
 fn boxed_value<'a, V: Into<Value<'a>>>(v: V) -> Value<'a> {
     Value::new(v.into())
@@ -110,8 +143,12 @@ pub struct TrackMetadata {
     pub length: u64,
     pub artist: Vec<String>,
     pub album: String,
+    pub album_artist: Vec<String>,
     pub title: String,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
     pub art: Option<String>,
+    pub url: Option<String>,
 }
 
 impl Type for TrackMetadata {
@@ -120,8 +157,9 @@ impl Type for TrackMetadata {
     }
 }
 
-impl From<TrackMetadata> for Value<'_> {
-    fn from(meta: TrackMetadata) -> Self {
+impl TrackMetadata {
+    fn into_dict(self) -> Dict<'static, 'static> {
+        let meta = self;
         let mut d = Dict::new(Str::signature(), Value::signature());
         d.append("mpris:trackid".into(), boxed_value(meta.id))
             .unwrap();
@@ -129,16 +167,116 @@ impl From<TrackMetadata> for Value<'_> {
             .unwrap();
         d.append("xesam:title".into(), boxed_value(meta.title))
             .unwrap();
-        d.append("xesam:artist".into(), boxed_value(meta.artist.clone()))
+        d.append("xesam:artist".into(), boxed_value(meta.artist))
             .unwrap();
-        d.append("xesam:albumArtist".into(), boxed_value(meta.artist))
+        d.append("xesam:albumArtist".into(), boxed_value(meta.album_artist))
             .unwrap();
         d.append("xesam:album".into(), boxed_value(meta.album))
             .unwrap();
+        // The spec types these as 32 bits integers
+        if let Some(track_number) = meta.track_number {
+            d.append("xesam:trackNumber".into(), boxed_value(track_number as i32))
+                .unwrap();
+        }
+        if let Some(disc_number) = meta.disc_number {
+            d.append("xesam:discNumber".into(), boxed_value(disc_number as i32))
+                .unwrap();
+        }
         if let Some(art) = meta.art {
             d.append("mpris:artUrl".into(), boxed_value(art)).unwrap();
         }
-        Value::Dict(d)
+        if let Some(url) = meta.url {
+            d.append("xesam:url".into(), boxed_value(url)).unwrap();
+        }
+        d
+    }
+}
+
+impl From<TrackMetadata> for Value<'_> {
+    fn from(meta: TrackMetadata) -> Self {
+        Value::Dict(meta.into_dict())
+    }
+}
+
+// Needed to pass metadata as a method result or signal argument, not just as a property
+impl Serialize for TrackMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.clone().into_dict().serialize(serializer)
+    }
+}
+
+// How the track list went from one snapshot to the next, in terms of TrackList signals
+#[derive(Debug, PartialEq, Eq)]
+pub enum TrackListChange {
+    Unchanged,
+    Added(usize),
+    Removed(String),
+    Replaced,
+}
+
+impl TrackListChange {
+    fn between(old: &[TrackMetadata], new: &[TrackMetadata]) -> Self {
+        let common_prefix = old
+            .iter()
+            .zip(new.iter())
+            .take_while(|(a, b)| a.id == b.id)
+            .count();
+        let same_suffix = |longer: &[TrackMetadata], shorter: &[TrackMetadata]| {
+            longer[common_prefix + 1..]
+                .iter()
+                .zip(shorter[common_prefix..].iter())
+                .all(|(a, b)| a.id == b.id)
+        };
+
+        if old.len() == new.len() && common_prefix == old.len() {
+            Self::Unchanged
+        } else if new.len() == old.len() + 1 && same_suffix(new, old) {
+            Self::Added(common_prefix)
+        } else if old.len() == new.len() + 1 && same_suffix(old, new) {
+            Self::Removed(old[common_prefix].id.clone())
+        } else {
+            Self::Replaced
+        }
+    }
+}
+
+pub struct TrackListState {
+    tracks: Vec<TrackMetadata>,
+    current: Option<String>,
+}
+
+impl TrackListState {
+    pub fn new() -> Self {
+        Self {
+            tracks: vec![],
+            current: None,
+        }
+    }
+
+    pub fn tracks(&self) -> &[TrackMetadata] {
+        &self.tracks[..]
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&TrackMetadata> {
+        self.tracks.iter().find(|t| t.id == id)
+    }
+
+    pub fn set_tracks(
+        &mut self,
+        tracks: Vec<TrackMetadata>,
+        current: Option<String>,
+    ) -> TrackListChange {
+        let change = TrackListChange::between(&self.tracks, &tracks);
+        self.tracks = tracks;
+        self.current = current;
+        change
     }
 }
 
@@ -154,7 +292,7 @@ pub struct MprisState {
 }
 
 impl MprisState {
-    pub fn new() -> Self {
+    pub fn new(volume: f64) -> Self {
         Self {
             status: PlaybackStatus::Stopped,
             loop_status: LoopStatus::None,
@@ -163,7 +301,7 @@ impl MprisState {
             metadata: None,
             has_prev: false,
             has_next: false,
-            volume: 1f64,
+            volume,
         }
     }
 
@@ -245,3 +383,63 @@ impl MprisState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn tracks(ids: &[&str]) -> Vec<TrackMetadata> {
+        ids.iter()
+            .map(|id| TrackMetadata {
+                id: track_path(id),
+                length: 0,
+                artist: vec![],
+                album: String::new(),
+                album_artist: vec![],
+                title: String::new(),
+                track_number: None,
+                disc_number: None,
+                art: None,
+                url: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_track_list_changes() {
+        let mut state = TrackListState::new();
+        assert_eq!(
+            state.set_tracks(tracks(&["a", "b"]), None),
+            TrackListChange::Replaced
+        );
+        assert_eq!(
+            state.set_tracks(tracks(&["a", "b"]), None),
+            TrackListChange::Unchanged
+        );
+        assert_eq!(
+            state.set_tracks(tracks(&["a", "c", "b"]), None),
+            TrackListChange::Added(1)
+        );
+        assert_eq!(
+            state.set_tracks(tracks(&["a", "c"]), None),
+            TrackListChange::Removed(track_path("b"))
+        );
+        assert_eq!(
+            state.set_tracks(tracks(&["c", "a"]), None),
+            TrackListChange::Replaced
+        );
+        assert_eq!(
+            state.set_tracks(tracks(&[]), None),
+            TrackListChange::Replaced
+        );
+    }
+
+    #[test]
+    fn test_track_paths() {
+        assert_eq!(track_id(&track_path("abc")), Some("abc"));
+        assert_eq!(track_id(NO_TRACK), None);
+        assert_eq!(playlist_id(&playlist_path("abc")), Some("abc"));
+        assert_eq!(playlist_id(&track_path("abc")), None);
+    }
+}
@@ -12,9 +12,11 @@ use gio::prelude::*;
 use gio::ApplicationFlags;
 use gio::SimpleAction;
 use gtk::prelude::*;
+use std::rc::Rc;
 
 mod api;
 mod app;
+mod cli;
 mod config;
 mod dbus;
 mod player;
@@ -23,7 +25,7 @@ pub use config::VERSION;
 
 use crate::app::components::expose_widgets;
 use crate::app::dispatch::{spawn_task_handler, DispatchLoop};
-use crate::app::{state::PlaybackAction, App, AppAction, BrowserAction};
+use crate::app::{state::PlaybackAction, ActionDispatcherImpl, App, AppAction, BrowserAction};
 
 fn main() {
     env_logger::init();
@@ -34,7 +36,11 @@ fn main() {
 
     let settings = settings::SpotSettings::new_from_gsettings().unwrap_or_default();
     startup(&settings);
-    let gtk_app = gtk::Application::new(Some(config::APPID), ApplicationFlags::HANDLES_OPEN);
+    let gtk_app = gtk::Application::new(
+        Some(config::APPID),
+        ApplicationFlags::HANDLES_OPEN | ApplicationFlags::HANDLES_COMMAND_LINE,
+    );
+    cli::add_main_options(&gtk_app);
     expose_widgets();
     let builder = gtk::Builder::from_resource("/dev/alextren/Spot/window.ui");
     let window: libadwaita::ApplicationWindow = builder.object("window").unwrap();
@@ -50,15 +56,19 @@ fn main() {
     let sender = dispatch_loop.make_dispatcher();
     register_actions(&gtk_app, sender.clone());
 
-    let app = App::new(
-        settings,
-        builder,
-        sender.clone(),
-        spawn_task_handler(&context),
+    let worker = spawn_task_handler(&context);
+    let app = App::new(settings, builder, sender.clone(), worker.clone());
+
+    let command_line = Rc::new(cli::CommandLineHandler::new(
+        app.model(),
+        Box::new(ActionDispatcherImpl::new(sender.clone(), worker)),
+    ));
+    gtk_app.connect_command_line(
+        clone!(@strong command_line => move |gtk_app, cmdline| command_line.handle(gtk_app, cmdline)),
     );
+
     context.spawn_local(app.attach(dispatch_loop));
 
-    let sender_clone = sender.clone();
     gtk_app.connect_activate(move |gtk_app| {
         debug!("activate");
         if let Some(existing_window) = gtk_app.active_window() {
@@ -66,7 +76,7 @@ fn main() {
         } else {
             window.set_application(Some(gtk_app));
             gtk_app.add_window(&window);
-            sender_clone.unbounded_send(AppAction::Start).unwrap();
+            sender.unbounded_send(AppAction::Start).unwrap();
         }
     });
 
@@ -74,11 +84,7 @@ fn main() {
         gtk_app.activate();
 
         // There should only be one target because %u is used in desktop file
-        let target = &targets[0];
-        let uri = target.uri().to_string();
-        let action = AppAction::OpenURI(uri)
-            .unwrap_or_else(|| AppAction::ShowNotification(gettext("Failed to open link!")));
-        sender.unbounded_send(action).unwrap();
+        command_line.open(&targets[0].uri());
     });
 
     context.invoke_local(move || {
@@ -12,18 +12,24 @@ use crate::app::AppAction;
 mod player;
 pub use player::*;
 
+mod sinks;
+pub use sinks::*;
+
 #[derive(Debug, Clone)]
 pub enum Command {
     PasswordLogin { username: String, password: String },
     TokenLogin { username: String, token: String },
     Logout,
-    PlayerLoad(SpotifyId),
+    // Tracks always start from the beginning, episodes may resume where they were left
+    PlayerLoad { track: SpotifyId, position: u32 },
     PlayerResume,
     PlayerPause,
     PlayerStop,
     PlayerSeek(u32),
     PlayerSetVolume(f64),
     PlayerPreload(SpotifyId),
+    // Plays on another PulseAudio sink (None for the default one), picking up where it was
+    SwitchAudioSink(Option<String>),
     RefreshToken,
     ReloadSettings,
 }
@@ -96,17 +102,32 @@ impl SpotifyPlayerDelegate for AppPlayerDelegate {
             .unwrap();
     }
 
+    fn notify_resume_position(&self, id: String, position: u32) {
+        self.sender
+            .borrow_mut()
+            .unbounded_send(PlaybackAction::SetResumePosition(id, position).into())
+            .unwrap();
+    }
+
     fn preload_next_track(&self) {
         self.sender
             .borrow_mut()
             .unbounded_send(PlaybackAction::Preload.into())
             .unwrap();
     }
+
+    fn notify_volume(&self, volume: f64) {
+        self.sender
+            .borrow_mut()
+            .unbounded_send(PlaybackAction::SetVolume(volume).into())
+            .unwrap();
+    }
 }
 
 #[tokio::main]
 async fn player_main(
     player_settings: SpotifyPlayerSettings,
+    volume: f64,
     appaction_sender: UnboundedSender<AppAction>,
     receiver: UnboundedReceiver<Command>,
 ) {
@@ -114,7 +135,7 @@ async fn player_main(
         .run_until(async move {
             task::spawn_local(async move {
                 let delegate = Rc::new(AppPlayerDelegate::new(appaction_sender.clone()));
-                let player = SpotifyPlayer::new(player_settings, delegate);
+                let player = SpotifyPlayer::new(player_settings, volume, delegate);
                 player.start(receiver).await.unwrap();
             })
             .await
@@ -125,9 +146,10 @@ async fn player_main(
 
 pub fn start_player_service(
     player_settings: SpotifyPlayerSettings,
+    volume: f64,
     appaction_sender: UnboundedSender<AppAction>,
 ) -> UnboundedSender<Command> {
     let (sender, receiver) = unbounded::<Command>();
-    std::thread::spawn(move || player_main(player_settings, appaction_sender, receiver));
+    std::thread::spawn(move || player_main(player_settings, volume, appaction_sender, receiver));
     sender
 }
@@ -5,20 +5,25 @@ use librespot::core::authentication::Credentials;
 use librespot::core::config::SessionConfig;
 use librespot::core::keymaster;
 use librespot::core::session::{Session, SessionError};
+use librespot::core::spotify_id::{SpotifyAudioType, SpotifyId};
 
+use librespot::playback::mixer::alsamixer::AlsaMixer;
 use librespot::playback::mixer::softmixer::SoftMixer;
 use librespot::playback::mixer::{Mixer, MixerConfig};
 use librespot::protocol::authentication::AuthenticationType;
 
 use librespot::playback::audio_backend;
-use librespot::playback::config::{AudioFormat, Bitrate, PlayerConfig, VolumeCtrl};
+use librespot::playback::config::{
+    AudioFormat, Bitrate, NormalisationMethod, NormalisationType, PlayerConfig, VolumeCtrl,
+};
 use librespot::playback::player::{Player, PlayerEvent, PlayerEventChannel};
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::error::Error;
 use std::fmt;
-use std::rc::Rc;
-use std::time::{Duration, SystemTime};
+use std::panic;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant, SystemTime};
 
 use super::Command;
 use crate::app::credentials;
@@ -50,21 +55,86 @@ pub trait SpotifyPlayerDelegate {
     fn refresh_successful(&self, token: String, token_expiry_time: SystemTime);
     fn report_error(&self, error: SpotifyError);
     fn notify_playback_state(&self, position: u32);
+    fn notify_resume_position(&self, id: String, position: u32);
     fn preload_next_track(&self);
+    fn notify_volume(&self, volume: f64);
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AudioBackend {
-    PulseAudio,
+    // The sink to play on, if not the default one
+    PulseAudio(Option<String>),
     Alsa(String),
+    // Raw audio written to a file or a named pipe, or to stdout if there's no path
+    Pipe(String),
+    // Raw audio written to the stdin of a shell command
+    Subprocess(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioMixer {
+    Soft,
+    // Changes the volume on the sound card itself
+    Alsa { control: String, card: u32 },
+}
+
+// How the volume slider maps to the output volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeCurve {
+    Linear,
+    Log,
+    Cubic,
+    Fixed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct SpotifyPlayerSettings {
     pub bitrate: Bitrate,
     pub backend: AudioBackend,
+    // Only used by the pipe and subprocess backends
+    pub format: AudioFormat,
+    pub mixer: AudioMixer,
     pub gapless: bool,
     pub ap_port: Option<u16>,
+    pub normalisation: bool,
+    pub normalisation_type: NormalisationType,
+    // In dB
+    pub normalisation_pregain: f64,
+    // In dBFS, where the limiter starts
+    pub normalisation_threshold: f64,
+    pub normalisation_limiter: bool,
+    pub volume_curve: VolumeCurve,
+    // In dB, ignored by the linear and fixed curves
+    pub volume_range: f64,
+}
+
+impl SpotifyPlayerSettings {
+    // The PulseAudio sink can be switched mid-session, unlike the rest that needs a new player
+    pub fn switched_sink(&self, other: &Self) -> Option<Option<String>> {
+        match (&self.backend, &other.backend) {
+            (AudioBackend::PulseAudio(sink), AudioBackend::PulseAudio(other_sink))
+                if sink != other_sink =>
+            {
+                let same_otherwise = Self {
+                    backend: other.backend.clone(),
+                    ..self.clone()
+                } == *other;
+                Some(other_sink.clone()).filter(|_| same_otherwise)
+            }
+            _ => None,
+        }
+    }
+
+    fn volume_ctrl(&self) -> VolumeCtrl {
+        // A range of 0 dB would make librespot compute a NaN volume
+        let range = self.volume_range.clamp(1.0, 100.0);
+        match self.volume_curve {
+            VolumeCurve::Linear => VolumeCtrl::Linear,
+            VolumeCurve::Log => VolumeCtrl::Log(range),
+            VolumeCurve::Cubic => VolumeCtrl::Cubic(range),
+            VolumeCurve::Fixed => VolumeCtrl::Fixed,
+        }
+    }
 }
 
 impl Default for SpotifyPlayerSettings {
@@ -72,25 +142,47 @@ impl Default for SpotifyPlayerSettings {
         Self {
             bitrate: Bitrate::Bitrate160,
             gapless: true,
-            backend: AudioBackend::PulseAudio,
+            backend: AudioBackend::PulseAudio(None),
+            format: AudioFormat::default(),
+            mixer: AudioMixer::Soft,
             ap_port: None,
+            normalisation: false,
+            normalisation_type: NormalisationType::Track,
+            normalisation_pregain: 0.0,
+            normalisation_threshold: -2.0,
+            normalisation_limiter: true,
+            volume_curve: VolumeCurve::Log,
+            // This value feels reasonable to me. Feel free to change it
+            volume_range: VolumeCtrl::DEFAULT_DB_RANGE / 2.0,
         }
     }
 }
 
 pub struct SpotifyPlayer {
     settings: SpotifyPlayerSettings,
+    // From 0 to 1, kept here as the mixer only exists once logged in
+    volume: f64,
     player: Option<Player>,
-    mixer: Option<Box<dyn Mixer>>,
+    mixer: Option<Rc<dyn Mixer>>,
+    // Last volume of the mixer we know of, anything else was changed from outside of Spot
+    mixer_volume: Rc<Cell<u16>>,
+    progress: Rc<RefCell<PlaybackProgress>>,
     session: Option<Session>,
     delegate: Rc<dyn SpotifyPlayerDelegate>,
 }
 
 impl SpotifyPlayer {
-    pub fn new(settings: SpotifyPlayerSettings, delegate: Rc<dyn SpotifyPlayerDelegate>) -> Self {
+    pub fn new(
+        settings: SpotifyPlayerSettings,
+        volume: f64,
+        delegate: Rc<dyn SpotifyPlayerDelegate>,
+    ) -> Self {
         Self {
             settings,
+            volume,
             mixer: None,
+            mixer_volume: Rc::new(Cell::new(mixer_volume(volume))),
+            progress: Default::default(),
             player: None,
             session: None,
             delegate,
@@ -100,8 +192,10 @@ impl SpotifyPlayer {
     async fn handle(&mut self, action: Command) -> Result<(), SpotifyError> {
         match action {
             Command::PlayerSetVolume(volume) => {
-                if let Some(mixer) = self.mixer.as_mut() {
-                    mixer.set_volume((VolumeCtrl::MAX_VOLUME as f64 * volume) as u16);
+                self.volume = volume;
+                if let Some(mixer) = self.mixer.as_ref() {
+                    mixer.set_volume(mixer_volume(volume));
+                    self.mixer_volume.set(mixer.volume());
                 }
                 Ok(())
             }
@@ -133,11 +227,11 @@ impl SpotifyPlayer {
                     .seek(position);
                 Ok(())
             }
-            Command::PlayerLoad(track) => {
+            Command::PlayerLoad { track, position } => {
                 self.player
                     .as_mut()
                     .ok_or(SpotifyError::PlayerNotReady)?
-                    .load(track, true, 0);
+                    .load(track, true, position);
                 Ok(())
             }
             Command::PlayerPreload(track) => {
@@ -175,8 +269,7 @@ impl SpotifyPlayer {
                 };
                 self.delegate.password_login_successful(credentials);
 
-                let (new_player, channel) = self.create_player(new_session.clone());
-                tokio::task::spawn_local(player_setup_delegate(channel, Rc::clone(&self.delegate)));
+                let new_player = self.create_player(new_session.clone());
                 self.player.replace(new_player);
                 self.session.replace(new_session);
 
@@ -192,8 +285,7 @@ impl SpotifyPlayer {
                 self.delegate
                     .token_login_successful(new_session.username(), token);
 
-                let (new_player, channel) = self.create_player(new_session.clone());
-                tokio::task::spawn_local(player_setup_delegate(channel, Rc::clone(&self.delegate)));
+                let new_player = self.create_player(new_session.clone());
                 self.player.replace(new_player);
                 self.session.replace(new_session);
 
@@ -202,10 +294,25 @@ impl SpotifyPlayer {
             Command::ReloadSettings => {
                 let settings = SpotSettings::new_from_gsettings().unwrap_or_default();
                 self.settings = settings.player_settings;
+                // The volume control might have changed, the new mixer picks up the volume we kept
+                self.mixer = None;
+
+                let session = self.session.clone().ok_or(SpotifyError::PlayerNotReady)?;
+                let new_player = self.create_player(session);
+                self.player.replace(new_player);
 
-                let session = self.session.take().ok_or(SpotifyError::PlayerNotReady)?;
-                let (new_player, channel) = self.create_player(session);
-                tokio::task::spawn_local(player_setup_delegate(channel, Rc::clone(&self.delegate)));
+                Ok(())
+            }
+            Command::SwitchAudioSink(sink) => {
+                self.settings.backend = AudioBackend::PulseAudio(sink);
+
+                // Only the player knows which sink it writes to, so it is replaced as a whole
+                let session = self.session.clone().ok_or(SpotifyError::PlayerNotReady)?;
+                let progress = self.progress.borrow().position();
+                let mut new_player = self.create_player(session);
+                if let Some((track, position, playing)) = progress {
+                    new_player.load(track, playing, position);
+                }
                 self.player.replace(new_player);
 
                 Ok(())
@@ -213,42 +320,112 @@ impl SpotifyPlayer {
         }
     }
 
-    fn create_player(&mut self, session: Session) -> (Player, PlayerEventChannel) {
+    fn create_player(&mut self, session: Session) -> Player {
         let backend = self.settings.backend.clone();
+        let format = self.settings.format;
 
+        let normalisation_method = if self.settings.normalisation_limiter {
+            NormalisationMethod::Dynamic
+        } else {
+            NormalisationMethod::Basic
+        };
         let player_config = PlayerConfig {
             gapless: self.settings.gapless,
             bitrate: self.settings.bitrate,
+            normalisation: self.settings.normalisation,
+            normalisation_type: self.settings.normalisation_type,
+            normalisation_method,
+            normalisation_pregain_db: self.settings.normalisation_pregain,
+            normalisation_threshold_dbfs: self.settings.normalisation_threshold,
             ..Default::default()
         };
         info!("bitrate: {:?}", &player_config.bitrate);
+        info!(
+            "normalisation: {} ({:?})",
+            player_config.normalisation, &player_config.normalisation_type
+        );
 
-        let soft_volume = self
-            .mixer
-            .get_or_insert_with(|| {
-                let mix = Box::new(SoftMixer::open(MixerConfig {
-                    // This value feels reasonable to me. Feel free to change it
-                    volume_ctrl: VolumeCtrl::Log(VolumeCtrl::DEFAULT_DB_RANGE / 2.0),
-                    ..Default::default()
-                }));
-                // TODO: Should read volume from somewhere instead of hard coding.
-                // Sets volume to 100%
-                mix.set_volume(VolumeCtrl::MAX_VOLUME);
-                mix
-            })
-            .get_soft_volume();
-        Player::new(player_config, session, soft_volume, move || match backend {
-            AudioBackend::PulseAudio => {
-                info!("using pulseaudio");
-                let backend = audio_backend::find(Some("pulseaudio".to_string())).unwrap();
-                backend(None, AudioFormat::default())
-            }
-            AudioBackend::Alsa(device) => {
-                info!("using alsa ({})", &device);
-                let backend = audio_backend::find(Some("alsa".to_string())).unwrap();
-                backend(Some(device), AudioFormat::default())
+        if self.mixer.is_none() {
+            self.mixer = Some(self.create_mixer());
+        }
+        let soft_volume = self.mixer.as_ref().unwrap().get_soft_volume();
+        let (player, channel) =
+            Player::new(player_config, session, soft_volume, move || match backend {
+                AudioBackend::PulseAudio(sink) => {
+                    info!("using pulseaudio ({:?})", &sink);
+                    let backend = audio_backend::find(Some("pulseaudio".to_string())).unwrap();
+                    backend(sink, AudioFormat::default())
+                }
+                AudioBackend::Alsa(device) => {
+                    info!("using alsa ({})", &device);
+                    let backend = audio_backend::find(Some("alsa".to_string())).unwrap();
+                    backend(Some(device), AudioFormat::default())
+                }
+                AudioBackend::Pipe(path) => {
+                    info!("using pipe ({}, {:?})", &path, format);
+                    let backend = audio_backend::find(Some("pipe".to_string())).unwrap();
+                    let path = if path.is_empty() { None } else { Some(path) };
+                    backend(path, format)
+                }
+                AudioBackend::Subprocess(command) if command.is_empty() => {
+                    warn!("no command for the subprocess backend, using pulseaudio");
+                    let backend = audio_backend::find(Some("pulseaudio".to_string())).unwrap();
+                    backend(None, AudioFormat::default())
+                }
+                AudioBackend::Subprocess(command) => {
+                    info!("using subprocess ({}, {:?})", &command, format);
+                    let backend = audio_backend::find(Some("subprocess".to_string())).unwrap();
+                    backend(Some(command), format)
+                }
+            });
+
+        tokio::task::spawn_local(player_setup_delegate(
+            channel,
+            Rc::clone(&self.delegate),
+            Rc::clone(&self.progress),
+        ));
+        player
+    }
+
+    fn create_mixer(&self) -> Rc<dyn Mixer> {
+        let volume_ctrl = self.settings.volume_ctrl();
+        let mixer: Rc<dyn Mixer> = match &self.settings.mixer {
+            AudioMixer::Soft => Rc::new(SoftMixer::open(MixerConfig {
+                volume_ctrl,
+                ..Default::default()
+            })),
+            AudioMixer::Alsa { control, card } => {
+                info!("using alsa mixer ({} on card {})", control, card);
+                let config = MixerConfig {
+                    device: format!("hw:{}", card),
+                    control: control.clone(),
+                    index: 0,
+                    volume_ctrl,
+                };
+                // librespot panics when the control can't be found
+                match panic::catch_unwind(|| AlsaMixer::open(config)) {
+                    Ok(mixer) => Rc::new(mixer),
+                    Err(_) => {
+                        warn!("could not open alsa mixer, falling back to soft mixer");
+                        Rc::new(SoftMixer::open(MixerConfig {
+                            volume_ctrl,
+                            ..Default::default()
+                        }))
+                    }
+                }
             }
-        })
+        };
+
+        mixer.set_volume(mixer_volume(self.volume));
+        self.mixer_volume.set(mixer.volume());
+        if let AudioMixer::Alsa { .. } = self.settings.mixer {
+            tokio::task::spawn_local(watch_mixer_volume(
+                Rc::downgrade(&mixer),
+                Rc::clone(&self.mixer_volume),
+                Rc::clone(&self.delegate),
+            ));
+        }
+        mixer
     }
 
     pub async fn start(self, receiver: UnboundedReceiver<Command>) -> Result<(), ()> {
@@ -266,6 +443,30 @@ impl SpotifyPlayer {
     }
 }
 
+// librespot's mixers go from 0 to MAX_VOLUME
+fn mixer_volume(volume: f64) -> u16 {
+    (VolumeCtrl::MAX_VOLUME as f64 * volume.clamp(0.0, 1.0)) as u16
+}
+
+// The hardware volume can be changed by other programs (or knobs), we poll it until the mixer goes away
+async fn watch_mixer_volume(
+    mixer: Weak<dyn Mixer>,
+    known_volume: Rc<Cell<u16>>,
+    delegate: Rc<dyn SpotifyPlayerDelegate>,
+) {
+    loop {
+        async_std::task::sleep(Duration::from_secs(1)).await;
+        let volume = match mixer.upgrade() {
+            Some(mixer) => mixer.volume(),
+            None => break,
+        };
+        if volume != known_volume.get() {
+            known_volume.set(volume);
+            delegate.notify_volume(volume as f64 / VolumeCtrl::MAX_VOLUME as f64);
+        }
+    }
+}
+
 const CLIENT_ID: &str = "782ae96ea60f4cdf986a766049607005";
 
 const SCOPES: &str = "user-read-private,\
@@ -275,6 +476,9 @@ user-library-read,\
 user-library-modify,\
 user-top-read,\
 user-read-recently-played,\
+user-follow-read,\
+user-follow-modify,\
+user-read-playback-position,\
 playlist-modify-public,\
 playlist-modify-private,\
 streaming";
@@ -336,16 +540,107 @@ async fn create_session(
     }
 }
 
+// Keeps track of where the episode being played is at, so that it can be resumed later
+#[derive(Default)]
+struct EpisodeProgress {
+    // Last known position, and since when it has been playing
+    current: Option<(SpotifyId, u32, Option<Instant>)>,
+}
+
+impl EpisodeProgress {
+    fn update(&mut self, track_id: SpotifyId, position_ms: u32, playing: bool) {
+        self.current = if track_id.audio_type == SpotifyAudioType::Podcast {
+            let since = if playing { Some(Instant::now()) } else { None };
+            Some((track_id, position_ms, since))
+        } else {
+            None
+        };
+    }
+
+    fn position(&self) -> Option<(String, u32)> {
+        let (track_id, position_ms, since) = self.current.as_ref()?;
+        let elapsed = since.map(|i| i.elapsed().as_millis() as u32).unwrap_or(0);
+        Some((track_id.to_base62(), position_ms + elapsed))
+    }
+
+    fn take(&mut self) -> Option<(String, u32)> {
+        let position = self.position();
+        self.current = None;
+        position
+    }
+}
+
+// Where any track is at, so that a new player can pick up from there
+#[derive(Default)]
+struct PlaybackProgress {
+    current: Option<(SpotifyId, u32, Option<Instant>)>,
+}
+
+impl PlaybackProgress {
+    fn update(&mut self, track_id: SpotifyId, position_ms: u32, playing: bool) {
+        let since = if playing { Some(Instant::now()) } else { None };
+        self.current = Some((track_id, position_ms, since));
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+    }
+
+    // The track, its position and whether it is playing
+    fn position(&self) -> Option<(SpotifyId, u32, bool)> {
+        let (track_id, position_ms, since) = self.current.as_ref()?;
+        let elapsed = since.map(|i| i.elapsed().as_millis() as u32).unwrap_or(0);
+        Some((*track_id, position_ms + elapsed, since.is_some()))
+    }
+}
+
 async fn player_setup_delegate(
     mut channel: PlayerEventChannel,
     delegate: Rc<dyn SpotifyPlayerDelegate>,
+    progress: Rc<RefCell<PlaybackProgress>>,
 ) {
+    let mut episode = EpisodeProgress::default();
     while let Some(event) = channel.recv().await {
         match event {
-            PlayerEvent::EndOfTrack { .. } | PlayerEvent::Stopped { .. } => {
+            PlayerEvent::EndOfTrack { track_id, .. } => {
+                progress.borrow_mut().clear();
+                // Fully played, next time it starts over
+                if track_id.audio_type == SpotifyAudioType::Podcast {
+                    episode.take();
+                    delegate.notify_resume_position(track_id.to_base62(), 0);
+                }
                 delegate.end_of_track_reached();
             }
-            PlayerEvent::Playing { position_ms, .. } => {
+            PlayerEvent::Stopped { .. } => {
+                progress.borrow_mut().clear();
+                if let Some((id, position)) = episode.take() {
+                    delegate.notify_resume_position(id, position);
+                }
+                delegate.end_of_track_reached();
+            }
+            PlayerEvent::Loading { .. } => {
+                if let Some((id, position)) = episode.take() {
+                    delegate.notify_resume_position(id, position);
+                }
+            }
+            PlayerEvent::Paused {
+                track_id,
+                position_ms,
+                ..
+            } => {
+                progress.borrow_mut().update(track_id, position_ms, false);
+                episode.update(track_id, position_ms, false);
+                if let Some((id, position)) = episode.position() {
+                    delegate.notify_resume_position(id, position);
+                }
+            }
+            PlayerEvent::Playing {
+                track_id,
+                position_ms,
+                ..
+            } => {
+                progress.borrow_mut().update(track_id, position_ms, true);
+                episode.update(track_id, position_ms, true);
                 delegate.notify_playback_state(position_ms);
             }
             PlayerEvent::TimeToPreloadNextTrack { .. } => {
@@ -356,3 +651,46 @@ async fn player_setup_delegate(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn with_sink(sink: Option<&str>) -> SpotifyPlayerSettings {
+        SpotifyPlayerSettings {
+            backend: AudioBackend::PulseAudio(sink.map(String::from)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_switched_sink() {
+        let settings = with_sink(None);
+        assert_eq!(
+            settings.switched_sink(&with_sink(Some("headphones"))),
+            Some(Some("headphones".to_string()))
+        );
+        assert_eq!(
+            with_sink(Some("headphones")).switched_sink(&settings),
+            Some(None)
+        );
+        assert_eq!(settings.switched_sink(&with_sink(None)), None);
+    }
+
+    #[test]
+    fn test_switched_sink_and_other_settings() {
+        let settings = with_sink(None);
+        let other = SpotifyPlayerSettings {
+            gapless: false,
+            ..with_sink(Some("headphones"))
+        };
+        assert_eq!(settings.switched_sink(&other), None);
+
+        let other = SpotifyPlayerSettings {
+            backend: AudioBackend::Alsa("default".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(settings.switched_sink(&other), None);
+    }
+}
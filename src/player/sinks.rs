@@ -0,0 +1,65 @@
+use libpulse_binding::callbacks::ListResult;
+use libpulse_binding::context::{Context, FlagSet, State};
+use libpulse_binding::mainloop::standard::{IterateResult, Mainloop};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+// An output of the PulseAudio (or PipeWire) server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioSink {
+    pub name: String,
+    pub description: String,
+}
+
+// Blocks until the server answers, which is quick as it runs locally
+pub fn list_pulseaudio_sinks() -> Vec<AudioSink> {
+    query_sinks().unwrap_or_else(|| {
+        warn!("could not list pulseaudio sinks");
+        vec![]
+    })
+}
+
+fn iterate(mainloop: &mut Mainloop) -> Option<()> {
+    match mainloop.iterate(true) {
+        IterateResult::Success(_) => Some(()),
+        IterateResult::Quit(_) | IterateResult::Err(_) => None,
+    }
+}
+
+fn query_sinks() -> Option<Vec<AudioSink>> {
+    let mut mainloop = Mainloop::new()?;
+    let mut context = Context::new(&mainloop, "Spot")?;
+    context.connect(None, FlagSet::NOFLAGS, None).ok()?;
+
+    loop {
+        iterate(&mut mainloop)?;
+        match context.get_state() {
+            State::Ready => break,
+            State::Failed | State::Terminated => return None,
+            _ => {}
+        }
+    }
+
+    let sinks = Rc::new(RefCell::new(vec![]));
+    let done = Rc::new(Cell::new(false));
+    let _operation = context.introspect().get_sink_info_list(
+        clone!(@strong sinks, @strong done => move |result| match result {
+            ListResult::Item(info) => {
+                let name = info.name.as_deref().unwrap_or_default().to_string();
+                let description = info.description.as_deref().unwrap_or(&name).to_string();
+                sinks.borrow_mut().push(AudioSink { name, description });
+            }
+            ListResult::End | ListResult::Error => done.set(true),
+        }),
+    );
+
+    while !done.get() {
+        if iterate(&mut mainloop).is_none() {
+            break;
+        }
+    }
+    context.disconnect();
+
+    Some(sinks.take())
+}
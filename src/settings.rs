@@ -1,7 +1,9 @@
-use crate::player::{AudioBackend, SpotifyPlayerSettings};
+use crate::player::{AudioBackend, AudioMixer, SpotifyPlayerSettings, VolumeCurve};
 use gio::prelude::SettingsExt;
+use glib::ToVariant;
 use libadwaita::ColorScheme;
-use librespot::playback::config::Bitrate;
+use librespot::playback::config::{AudioFormat, Bitrate, NormalisationType};
+use std::collections::HashMap;
 
 const SETTINGS: &str = "dev.alextren.Spot";
 
@@ -35,6 +37,28 @@ impl WindowGeometry {
     }
 }
 
+// Saved every time it changes, so that the next launch starts where this one left off
+pub fn save_volume(volume: f64) -> Option<()> {
+    let settings = gio::Settings::new(SETTINGS);
+    settings.set_double("volume", volume).ok()
+}
+
+// Saved whenever an episode is paused, stopped or played to the end
+pub fn save_resume_positions(positions: &HashMap<String, u32>) -> Option<()> {
+    let settings = gio::Settings::new(SETTINGS);
+    settings
+        .set_value("episode-resume-positions", &positions.to_variant())
+        .ok()
+}
+
+// The sink can also be picked from the playback bar, outside of the settings window
+pub fn save_audio_sink(sink: Option<&str>) -> Option<()> {
+    let settings = gio::Settings::new(SETTINGS);
+    settings
+        .set_string("pulseaudio-sink", sink.unwrap_or_default())
+        .ok()
+}
+
 impl SpotifyPlayerSettings {
     pub fn new_from_gsettings() -> Option<Self> {
         let settings = gio::Settings::new(SETTINGS);
@@ -45,13 +69,51 @@ impl SpotifyPlayerSettings {
             _ => None,
         }?;
         let backend = match settings.enum_("audio-backend") {
-            0 => Some(AudioBackend::PulseAudio),
+            0 => Some(AudioBackend::PulseAudio(
+                Some(settings.string("pulseaudio-sink").as_str().to_string())
+                    .filter(|sink| !sink.is_empty()),
+            )),
             1 => Some(AudioBackend::Alsa(
                 settings.string("alsa-device").as_str().to_string(),
             )),
+            2 => Some(AudioBackend::Pipe(
+                settings.string("pipe-path").as_str().to_string(),
+            )),
+            3 => Some(AudioBackend::Subprocess(
+                settings.string("subprocess-command").as_str().to_string(),
+            )),
+            _ => None,
+        }?;
+        let format = match settings.enum_("sample-format") {
+            0 => Some(AudioFormat::F64),
+            1 => Some(AudioFormat::F32),
+            2 => Some(AudioFormat::S32),
+            3 => Some(AudioFormat::S24),
+            4 => Some(AudioFormat::S24_3),
+            5 => Some(AudioFormat::S16),
+            _ => None,
+        }?;
+        let mixer = match settings.enum_("mixer") {
+            0 => Some(AudioMixer::Soft),
+            1 => Some(AudioMixer::Alsa {
+                control: settings.string("alsa-mixer-control").as_str().to_string(),
+                card: settings.uint("alsa-mixer-card"),
+            }),
             _ => None,
         }?;
         let gapless = settings.boolean("gapless-playback");
+        let normalisation_type = match settings.enum_("normalisation-type") {
+            0 => Some(NormalisationType::Track),
+            1 => Some(NormalisationType::Album),
+            _ => None,
+        }?;
+        let volume_curve = match settings.enum_("volume-control") {
+            0 => Some(VolumeCurve::Linear),
+            1 => Some(VolumeCurve::Log),
+            2 => Some(VolumeCurve::Cubic),
+            3 => Some(VolumeCurve::Fixed),
+            _ => None,
+        }?;
 
         let ap_port_val = settings.uint("ap-port");
         if ap_port_val > 65535 {
@@ -69,8 +131,17 @@ impl SpotifyPlayerSettings {
         Some(Self {
             bitrate,
             backend,
+            format,
+            mixer,
             gapless,
             ap_port,
+            normalisation: settings.boolean("normalisation"),
+            normalisation_type,
+            normalisation_pregain: settings.double("normalisation-pregain"),
+            normalisation_threshold: settings.double("normalisation-threshold"),
+            normalisation_limiter: settings.boolean("normalisation-limiter"),
+            volume_curve,
+            volume_range: settings.double("volume-range"),
         })
     }
 }
@@ -79,6 +150,9 @@ impl SpotifyPlayerSettings {
 pub struct SpotSettings {
     pub theme_preference: ColorScheme,
     pub player_settings: SpotifyPlayerSettings,
+    pub autoplay: bool,
+    pub volume: f64,
+    pub resume_positions: HashMap<String, u32>,
     pub window: WindowGeometry,
 }
 
@@ -94,6 +168,12 @@ impl SpotSettings {
         Some(Self {
             theme_preference,
             player_settings: SpotifyPlayerSettings::new_from_gsettings()?,
+            autoplay: settings.boolean("autoplay"),
+            volume: settings.double("volume"),
+            resume_positions: settings
+                .value("episode-resume-positions")
+                .get()
+                .unwrap_or_default(),
             window: WindowGeometry::new_from_gsettings(),
         })
     }
@@ -104,6 +184,9 @@ impl Default for SpotSettings {
         Self {
             theme_preference: ColorScheme::PreferDark,
             player_settings: Default::default(),
+            autoplay: false,
+            volume: 1.0,
+            resume_positions: HashMap::new(),
             window: Default::default(),
         }
     }